@@ -137,8 +137,14 @@ impl PathApproxParams {
 
         // We can't generate a point exactly at the boundaries of the range as
         // part of the approximation. Make sure we stay inside the range.
-        let min = min.floor() + 1.;
-        let max = max.ceil() - 1.;
+        //
+        // Boundaries are often computed (for example, as the intersection of
+        // two paths), and might miss a point of the approximation only due to
+        // floating point inaccuracy. Those points would be too close to the
+        // boundary, so treat them like they were exactly on it.
+        let epsilon = 1e-9;
+        let min = (min + epsilon).floor() + 1.;
+        let max = (max - epsilon).ceil() - 1.;
 
         let [start, end] = match direction {
             Sign::Negative => [max, min],
@@ -1,29 +1,30 @@
-use fj_math::{Aabb, Vector};
+use fj_math::{Aabb, Scalar, Vector};
 
 use crate::{geometry::SurfacePath, objects::HalfEdge};
 
 impl super::BoundingVolume<2> for HalfEdge {
     fn aabb(&self) -> Option<Aabb<2>> {
-        match self.path() {
-            SurfacePath::Circle(circle) => {
-                // Just calculate the AABB of the whole circle. This is not the
-                // most precise, but it should do for now.
-
-                let center_to_min_max =
-                    Vector::from([circle.radius(), circle.radius()]);
+        super::aabb_from_extents(|direction| Some(extent(self, direction)))
+    }
+}
 
-                Some(Aabb {
-                    min: circle.center() - center_to_min_max,
-                    max: circle.center() + center_to_min_max,
-                })
-            }
-            SurfacePath::Line(_) => {
-                let points = self.boundary().inner.map(|point_curve| {
-                    self.path().point_from_path_coords(point_curve)
-                });
+/// Compute the extent of a half-edge along a direction, in surface coordinates
+fn extent(half_edge: &HalfEdge, direction: Vector<2>) -> [Scalar; 2] {
+    let range = half_edge.boundary().inner.map(|point| point.t);
 
-                Some(Aabb::<2>::from_points(points))
-            }
+    match half_edge.path() {
+        SurfacePath::Circle(circle) => super::arc_extent(
+            circle.center(),
+            [circle.a(), circle.b()],
+            range,
+            direction,
+        ),
+        SurfacePath::Line(line) => {
+            let mut extent = range.map(|t| {
+                line.point_from_line_coords([t]).coords.dot(&direction)
+            });
+            extent.sort();
+            extent
         }
     }
 }
@@ -1,30 +1,95 @@
-use fj_math::Aabb;
+use fj_math::{Aabb, Obb, Scalar, Vector};
 
-use crate::{geometry::GlobalPath, objects::Face};
+use crate::{
+    algorithms::mass_properties::MassProperties,
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{Face, HalfEdge},
+};
+
+use super::BoundingVolume;
 
 impl super::BoundingVolume<3> for Face {
     fn aabb(&self) -> Option<Aabb<3>> {
-        self.region().exterior().aabb().map(|aabb2| {
-            let surface = self.surface().geometry();
-
-            match surface.u {
-                GlobalPath::Circle(circle) => {
-                    // This is not the most precise way to calculate the AABB,
-                    // doing it for the whole circle, but it should do.
-
-                    let aabb_bottom = circle.aabb();
-                    let aabb_top = Aabb {
-                        min: aabb_bottom.min + surface.v,
-                        max: aabb_bottom.max + surface.v,
-                    };
-
-                    aabb_bottom.merged(&aabb_top)
-                }
-                GlobalPath::Line(_) => Aabb {
-                    min: surface.point_from_surface_coords(aabb2.min),
-                    max: surface.point_from_surface_coords(aabb2.max),
-                },
-            }
+        super::aabb_from_extents(|direction| extent(self, direction))
+    }
+}
+
+impl super::OrientedBoundingVolume for Face {
+    fn obb(&self) -> Option<Obb> {
+        let inertia = self.mass_properties().inertia;
+        super::obb_from_extents(inertia, |direction| extent(self, direction))
+    }
+}
+
+/// Compute the extent of a face along a direction
+///
+/// The extremes of a face are always on its boundary, so only the half-edges of
+/// its exterior cycle need to be considered.
+pub(super) fn extent(face: &Face, direction: Vector<3>) -> Option<[Scalar; 2]> {
+    let surface = face.surface().geometry();
+
+    face.region()
+        .exterior()
+        .half_edges()
+        .iter()
+        .map(|half_edge| extent_of_half_edge(half_edge, &surface, direction))
+        .reduce(|[min_a, max_a], [min_b, max_b]| {
+            [min_a.min(min_b), max_a.max(max_b)]
         })
+}
+
+fn extent_of_half_edge(
+    half_edge: &HalfEdge,
+    surface: &SurfaceGeometry,
+    direction: Vector<3>,
+) -> [Scalar; 2] {
+    let range = half_edge.boundary().inner.map(|point| point.t);
+
+    match (surface.u, half_edge.path()) {
+        (GlobalPath::Line(_), SurfacePath::Circle(circle)) => {
+            // A circle on a plane is still a circle, or at least an ellipse, in
+            // global coordinates.
+            let center = surface.point_from_surface_coords(circle.center());
+            let [a, b] = [circle.a(), circle.b()]
+                .map(|vector| surface.vector_from_surface_coords(vector));
+
+            super::arc_extent(center, [a, b], range, direction)
+        }
+        (GlobalPath::Line(_), SurfacePath::Line(line)) => {
+            let mut extent = range.map(|t| {
+                surface
+                    .point_from_surface_coords(line.point_from_line_coords([t]))
+                    .coords
+                    .dot(&direction)
+            });
+            extent.sort();
+            extent
+        }
+        (GlobalPath::Circle(circle), _) => {
+            // The surface is the circle, swept along its v-axis. Any point on
+            // the half-edge is a point on the circle, plus a multiple of that
+            // v-axis. So the extent of the half-edge is within the sum of the
+            // extents of both.
+            //
+            // This is exact for half-edges that run along the circle or along
+            // the v-axis, which covers all faces created by sweeping.
+            let aabb = half_edge
+                .aabb()
+                .expect("`HalfEdge` can always compute AABB");
+
+            let [min_circle, max_circle] = super::arc_extent(
+                circle.center(),
+                [circle.a(), circle.b()],
+                [aabb.min.u, aabb.max.u],
+                direction,
+            );
+
+            let mut extent_v =
+                [aabb.min.v, aabb.max.v].map(|v| surface.v.dot(&direction) * v);
+            extent_v.sort();
+            let [min_v, max_v] = extent_v;
+
+            [min_circle + min_v, max_circle + max_v]
+        }
     }
 }
@@ -6,7 +6,7 @@ mod face;
 mod shell;
 mod solid;
 
-use fj_math::Aabb;
+use fj_math::{Aabb, Obb, Point, Scalar, Vector};
 
 /// Compute a bounding volume for an object
 pub trait BoundingVolume<const D: usize> {
@@ -15,3 +15,222 @@ pub trait BoundingVolume<const D: usize> {
     /// Return `None`, if no AABB can be computed (if the object is empty).
     fn aabb(&self) -> Option<Aabb<D>>;
 }
+
+/// Compute an oriented bounding volume for an object
+pub trait OrientedBoundingVolume {
+    /// Compute an oriented bounding box (OBB)
+    ///
+    /// The OBB is aligned to the principal axes of the object, as computed by
+    /// [`MassProperties`]. This makes it a tight fit for most objects, but it
+    /// is not guaranteed to be the smallest possible OBB.
+    ///
+    /// Return `None`, if no OBB can be computed (if the object is empty).
+    ///
+    /// [`MassProperties`]: crate::algorithms::mass_properties::MassProperties
+    fn obb(&self) -> Option<Obb>;
+}
+
+/// Compute the extent of an arc along a direction
+///
+/// The arc is defined by `center`, `a`, and `b`, like a [`Circle`], but `a` and
+/// `b` don't need to be of equal length or perpendicular. This also covers
+/// circles that have been transformed into ellipses.
+///
+/// Returns the minimum and maximum of the dot product between `direction` and
+/// all points of the arc.
+///
+/// [`Circle`]: fj_math::Circle
+fn arc_extent<const D: usize>(
+    center: Point<D>,
+    [a, b]: [Vector<D>; 2],
+    range: [Scalar; 2],
+    direction: Vector<D>,
+) -> [Scalar; 2] {
+    let [c, a, b] = [center.coords, a, b].map(|vector| vector.dot(&direction));
+    let value = |t: Scalar| {
+        let (sin, cos) = t.sin_cos();
+        c + a * cos + b * sin
+    };
+
+    let [start, end] = range;
+    let [min_t, max_t] = if start < end {
+        [start, end]
+    } else {
+        [end, start]
+    };
+
+    let mut extent = [value(start), value(end)];
+    extent.sort();
+
+    // The arc reaches its extreme values, wherever its derivative is zero.
+    // That's the case every half-turn, starting from here.
+    let first_extreme = b.atan2(a);
+    let num_half_turns = ((min_t - first_extreme) / Scalar::PI).ceil();
+
+    let mut t = first_extreme + Scalar::PI * num_half_turns;
+    while t <= max_t {
+        let value = value(t);
+        extent = [extent[0].min(value), extent[1].max(value)];
+
+        t += Scalar::PI;
+    }
+
+    extent
+}
+
+/// Compute an AABB from the extents of an object along each axis
+fn aabb_from_extents<const D: usize>(
+    extent: impl Fn(Vector<D>) -> Option<[Scalar; 2]>,
+) -> Option<Aabb<D>> {
+    let mut min = [Scalar::ZERO; D];
+    let mut max = [Scalar::ZERO; D];
+
+    for i in 0..D {
+        let mut axis = [Scalar::ZERO; D];
+        axis[i] = Scalar::ONE;
+
+        [min[i], max[i]] = extent(Vector::from(axis))?;
+    }
+
+    Some(Aabb {
+        min: Point::from(min),
+        max: Point::from(max),
+    })
+}
+
+/// Compute an OBB from the principal axes and the extents of an object
+fn obb_from_extents(
+    inertia: [[Scalar; 3]; 3],
+    extent: impl Fn(Vector<3>) -> Option<[Scalar; 2]>,
+) -> Option<Obb> {
+    let axes = principal_axes(inertia);
+
+    let mut center = Point::origin();
+    let mut half_extents = [Scalar::ZERO; 3];
+
+    for (axis, half_extent) in axes.iter().zip(&mut half_extents) {
+        let [min, max] = extent(*axis)?;
+
+        center += *axis * ((min + max) / 2.);
+        *half_extent = (max - min) / 2.;
+    }
+
+    Some(Obb {
+        center,
+        axes,
+        half_extents,
+    })
+}
+
+/// Compute the eigenvectors of a symmetric matrix
+///
+/// Uses the Jacobi eigenvalue algorithm, which repeatedly rotates the matrix to
+/// eliminate its largest off-diagonal element. The accumulated rotation is made
+/// up of the eigenvectors.
+fn principal_axes(matrix: [[Scalar; 3]; 3]) -> [Vector<3>; 3] {
+    let mut matrix = matrix.map(|row| row.map(Scalar::into_f64));
+    let mut rotation = [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+
+    let scale = matrix
+        .iter()
+        .flatten()
+        .fold(0., |max, value: &f64| f64::max(max, value.abs()));
+
+    for _ in 0..64 {
+        let [p, q] = [[0, 1], [0, 2], [1, 2]]
+            .into_iter()
+            .max_by(|[a, b], [c, d]| {
+                matrix[*a][*b].abs().total_cmp(&matrix[*c][*d].abs())
+            })
+            .expect("Array is not empty");
+
+        if matrix[p][q].abs() <= scale * f64::EPSILON {
+            break;
+        }
+
+        let theta = (matrix[q][q] - matrix[p][p]) / (2. * matrix[p][q]);
+        let t = theta.signum() / (theta.abs() + (theta * theta + 1.).sqrt());
+        let cos = 1. / (t * t + 1.).sqrt();
+        let sin = t * cos;
+
+        for row in matrix.iter_mut().chain(rotation.iter_mut()) {
+            let [a, b] = [row[p], row[q]];
+            row[p] = cos * a - sin * b;
+            row[q] = sin * a + cos * b;
+        }
+        for k in 0..3 {
+            let [a, b] = [matrix[p][k], matrix[q][k]];
+            matrix[p][k] = cos * a - sin * b;
+            matrix[q][k] = sin * a + cos * b;
+        }
+    }
+
+    [0, 1, 2].map(|j| Vector::from([0, 1, 2].map(|i| rotation[i][j])))
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Aabb, Point, Scalar};
+
+    use crate::{
+        algorithms::bounding_volume::{BoundingVolume, OrientedBoundingVolume},
+        objects::{Region, Sketch, Solid},
+        operations::{
+            build::{BuildRegion, BuildSketch, BuildSolid, Tetrahedron},
+            sweep::SweepSketch,
+            update::UpdateSketch,
+        },
+        Instance,
+    };
+
+    #[test]
+    fn aabb_of_cylinder() {
+        let mut core = Instance::new();
+
+        let bottom_surface = core.services.objects.surfaces.xy_plane();
+        let solid = Sketch::empty()
+            .add_regions(
+                [Region::circle(Point::from([1., 2.]), 1., &mut core)],
+                &mut core,
+            )
+            .sweep_sketch(bottom_surface, [0., 0., 2.], &mut core);
+
+        let aabb = solid.aabb().unwrap();
+        let expected = Aabb {
+            min: Point::from([0., 1., 0.]),
+            max: Point::from([2., 3., 2.]),
+        };
+
+        for (a, b) in [aabb.min, aabb.max]
+            .into_iter()
+            .zip([expected.min, expected.max])
+        {
+            assert!(a.distance_to(&b) < Scalar::from_f64(1e-12));
+        }
+    }
+
+    #[test]
+    fn obb_of_tetrahedron() {
+        let mut core = Instance::new();
+
+        // A long tetrahedron that is not aligned to any axis.
+        let points =
+            [[0., 0., 0.], [0., 1., 0.], [1., 0., 0.], [10., 10., 10.]];
+        let Tetrahedron { solid, .. } = Solid::tetrahedron(points, &mut core);
+
+        let obb = solid.obb().unwrap();
+        let aabb = solid.aabb().unwrap();
+
+        for point in points {
+            let offset = Point::from(point) - obb.center;
+            for (axis, half_extent) in obb.axes.iter().zip(obb.half_extents) {
+                let distance = offset.dot(axis).abs();
+                assert!(distance <= half_extent + Scalar::from_f64(1e-12));
+            }
+        }
+
+        let [x, y, z] = aabb.size().components;
+        let aabb_volume = x * y * z;
+        assert!(obb.volume() < aabb_volume);
+    }
+}
@@ -1,19 +1,30 @@
-use fj_math::Aabb;
+use fj_math::{Aabb, Obb, Scalar, Vector};
 
-use crate::objects::Shell;
+use crate::{algorithms::mass_properties::MassProperties, objects::Shell};
 
 impl super::BoundingVolume<3> for Shell {
     fn aabb(&self) -> Option<Aabb<3>> {
-        let mut aabb: Option<Aabb<3>> = None;
-
-        for face in self.faces() {
-            let new_aabb = face.aabb();
-            aabb = aabb.map_or(new_aabb, |aabb| match new_aabb {
-                Some(new_aabb) => Some(aabb.merged(&new_aabb)),
-                None => Some(aabb),
-            });
-        }
+        super::aabb_from_extents(|direction| extent(self, direction))
+    }
+}
 
-        aabb
+impl super::OrientedBoundingVolume for Shell {
+    fn obb(&self) -> Option<Obb> {
+        let inertia = self.mass_properties().inertia;
+        super::obb_from_extents(inertia, |direction| extent(self, direction))
     }
 }
+
+/// Compute the extent of a shell along a direction
+pub(super) fn extent(
+    shell: &Shell,
+    direction: Vector<3>,
+) -> Option<[Scalar; 2]> {
+    shell
+        .faces()
+        .iter()
+        .filter_map(|face| super::face::extent(face, direction))
+        .reduce(|[min_a, max_a], [min_b, max_b]| {
+            [min_a.min(min_b), max_a.max(max_b)]
+        })
+}
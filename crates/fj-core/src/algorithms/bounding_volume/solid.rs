@@ -1,19 +1,26 @@
-use fj_math::Aabb;
+use fj_math::{Aabb, Obb, Scalar, Vector};
 
-use crate::objects::Solid;
+use crate::{algorithms::mass_properties::MassProperties, objects::Solid};
 
 impl super::BoundingVolume<3> for Solid {
     fn aabb(&self) -> Option<Aabb<3>> {
-        let mut aabb: Option<Aabb<3>> = None;
-
-        for shell in self.shells() {
-            let new_aabb = shell.aabb();
-            aabb = aabb.map_or(new_aabb, |aabb| match new_aabb {
-                Some(new_aabb) => Some(aabb.merged(&new_aabb)),
-                None => Some(aabb),
-            });
-        }
+        super::aabb_from_extents(|direction| extent(self, direction))
+    }
+}
 
-        aabb
+impl super::OrientedBoundingVolume for Solid {
+    fn obb(&self) -> Option<Obb> {
+        let inertia = self.mass_properties().inertia;
+        super::obb_from_extents(inertia, |direction| extent(self, direction))
     }
 }
+
+fn extent(solid: &Solid, direction: Vector<3>) -> Option<[Scalar; 2]> {
+    solid
+        .shells()
+        .iter()
+        .filter_map(|shell| super::shell::extent(shell, direction))
+        .reduce(|[min_a, max_a], [min_b, max_b]| {
+            [min_a.min(min_b), max_a.max(max_b)]
+        })
+}
@@ -0,0 +1,866 @@
+//! Constraint-based sketching
+//!
+//! See [`ConstrainedSketch`].
+
+mod solver;
+
+use std::f64::consts::{PI, TAU};
+
+use fj_math::{Point, Scalar, Winding};
+
+use crate::{
+    geometry::SurfacePath,
+    objects::{Cycle, HalfEdge},
+    operations::{build::BuildHalfEdge, insert::Insert},
+    Instance,
+};
+
+/// A 2D sketch, defined by dimensions and relationships between its elements
+///
+/// Instead of specifying the exact coordinates of every point, a constrained
+/// sketch consists of points and edges, whose positions only need to be
+/// roughly right, and [`Constraint`]s that define their actual relationships.
+/// [`ConstrainedSketch::solve`] then computes positions that satisfy all
+/// constraints.
+///
+/// The solved sketch can be turned into [`HalfEdge`]s and [`Cycle`]s, which in
+/// turn can be used to build regions and sketches.
+///
+/// # Implementation Note
+///
+/// The constraints are solved numerically. If a sketch is under-constrained,
+/// the solution that is closest to the initial positions is found. This means
+/// that initial positions that are far off can lead to a solution that is
+/// valid, but not the one that was intended. For example, a tangent arc could
+/// end up on the wrong side of a line.
+#[derive(Clone, Debug, Default)]
+pub struct ConstrainedSketch {
+    points: Vec<Point<2>>,
+    radii: Vec<Scalar>,
+    edges: Vec<Edge>,
+    constraints: Vec<Constraint>,
+}
+
+impl ConstrainedSketch {
+    /// Construct an empty `ConstrainedSketch`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a point, at an approximate initial position
+    pub fn point(&mut self, initial: impl Into<Point<2>>) -> PointId {
+        self.points.push(initial.into());
+        PointId(self.points.len() - 1)
+    }
+
+    /// Add a line segment between two points
+    pub fn line(&mut self, start: PointId, end: PointId) -> EdgeId {
+        self.add_edge(Edge::Line { start, end })
+    }
+
+    /// Add a circular arc around `center`, from `start` to `end`
+    ///
+    /// The distances of `start` and `end` from `center` are always kept equal.
+    /// The arc runs from `start` to `end` in the direction specified by
+    /// `winding`.
+    pub fn arc(
+        &mut self,
+        center: PointId,
+        start: PointId,
+        end: PointId,
+        winding: Winding,
+    ) -> EdgeId {
+        self.add_edge(Edge::Arc {
+            center,
+            start,
+            end,
+            winding,
+        })
+    }
+
+    /// Add a full circle around `center`, with an approximate initial radius
+    pub fn circle(
+        &mut self,
+        center: PointId,
+        radius: impl Into<Scalar>,
+    ) -> EdgeId {
+        self.radii.push(radius.into());
+        let radius = self.radii.len() - 1;
+
+        self.add_edge(Edge::Circle { center, radius })
+    }
+
+    /// Add a constraint
+    pub fn constrain(&mut self, constraint: Constraint) {
+        self.constraints.push(constraint);
+    }
+
+    /// Compute the positions of all points, so they satisfy all constraints
+    pub fn solve(&self) -> Result<SolvedSketch, SolveError> {
+        let constraints = self
+            .constraints
+            .iter()
+            .enumerate()
+            .map(|(index, constraint)| self.prepare(index, constraint))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Edges contribute to the solution, even if no constraint refers to
+        // them, so all of them need to be checked.
+        for (index, edge) in self.edges.iter().enumerate() {
+            if !self.contains_points(edge.points()) {
+                return Err(SolveError::InvalidEdge { index });
+            }
+        }
+
+        let mut x = self.initial_variables();
+
+        let residual = solver::solve(&mut x, |x, residuals| {
+            let variables = Variables {
+                x,
+                num_points: self.points.len(),
+            };
+
+            for edge in &self.edges {
+                if let Edge::Arc {
+                    center, start, end, ..
+                } = *edge
+                {
+                    residuals.push(
+                        variables.distance(center, end)
+                            - variables.distance(center, start),
+                    );
+                }
+            }
+
+            for constraint in &constraints {
+                constraint.residuals(&variables, residuals);
+            }
+        });
+
+        if residual > solver::TOLERANCE {
+            return Err(SolveError::NotSolved {
+                residual: Scalar::from_f64(residual),
+            });
+        }
+
+        let variables = Variables {
+            x: &x,
+            num_points: self.points.len(),
+        };
+
+        Ok(SolvedSketch {
+            points: (0..self.points.len())
+                .map(|i| Point::from(variables.point(PointId(i))))
+                .collect(),
+            radii: x[self.points.len() * 2..]
+                .iter()
+                .copied()
+                .map(Scalar::from_f64)
+                .collect(),
+            edges: self.edges.clone(),
+        })
+    }
+
+    fn add_edge(&mut self, edge: Edge) -> EdgeId {
+        self.edges.push(edge);
+        EdgeId(self.edges.len() - 1)
+    }
+
+    fn edge(&self, index: usize, edge: EdgeId) -> Result<Edge, SolveError> {
+        self.edges
+            .get(edge.0)
+            .copied()
+            .ok_or(SolveError::InvalidConstraint { index })
+    }
+
+    fn line_points(
+        &self,
+        index: usize,
+        edge: EdgeId,
+    ) -> Result<[PointId; 2], SolveError> {
+        match self.edge(index, edge)? {
+            Edge::Line { start, end } => Ok([start, end]),
+            Edge::Arc { .. } | Edge::Circle { .. } => {
+                Err(SolveError::InvalidConstraint { index })
+            }
+        }
+    }
+
+    /// Check the constraint, and decide anything that depends on the initial
+    /// positions
+    fn prepare(
+        &self,
+        index: usize,
+        constraint: &Constraint,
+    ) -> Result<Prepared, SolveError> {
+        let invalid = SolveError::InvalidConstraint { index };
+
+        let mut points = constraint.points();
+        for edge in constraint.edges() {
+            points.extend(self.edge(index, edge)?.points());
+        }
+        if !self.contains_points(points) {
+            return Err(invalid);
+        }
+
+        let prepared = match *constraint {
+            Constraint::Fixed(point, position) => Prepared::Fixed(
+                point,
+                position.coords.components.map(f64::from),
+            ),
+            Constraint::Coincident(a, b) => Prepared::Coincident(a, b),
+            Constraint::Horizontal(line) => {
+                Prepared::Horizontal(self.line_points(index, line)?)
+            }
+            Constraint::Vertical(line) => {
+                Prepared::Vertical(self.line_points(index, line)?)
+            }
+            Constraint::Parallel(a, b) => Prepared::Parallel(
+                self.line_points(index, a)?,
+                self.line_points(index, b)?,
+            ),
+            Constraint::Perpendicular(a, b) => Prepared::Perpendicular(
+                self.line_points(index, a)?,
+                self.line_points(index, b)?,
+            ),
+            Constraint::Tangent(a, b) => {
+                let [a, b] = [self.edge(index, a)?, self.edge(index, b)?];
+
+                match (a, b) {
+                    (Edge::Line { start, end }, curve)
+                    | (curve, Edge::Line { start, end }) => {
+                        let curve =
+                            Curve::from_edge(curve).ok_or(invalid.clone())?;
+                        Prepared::TangentLine([start, end], curve)
+                    }
+                    (a, b) => {
+                        let [a, b] = [a, b].map(Curve::from_edge);
+                        let (Some(a), Some(b)) = (a, b) else {
+                            return Err(invalid);
+                        };
+
+                        // Curves can touch from the outside or the inside.
+                        // Let's go with whatever is closer initially.
+                        let initial = self.initial_variables();
+                        let variables = Variables {
+                            x: &initial,
+                            num_points: self.points.len(),
+                        };
+                        let [distance, ra, rb] = [
+                            variables.distance(a.center, b.center),
+                            a.radius(&variables),
+                            b.radius(&variables),
+                        ];
+                        let external = (distance - (ra + rb)).abs()
+                            <= (distance - (ra - rb).abs()).abs();
+
+                        Prepared::TangentCurves { a, b, external }
+                    }
+                }
+            }
+            Constraint::Distance(a, b, distance) => {
+                Prepared::Distance(a, b, distance.into_f64())
+            }
+            Constraint::Radius(edge, radius) => {
+                let curve =
+                    Curve::from_edge(self.edge(index, edge)?).ok_or(invalid)?;
+                Prepared::Radius(curve, radius.into_f64())
+            }
+            Constraint::Angle(a, b, angle) => Prepared::Angle(
+                self.line_points(index, a)?,
+                self.line_points(index, b)?,
+                angle.into_f64(),
+            ),
+        };
+
+        Ok(prepared)
+    }
+
+    fn contains_points(
+        &self,
+        points: impl IntoIterator<Item = PointId>,
+    ) -> bool {
+        points.into_iter().all(|point| point.0 < self.points.len())
+    }
+
+    fn initial_variables(&self) -> Vec<f64> {
+        self.points
+            .iter()
+            .flat_map(|point| [point.u, point.v])
+            .chain(self.radii.iter().copied())
+            .map(Scalar::into_f64)
+            .collect()
+    }
+}
+
+/// Identifies a point of a [`ConstrainedSketch`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct PointId(usize);
+
+/// Identifies an edge of a [`ConstrainedSketch`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct EdgeId(usize);
+
+/// A constraint on the points and edges of a [`ConstrainedSketch`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Constraint {
+    /// The point is at the provided position
+    Fixed(PointId, Point<2>),
+
+    /// The points are at the same position
+    Coincident(PointId, PointId),
+
+    /// The line is parallel to the u-axis
+    Horizontal(EdgeId),
+
+    /// The line is parallel to the v-axis
+    Vertical(EdgeId),
+
+    /// The lines are parallel to each other
+    Parallel(EdgeId, EdgeId),
+
+    /// The lines are perpendicular to each other
+    Perpendicular(EdgeId, EdgeId),
+
+    /// The edges touch without crossing
+    ///
+    /// Can be applied to a line and an arc or circle, or to two arcs or
+    /// circles. A line is treated as being infinitely long.
+    Tangent(EdgeId, EdgeId),
+
+    /// The points are the provided distance apart
+    Distance(PointId, PointId, Scalar),
+
+    /// The arc or circle has the provided radius
+    Radius(EdgeId, Scalar),
+
+    /// The angle from the first line to the second is the provided one
+    ///
+    /// The angle is in radians, and measured counter-clockwise.
+    Angle(EdgeId, EdgeId, Scalar),
+}
+
+impl Constraint {
+    fn points(&self) -> Vec<PointId> {
+        match *self {
+            Self::Fixed(point, _) => vec![point],
+            Self::Coincident(a, b) | Self::Distance(a, b, _) => vec![a, b],
+            _ => Vec::new(),
+        }
+    }
+
+    fn edges(&self) -> Vec<EdgeId> {
+        match *self {
+            Self::Horizontal(edge)
+            | Self::Vertical(edge)
+            | Self::Radius(edge, _) => vec![edge],
+            Self::Parallel(a, b)
+            | Self::Perpendicular(a, b)
+            | Self::Tangent(a, b)
+            | Self::Angle(a, b, _) => vec![a, b],
+            Self::Fixed(..) | Self::Coincident(..) | Self::Distance(..) => {
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// A [`ConstrainedSketch`] whose constraints have been solved
+///
+/// Returned by [`ConstrainedSketch::solve`].
+#[derive(Clone, Debug)]
+pub struct SolvedSketch {
+    points: Vec<Point<2>>,
+    radii: Vec<Scalar>,
+    edges: Vec<Edge>,
+}
+
+impl SolvedSketch {
+    /// Access the solved position of a point
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the point is not part of the sketch that was solved.
+    pub fn point(&self, point: PointId) -> Point<2> {
+        self.points[point.0]
+    }
+
+    /// Build a [`HalfEdge`] from the solved edge
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the edge is not part of the sketch that was solved.
+    pub fn half_edge(&self, edge: EdgeId, core: &mut Instance) -> HalfEdge {
+        match self.edges[edge.0] {
+            Edge::Line { start, end } => HalfEdge::line_segment(
+                [self.point(start), self.point(end)],
+                None,
+                core,
+            ),
+            Edge::Arc {
+                center,
+                start,
+                end,
+                winding,
+            } => {
+                let center = self.point(center);
+                let radius = center.distance_to(&self.point(start));
+
+                let [start, end] = [start, end].map(|point| {
+                    let offset = self.point(point) - center;
+                    offset.v.atan2(offset.u).into_f64()
+                });
+
+                // An arc whose start and end are at the same position is a
+                // full circle.
+                let mut angle = (end - start).rem_euclid(TAU);
+                if angle == 0. {
+                    angle = TAU;
+                }
+                if let Winding::Cw = winding {
+                    angle -= TAU;
+                }
+
+                let path =
+                    SurfacePath::circle_from_center_and_radius(center, radius);
+                let boundary = [start, start + angle].map(|t| Point::from([t]));
+
+                HalfEdge::unjoined(path, boundary, core)
+            }
+            Edge::Circle { center, radius } => {
+                HalfEdge::circle(self.point(center), self.radii[radius], core)
+            }
+        }
+    }
+
+    /// Build a [`Cycle`] from the solved edges
+    ///
+    /// The edges must be provided in order, each ending where the next one
+    /// starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if any of the edges is not part of the sketch that was solved.
+    pub fn cycle(
+        &self,
+        edges: impl IntoIterator<Item = EdgeId>,
+        core: &mut Instance,
+    ) -> Cycle {
+        let half_edges = edges
+            .into_iter()
+            .map(|edge| self.half_edge(edge, core).insert(core))
+            .collect::<Vec<_>>();
+
+        Cycle::new(half_edges)
+    }
+}
+
+/// An error that can occur when solving a [`ConstrainedSketch`]
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum SolveError {
+    /// A constraint refers to elements that it can't be applied to
+    #[error(
+        "Constraint {index} can't be applied to the elements it refers to"
+    )]
+    InvalidConstraint {
+        /// The index of the constraint, in the order they were added
+        index: usize,
+    },
+
+    /// An edge refers to points that are not part of the sketch
+    #[error("Edge {index} refers to points that are not part of the sketch")]
+    InvalidEdge {
+        /// The index of the edge, in the order they were added
+        index: usize,
+    },
+
+    /// No positions could be found that satisfy all constraints
+    ///
+    /// This is usually the case, if constraints contradict each other.
+    #[error("Could not satisfy constraints (residual: {residual})")]
+    NotSolved {
+        /// The residual error that remained
+        residual: Scalar,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Edge {
+    Line {
+        start: PointId,
+        end: PointId,
+    },
+    Arc {
+        center: PointId,
+        start: PointId,
+        end: PointId,
+        winding: Winding,
+    },
+    Circle {
+        center: PointId,
+        radius: usize,
+    },
+}
+
+impl Edge {
+    fn points(&self) -> Vec<PointId> {
+        match *self {
+            Self::Line { start, end } => vec![start, end],
+            Self::Arc {
+                center, start, end, ..
+            } => vec![center, start, end],
+            Self::Circle { center, .. } => vec![center],
+        }
+    }
+}
+
+/// An arc or circle, as far as constraints are concerned
+#[derive(Clone, Copy)]
+struct Curve {
+    center: PointId,
+    radius: Radius,
+}
+
+impl Curve {
+    fn from_edge(edge: Edge) -> Option<Self> {
+        match edge {
+            Edge::Line { .. } => None,
+            Edge::Arc { center, start, .. } => Some(Self {
+                center,
+                radius: Radius::Point(start),
+            }),
+            Edge::Circle { center, radius } => Some(Self {
+                center,
+                radius: Radius::Variable(radius),
+            }),
+        }
+    }
+
+    fn radius(&self, variables: &Variables) -> f64 {
+        match self.radius {
+            Radius::Point(point) => variables.distance(self.center, point),
+            Radius::Variable(index) => variables.radius(index),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Radius {
+    Point(PointId),
+    Variable(usize),
+}
+
+/// A constraint, ready to compute its residuals
+enum Prepared {
+    Fixed(PointId, [f64; 2]),
+    Coincident(PointId, PointId),
+    Horizontal([PointId; 2]),
+    Vertical([PointId; 2]),
+    Parallel([PointId; 2], [PointId; 2]),
+    Perpendicular([PointId; 2], [PointId; 2]),
+    TangentLine([PointId; 2], Curve),
+    TangentCurves { a: Curve, b: Curve, external: bool },
+    Distance(PointId, PointId, f64),
+    Radius(Curve, f64),
+    Angle([PointId; 2], [PointId; 2], f64),
+}
+
+impl Prepared {
+    fn residuals(&self, variables: &Variables, residuals: &mut Vec<f64>) {
+        match *self {
+            Self::Fixed(point, [u, v]) => {
+                let [pu, pv] = variables.point(point);
+                residuals.extend([pu - u, pv - v]);
+            }
+            Self::Coincident(a, b) => {
+                let [au, av] = variables.point(a);
+                let [bu, bv] = variables.point(b);
+                residuals.extend([bu - au, bv - av]);
+            }
+            Self::Horizontal(line) => {
+                let [_, v] = variables.direction(line);
+                residuals.push(v);
+            }
+            Self::Vertical(line) => {
+                let [u, _] = variables.direction(line);
+                residuals.push(u);
+            }
+            Self::Parallel(a, b) => {
+                let [a, b] = [a, b].map(|line| variables.unit_direction(line));
+                residuals.push(cross(a, b));
+            }
+            Self::Perpendicular(a, b) => {
+                let [a, b] = [a, b].map(|line| variables.unit_direction(line));
+                residuals.push(dot(a, b));
+            }
+            Self::TangentLine(line, curve) => {
+                let direction = variables.unit_direction(line);
+                let [cu, cv] = variables.point(curve.center);
+                let [su, sv] = variables.point(line[0]);
+
+                let distance = cross(direction, [cu - su, cv - sv]).abs();
+                residuals.push(distance - curve.radius(variables));
+            }
+            Self::TangentCurves { a, b, external } => {
+                let distance = variables.distance(a.center, b.center);
+                let [ra, rb] = [a, b].map(|curve| curve.radius(variables));
+
+                let expected = if external { ra + rb } else { (ra - rb).abs() };
+                residuals.push(distance - expected);
+            }
+            Self::Distance(a, b, distance) => {
+                residuals.push(variables.distance(a, b) - distance);
+            }
+            Self::Radius(curve, radius) => {
+                residuals.push(curve.radius(variables) - radius);
+            }
+            Self::Angle(a, b, angle) => {
+                let [a, b] = [a, b].map(|line| variables.unit_direction(line));
+                let actual = cross(a, b).atan2(dot(a, b));
+
+                let difference = (actual - angle + PI).rem_euclid(TAU) - PI;
+                residuals.push(difference);
+            }
+        }
+    }
+}
+
+/// Provides access to the variables that the solver works with
+struct Variables<'r> {
+    x: &'r [f64],
+    num_points: usize,
+}
+
+impl Variables<'_> {
+    fn point(&self, point: PointId) -> [f64; 2] {
+        [self.x[point.0 * 2], self.x[point.0 * 2 + 1]]
+    }
+
+    fn radius(&self, index: usize) -> f64 {
+        self.x[self.num_points * 2 + index]
+    }
+
+    fn distance(&self, a: PointId, b: PointId) -> f64 {
+        let [u, v] = self.direction([a, b]);
+        u.hypot(v)
+    }
+
+    fn direction(&self, [start, end]: [PointId; 2]) -> [f64; 2] {
+        let [su, sv] = self.point(start);
+        let [eu, ev] = self.point(end);
+        [eu - su, ev - sv]
+    }
+
+    fn unit_direction(&self, line: [PointId; 2]) -> [f64; 2] {
+        let [u, v] = self.direction(line);
+        let length = u.hypot(v).max(f64::EPSILON);
+        [u / length, v / length]
+    }
+}
+
+fn cross([au, av]: [f64; 2], [bu, bv]: [f64; 2]) -> f64 {
+    au * bv - av * bu
+}
+
+fn dot([au, av]: [f64; 2], [bu, bv]: [f64; 2]) -> f64 {
+    au * bu + av * bv
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_3;
+
+    use fj_math::{Point, Scalar, Winding};
+
+    use crate::{
+        objects::Region,
+        operations::{build::BuildRegion, update::UpdateRegion},
+        validate::Validate,
+        Instance,
+    };
+
+    use super::{ConstrainedSketch, Constraint, SolveError};
+
+    #[test]
+    fn rectangle() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+        let mut sketch = ConstrainedSketch::new();
+
+        let a = sketch.point([0.1, -0.2]);
+        let b = sketch.point([2.5, 0.3]);
+        let c = sketch.point([2.8, 2.2]);
+        let d = sketch.point([-0.3, 1.5]);
+
+        let ab = sketch.line(a, b);
+        let bc = sketch.line(b, c);
+        let cd = sketch.line(c, d);
+        let da = sketch.line(d, a);
+
+        for constraint in [
+            Constraint::Fixed(a, Point::from([0., 0.])),
+            Constraint::Horizontal(ab),
+            Constraint::Vertical(bc),
+            Constraint::Parallel(cd, ab),
+            Constraint::Perpendicular(da, cd),
+            Constraint::Distance(a, b, Scalar::from(3.)),
+            Constraint::Distance(b, c, Scalar::from(2.)),
+        ] {
+            sketch.constrain(constraint);
+        }
+
+        let solved = sketch.solve().unwrap();
+        assert_points_eq(solved.point(c), [3., 2.]);
+        assert_points_eq(solved.point(d), [0., 2.]);
+
+        let exterior = solved.cycle([ab, bc, cd, da], &mut core);
+        let region = Region::empty(&mut core)
+            .update_exterior(|_, _| exterior, &mut core);
+        region.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn tangent_arc() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+        let mut sketch = ConstrainedSketch::new();
+
+        let a = sketch.point([0., 0.]);
+        let b = sketch.point([1.8, 0.2]);
+        let center = sketch.point([2.1, 0.9]);
+        let c = sketch.point([1.9, 2.1]);
+        let d = sketch.point([0.1, 1.8]);
+
+        let ab = sketch.line(a, b);
+        let arc = sketch.arc(center, b, c, Winding::Ccw);
+        let cd = sketch.line(c, d);
+        let da = sketch.line(d, a);
+
+        for constraint in [
+            Constraint::Fixed(a, Point::from([0., 0.])),
+            Constraint::Horizontal(ab),
+            Constraint::Vertical(da),
+            Constraint::Distance(a, b, Scalar::from(2.)),
+            Constraint::Tangent(ab, arc),
+            Constraint::Tangent(arc, cd),
+            Constraint::Radius(arc, Scalar::ONE),
+            Constraint::Parallel(cd, ab),
+        ] {
+            sketch.constrain(constraint);
+        }
+
+        let solved = sketch.solve().unwrap();
+        assert_points_eq(solved.point(center), [2., 1.]);
+        assert_points_eq(solved.point(c), [2., 2.]);
+        assert_points_eq(solved.point(d), [0., 2.]);
+
+        let exterior = solved.cycle([ab, arc, cd, da], &mut core);
+        let region = Region::empty(&mut core)
+            .update_exterior(|_, _| exterior, &mut core);
+        region.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn angle_and_tangent_circles() {
+        let mut sketch = ConstrainedSketch::new();
+
+        let origin = sketch.point([0., 0.]);
+        let a = sketch.point([1., 0.]);
+        let b = sketch.point([0.4, 0.9]);
+        let oa = sketch.line(origin, a);
+        let ob = sketch.line(origin, b);
+
+        let circle_a = sketch.circle(a, 0.5);
+        let circle_b = sketch.circle(b, 0.3);
+
+        for constraint in [
+            Constraint::Fixed(origin, Point::from([0., 0.])),
+            Constraint::Fixed(a, Point::from([1., 0.])),
+            Constraint::Angle(oa, ob, Scalar::from(FRAC_PI_3)),
+            Constraint::Distance(origin, b, Scalar::ONE),
+            Constraint::Radius(circle_a, Scalar::from(0.6)),
+            Constraint::Tangent(circle_a, circle_b),
+        ] {
+            sketch.constrain(constraint);
+        }
+
+        let solved = sketch.solve().unwrap();
+        assert_points_eq(solved.point(b), [0.5, FRAC_PI_3.sin()]);
+
+        // The distance between the centers is 1, so the circles touch from
+        // the outside.
+        let radius_b = solved.radii[1];
+        assert!((radius_b - 0.4).abs() < Scalar::from(1e-6));
+    }
+
+    #[test]
+    fn invalid_constraints() {
+        let mut sketch = ConstrainedSketch::new();
+
+        let a = sketch.point([0., 0.]);
+        let b = sketch.point([1., 0.]);
+        let circle = sketch.circle(a, 1.);
+        sketch.constrain(Constraint::Distance(a, b, Scalar::ONE));
+        sketch.constrain(Constraint::Horizontal(circle));
+
+        assert_eq!(
+            sketch.solve().unwrap_err(),
+            SolveError::InvalidConstraint { index: 1 }
+        );
+
+        let mut sketch = ConstrainedSketch::new();
+
+        let a = sketch.point([0., 0.]);
+        let b = sketch.point([1., 0.]);
+        sketch.constrain(Constraint::Distance(a, b, Scalar::ONE));
+        sketch.constrain(Constraint::Distance(a, b, Scalar::from(2.)));
+
+        assert!(matches!(sketch.solve(), Err(SolveError::NotSolved { .. })));
+    }
+
+    #[test]
+    fn points_of_other_sketch() {
+        let mut other = ConstrainedSketch::new();
+        for u in 0..4 {
+            other.point([f64::from(u), 0.]);
+        }
+        let foreign = other.point([4., 0.]);
+
+        // A constraint on an edge that refers to a foreign point can't be
+        // applied.
+        let mut sketch = ConstrainedSketch::new();
+
+        let a = sketch.point([0., 0.]);
+        let line = sketch.line(a, foreign);
+        sketch.constrain(Constraint::Fixed(a, Point::from([0., 0.])));
+        sketch.constrain(Constraint::Horizontal(line));
+
+        assert_eq!(
+            sketch.solve().unwrap_err(),
+            SolveError::InvalidConstraint { index: 1 }
+        );
+
+        // Edges that no constraint refers to are checked too.
+        let mut sketch = ConstrainedSketch::new();
+
+        let a = sketch.point([0., 0.]);
+        let b = sketch.point([1., 0.]);
+        sketch.line(a, b);
+        sketch.arc(a, b, foreign, Winding::Ccw);
+
+        assert_eq!(
+            sketch.solve().unwrap_err(),
+            SolveError::InvalidEdge { index: 1 }
+        );
+    }
+
+    fn assert_points_eq(actual: Point<2>, expected: [f64; 2]) {
+        let expected = Point::from(expected);
+        assert!(
+            actual.distance_to(&expected) < Scalar::from(1e-6),
+            "{actual:?} != {expected:?}"
+        );
+    }
+}
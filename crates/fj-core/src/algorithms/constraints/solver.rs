@@ -0,0 +1,152 @@
+/// The maximum number of iterations, before the solver gives up
+const MAX_ITERATIONS: usize = 200;
+
+/// The residual below which a system is considered to be solved
+pub const TOLERANCE: f64 = 1e-9;
+
+/// The residual below which the solver stops improving the solution
+///
+/// This is much smaller than [`TOLERANCE`]. Some constraints, like a line that
+/// is tangent to an arc at its end point, only change quadratically with the
+/// position of the points. A small residual doesn't mean the positions are
+/// accurate, in that case.
+const TARGET: f64 = 1e-15;
+
+/// Solve a system of equations numerically
+///
+/// Updates `x`, so that all residuals computed by `residuals` become zero. This
+/// is done using the Levenberg-Marquardt algorithm, which minimizes the sum of
+/// the squared residuals. The damping it applies makes it well-behaved for
+/// under-constrained systems, which have many solutions. In that case, it
+/// converges to a solution that is close to the initial value of `x`.
+///
+/// Returns the norm of the remaining residuals, which is larger than
+/// [`TOLERANCE`], if no solution was found.
+pub fn solve(x: &mut [f64], residuals: impl Fn(&[f64], &mut Vec<f64>)) -> f64 {
+    let mut r = Vec::new();
+    residuals(x, &mut r);
+    let mut cost = squared_norm(&r);
+
+    let mut damping = 1e-3;
+
+    for _ in 0..MAX_ITERATIONS {
+        if cost.sqrt() < TARGET {
+            break;
+        }
+
+        let jacobian = jacobian(x, &r, &residuals);
+
+        // Set up the normal equations, `(JᵀJ + λD) δ = -Jᵀr`.
+        let n = x.len();
+        let mut a = vec![vec![0.; n]; n];
+        let mut g = vec![0.; n];
+        for (row, r) in jacobian.iter().zip(&r) {
+            for i in 0..n {
+                g[i] -= row[i] * r;
+                for j in 0..n {
+                    a[i][j] += row[i] * row[j];
+                }
+            }
+        }
+
+        loop {
+            let mut damped = a.clone();
+            for (i, row) in damped.iter_mut().enumerate() {
+                row[i] += damping * (a[i][i] + 1e-12);
+            }
+
+            let step = solve_linear(damped, g.clone());
+            let candidate = step.map(|step| {
+                x.iter().zip(step).map(|(x, dx)| x + dx).collect::<Vec<_>>()
+            });
+
+            if let Some(candidate) = candidate {
+                let mut candidate_r = Vec::new();
+                residuals(&candidate, &mut candidate_r);
+                let candidate_cost = squared_norm(&candidate_r);
+
+                if candidate_cost < cost {
+                    x.copy_from_slice(&candidate);
+                    r = candidate_r;
+                    cost = candidate_cost;
+                    damping = (damping / 10.).max(1e-12);
+                    break;
+                }
+            }
+
+            damping *= 10.;
+            if damping > 1e12 {
+                // We're not making any progress, and won't be able to.
+                return cost.sqrt();
+            }
+        }
+    }
+
+    cost.sqrt()
+}
+
+fn squared_norm(r: &[f64]) -> f64 {
+    r.iter().map(|r| r * r).sum()
+}
+
+/// Approximate the Jacobian of the residuals using forward differences
+fn jacobian(
+    x: &[f64],
+    r: &[f64],
+    residuals: &impl Fn(&[f64], &mut Vec<f64>),
+) -> Vec<Vec<f64>> {
+    let mut jacobian = vec![vec![0.; x.len()]; r.len()];
+
+    let mut x = x.to_vec();
+    let mut r_h = Vec::new();
+
+    for j in 0..x.len() {
+        let original = x[j];
+        let h = 1e-8 * original.abs().max(1.);
+
+        x[j] = original + h;
+        r_h.clear();
+        residuals(&x, &mut r_h);
+        x[j] = original;
+
+        for (i, (r_h, r)) in r_h.iter().zip(r).enumerate() {
+            jacobian[i][j] = (r_h - r) / h;
+        }
+    }
+
+    jacobian
+}
+
+/// Solve a linear system using Gaussian elimination with partial pivoting
+///
+/// Returns `None`, if the system is singular.
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+
+    for k in 0..n {
+        let pivot =
+            (k..n).max_by(|&i, &j| a[i][k].abs().total_cmp(&a[j][k].abs()))?;
+        if a[pivot][k].abs() < f64::EPSILON {
+            return None;
+        }
+
+        a.swap(k, pivot);
+        b.swap(k, pivot);
+
+        for i in k + 1..n {
+            let factor = a[i][k] / a[k][k];
+            for j in k..n {
+                a[i][j] -= factor * a[k][j];
+            }
+            b[i] -= factor * b[k];
+        }
+    }
+
+    let mut x = vec![0.; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|j| a[i][j] * x[j]).sum();
+        x[i] = (b[i] - sum) / a[i][i];
+    }
+
+    Some(x)
+}
@@ -0,0 +1,382 @@
+//! Compute the minimum distance between objects
+//!
+//! See [`Distance`].
+
+use fj_interop::Mesh;
+use fj_math::{Aabb, Point, Scalar, Triangle};
+
+use crate::objects::{Face, Shell, Solid};
+
+use super::{
+    approx::{Approx, Tolerance},
+    triangulate::Triangulate,
+};
+
+/// Compute the minimum distance between two objects
+///
+/// This is intended for clearance checks, for example between the parts of an
+/// assembly.
+///
+/// # Implementation Note
+///
+/// The faces of both objects are triangulated at the provided tolerance. The
+/// AABBs of those triangulated faces are used to skip all pairs of faces that
+/// can't be closer than the closest pair found so far. The remaining pairs of
+/// triangles are compared exactly.
+///
+/// For faces that are planar and bounded by line segments, the result is
+/// exact. Where curved geometry is involved, the result can be off by up to the
+/// tolerance.
+pub trait Distance<Other = Self> {
+    /// Compute the minimum distance to another object
+    ///
+    /// Returns `None`, if either of the objects has no faces.
+    fn distance_to(
+        &self,
+        other: &Other,
+        tolerance: impl Into<Tolerance>,
+    ) -> Option<Clearance>;
+}
+
+impl Distance for Face {
+    fn distance_to(
+        &self,
+        other: &Self,
+        tolerance: impl Into<Tolerance>,
+    ) -> Option<Clearance> {
+        let tolerance = tolerance.into();
+
+        let a = FaceMesh::from_faces([self], tolerance);
+        let b = FaceMesh::from_faces([other], tolerance);
+
+        // Faces don't enclose any volume, so they can't contain each other.
+        clearance_between_boundaries(&a, &b)
+    }
+}
+
+impl Distance for Shell {
+    fn distance_to(
+        &self,
+        other: &Self,
+        tolerance: impl Into<Tolerance>,
+    ) -> Option<Clearance> {
+        let tolerance = tolerance.into();
+
+        let a = FaceMesh::from_faces(faces_of_shell(self), tolerance);
+        let b = FaceMesh::from_faces(faces_of_shell(other), tolerance);
+
+        clearance_between_volumes(&a, &b)
+    }
+}
+
+impl Distance for Solid {
+    fn distance_to(
+        &self,
+        other: &Self,
+        tolerance: impl Into<Tolerance>,
+    ) -> Option<Clearance> {
+        let tolerance = tolerance.into();
+
+        let a = FaceMesh::from_faces(faces_of_solid(self), tolerance);
+        let b = FaceMesh::from_faces(faces_of_solid(other), tolerance);
+
+        clearance_between_volumes(&a, &b)
+    }
+}
+
+/// The clearance between two objects
+///
+/// Computed by [`Distance`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Clearance {
+    /// The objects are apart
+    Apart {
+        /// The minimum distance between the objects
+        distance: Scalar,
+
+        /// The closest points on both objects
+        ///
+        /// The point on the object that `distance_to` was called on comes
+        /// first, the point on the other object second.
+        closest_points: [Point<3>; 2],
+    },
+
+    /// The objects overlap
+    ///
+    /// This is the case, if their boundaries touch or intersect, or if one of
+    /// the objects is completely inside the other.
+    Overlapping,
+}
+
+impl Clearance {
+    /// Access the minimum distance between the objects
+    ///
+    /// Returns zero, if the objects overlap.
+    pub fn distance(&self) -> Scalar {
+        match self {
+            Self::Apart { distance, .. } => *distance,
+            Self::Overlapping => Scalar::ZERO,
+        }
+    }
+
+    /// Indicate whether the objects overlap
+    pub fn is_overlapping(&self) -> bool {
+        matches!(self, Self::Overlapping)
+    }
+}
+
+/// The triangulation of a face, prepared for distance queries
+pub(super) struct FaceMesh {
+    pub aabb: Aabb<3>,
+    pub triangles: Vec<(Triangle<3>, Aabb<3>)>,
+}
+
+impl FaceMesh {
+    pub fn from_faces<'r>(
+        faces: impl IntoIterator<Item = &'r Face>,
+        tolerance: Tolerance,
+    ) -> Vec<Self> {
+        faces
+            .into_iter()
+            .filter_map(|face| {
+                let mesh: Mesh<Point<3>> = face.approx(tolerance).triangulate();
+
+                let triangles = mesh
+                    .triangles()
+                    .map(|triangle| {
+                        let triangle = triangle.inner;
+                        (triangle, Aabb::<3>::from_points(triangle.points()))
+                    })
+                    .collect::<Vec<_>>();
+
+                let aabb = triangles
+                    .iter()
+                    .map(|(_, aabb)| *aabb)
+                    .reduce(|a, b| a.merged(&b))?;
+
+                Some(Self { aabb, triangles })
+            })
+            .collect()
+    }
+}
+
+fn faces_of_shell(shell: &Shell) -> impl Iterator<Item = &Face> {
+    shell.faces().iter().map(|face| &**face)
+}
+
+pub(super) fn faces_of_solid(solid: &Solid) -> impl Iterator<Item = &Face> {
+    solid
+        .shells()
+        .iter()
+        .flat_map(|shell| faces_of_shell(shell))
+}
+
+fn clearance_between_volumes(
+    a: &[FaceMesh],
+    b: &[FaceMesh],
+) -> Option<Clearance> {
+    let clearance = clearance_between_boundaries(a, b)?;
+
+    // Even if the boundaries don't meet, one object could be completely inside
+    // of the other. Then any of its points is inside.
+    if let Clearance::Apart { .. } = clearance {
+        if is_inside(a, b) || is_inside(b, a) {
+            return Some(Clearance::Overlapping);
+        }
+    }
+
+    Some(clearance)
+}
+
+fn clearance_between_boundaries(
+    a: &[FaceMesh],
+    b: &[FaceMesh],
+) -> Option<Clearance> {
+    // Sort all pairs of faces by the lower bound of their distance. This way,
+    // we're likely to find the closest points early, and can skip most of the
+    // remaining pairs.
+    let mut pairs = a
+        .iter()
+        .flat_map(|a| {
+            b.iter()
+                .map(move |b| (a.aabb.distance_to_aabb(&b.aabb), a, b))
+        })
+        .collect::<Vec<_>>();
+    pairs.sort_by_key(|(lower_bound, ..)| *lower_bound);
+
+    let mut closest: Option<(Scalar, [Point<3>; 2])> = None;
+
+    for (lower_bound, a, b) in pairs {
+        if let Some((distance, _)) = closest {
+            if lower_bound >= distance {
+                break;
+            }
+        }
+
+        for (triangle_a, aabb_a) in &a.triangles {
+            for (triangle_b, aabb_b) in &b.triangles {
+                if let Some((distance, _)) = closest {
+                    if aabb_a.distance_to_aabb(aabb_b) >= distance {
+                        continue;
+                    }
+                }
+
+                let Some(points) = triangle_a.closest_points(triangle_b) else {
+                    return Some(Clearance::Overlapping);
+                };
+
+                let [p, q] = points;
+                let distance = p.distance_to(&q);
+
+                if closest.map_or(true, |(closest, _)| distance < closest) {
+                    closest = Some((distance, points));
+                }
+            }
+        }
+    }
+
+    closest.map(|(distance, closest_points)| Clearance::Apart {
+        distance,
+        closest_points,
+    })
+}
+
+/// Determine whether the boundary `a` is inside of the volume bounded by `b`
+///
+/// Must only be called, if the boundaries don't cross each other. They may
+/// touch, which is why we can't just check any single point of `a`. It could
+/// be on the boundary of `b`, where the winding number is ambiguous.
+pub(super) fn is_inside(a: &[FaceMesh], b: &[FaceMesh]) -> bool {
+    let points =
+        a.iter()
+            .flat_map(|face| &face.triangles)
+            .map(|(triangle, _)| {
+                let [p, q, r] = triangle.points().map(|point| point.coords);
+                Point::origin() + (p + q + r) / 3.
+            });
+
+    for point in points {
+        let winding_number = winding_number(b, point).abs();
+
+        if winding_number > Scalar::from_f64(0.75) {
+            return true;
+        }
+        if winding_number < Scalar::from_f64(0.25) {
+            return false;
+        }
+    }
+
+    // All points we checked are on the boundary of `b`. If there are any, then
+    // `a` is just as big as `b`, which makes it inside, for our purposes.
+    !a.is_empty()
+}
+
+/// Compute the generalized winding number of the triangles around the point
+///
+/// Sums up the solid angles of all triangles, as seen from the point. For a
+/// closed surface, this is `1` for points inside and `0` for points outside,
+/// assuming the triangles face outwards. Unlike ray casting, this doesn't
+/// suffer from edge cases where a ray would hit an edge or a vertex.
+fn winding_number(faces: &[FaceMesh], point: Point<3>) -> Scalar {
+    let mut solid_angle = Scalar::ZERO;
+
+    for (triangle, _) in faces.iter().flat_map(|face| &face.triangles) {
+        let [a, b, c] = triangle.points().map(|vertex| vertex - point);
+        let [la, lb, lc] = [a, b, c].map(|vector| vector.magnitude());
+
+        // Van Oosterom and Strackee's formula for the solid angle of a
+        // triangle.
+        let numerator = a.dot(&b.cross(&c));
+        let denominator =
+            la * lb * lc + a.dot(&b) * lc + a.dot(&c) * lb + b.dot(&c) * la;
+
+        solid_angle += numerator.atan2(denominator) * 2.;
+    }
+
+    solid_angle / (Scalar::PI * 4.)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::distance::{Clearance, Distance},
+        objects::Solid,
+        operations::build::{BuildSolid, Tetrahedron},
+        Instance,
+    };
+
+    fn tetrahedron(
+        origin: [f64; 3],
+        size: f64,
+        core: &mut Instance,
+    ) -> Tetrahedron {
+        let [x, y, z] = origin;
+        Solid::tetrahedron(
+            [
+                [x, y, z],
+                [x, y + size, z],
+                [x + size, y, z],
+                [x, y, z + size],
+            ],
+            core,
+        )
+    }
+
+    #[test]
+    fn solids_apart() {
+        let mut core = Instance::new();
+
+        let a = tetrahedron([0., 0., 0.], 1., &mut core);
+        let b = tetrahedron([0., 0., 2.], 1., &mut core);
+
+        let clearance = a.solid.distance_to(&b.solid, 0.001).unwrap();
+        let Clearance::Apart {
+            distance,
+            closest_points: [p, q],
+        } = clearance
+        else {
+            panic!("Expected solids to be apart");
+        };
+
+        assert_eq!(distance, Scalar::ONE);
+        assert_eq!(p, Point::from([0., 0., 1.]));
+        assert_eq!(q, Point::from([0., 0., 2.]));
+
+        let clearance = a.shell.abc.face.distance_to(&b.shell.abc.face, 0.001);
+        assert_eq!(clearance.map(|c| c.distance()), Some(Scalar::from(2.)));
+    }
+
+    #[test]
+    fn solids_overlapping() {
+        let mut core = Instance::new();
+
+        let a = tetrahedron([0., 0., 0.], 1., &mut core);
+        let b = tetrahedron([0.1, 0.1, 0.1], 1., &mut core);
+
+        let clearance = a.solid.distance_to(&b.solid, 0.001);
+        assert_eq!(clearance, Some(Clearance::Overlapping));
+    }
+
+    #[test]
+    fn solid_inside_other_solid() {
+        let mut core = Instance::new();
+
+        let a = tetrahedron([0., 0., 0.], 1., &mut core);
+        let b = tetrahedron([0.1, 0.1, 0.1], 0.1, &mut core);
+
+        assert_eq!(
+            a.solid.distance_to(&b.solid, 0.001),
+            Some(Clearance::Overlapping)
+        );
+        assert_eq!(
+            b.solid.distance_to(&a.solid, 0.001),
+            Some(Clearance::Overlapping)
+        );
+
+        // Faces don't have an inside.
+        let clearance = a.shell.abc.face.distance_to(&b.shell.abc.face, 0.001);
+        assert!(clearance.is_some_and(|clearance| !clearance.is_overlapping()));
+    }
+}
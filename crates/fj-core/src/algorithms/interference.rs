@@ -0,0 +1,325 @@
+//! Detect interference between the solids of an assembly
+//!
+//! See [`Interference`].
+
+use fj_math::{Point, Scalar, Segment, Triangle};
+
+use crate::{objects::Solid, validate::ValidationConfig};
+
+use super::{
+    approx::Tolerance,
+    bounding_volume::BoundingVolume,
+    distance::{faces_of_solid, is_inside, FaceMesh},
+    mass_properties::MassProperties,
+};
+
+/// An interference between two solids of an assembly
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Interference {
+    /// The indices of the interfering solids, within the provided list
+    pub solids: [usize; 2],
+
+    /// How the solids interfere
+    pub kind: InterferenceKind,
+}
+
+impl Interference {
+    /// Detect all pairs of interfering solids
+    ///
+    /// Solids interfere, if their boundaries cross each other, or if one solid
+    /// is inside of the other. Solids that only touch each other, without
+    /// sharing any volume, are not reported.
+    ///
+    /// The AABBs of the solids are used to skip all pairs that can't
+    /// interfere. The faces of the remaining solids are triangulated at the
+    /// provided tolerance.
+    ///
+    /// # Implementation Note
+    ///
+    /// Solids that are identical, are considered to be inside of each other.
+    /// Beyond that, solids whose boundaries overlap everywhere, without
+    /// crossing, are not detected reliably.
+    pub fn detect<'r>(
+        solids: impl IntoIterator<Item = &'r Solid>,
+        tolerance: impl Into<Tolerance>,
+    ) -> Vec<Self> {
+        let tolerance = tolerance.into();
+
+        let solids = solids.into_iter().collect::<Vec<_>>();
+        let mut meshes: Vec<Option<Vec<FaceMesh>>> =
+            solids.iter().map(|_| None).collect();
+
+        let mut interferences = Vec::new();
+
+        for [i, j] in candidate_pairs(&solids) {
+            for k in [i, j] {
+                meshes[k].get_or_insert_with(|| {
+                    FaceMesh::from_faces(faces_of_solid(solids[k]), tolerance)
+                });
+            }
+
+            let [Some(a), Some(b)] = [&meshes[i], &meshes[j]] else {
+                unreachable!("Just computed meshes for both solids");
+            };
+
+            let curves = intersection_curves(a, b);
+
+            let kind = if !curves.is_empty() {
+                InterferenceKind::Intersecting { curves }
+            } else if is_inside(a, b) {
+                InterferenceKind::Contained {
+                    inner: i,
+                    volume: solids[i].mass_properties().volume,
+                }
+            } else if is_inside(b, a) {
+                InterferenceKind::Contained {
+                    inner: j,
+                    volume: solids[j].mass_properties().volume,
+                }
+            } else {
+                continue;
+            };
+
+            interferences.push(Self {
+                solids: [i, j],
+                kind,
+            });
+        }
+
+        interferences
+    }
+}
+
+/// How two solids interfere
+///
+/// See [`Interference`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum InterferenceKind {
+    /// The boundaries of the solids intersect
+    Intersecting {
+        /// The curves along which the boundaries intersect
+        ///
+        /// The curves are approximated by line segments, according to the
+        /// tolerance that was used to detect the interference. The segments
+        /// are not in any particular order.
+        curves: Vec<Segment<3>>,
+    },
+
+    /// One solid is completely inside the other
+    Contained {
+        /// The index of the solid that is inside of the other
+        inner: usize,
+
+        /// The volume that both solids share
+        ///
+        /// This is the volume of the inner solid.
+        volume: Scalar,
+    },
+}
+
+/// Find all pairs of solids whose AABBs overlap
+///
+/// Sorts the AABBs along the x-axis, so each solid only needs to be compared to
+/// those that overlap it along that axis.
+fn candidate_pairs(solids: &[&Solid]) -> Vec<[usize; 2]> {
+    let mut aabbs = solids
+        .iter()
+        .enumerate()
+        .filter_map(|(i, solid)| solid.aabb().map(|aabb| (i, aabb)))
+        .collect::<Vec<_>>();
+    aabbs.sort_by_key(|(_, aabb)| aabb.min.x);
+
+    let mut pairs = Vec::new();
+
+    for (n, (i, a)) in aabbs.iter().enumerate() {
+        for (j, b) in &aabbs[n + 1..] {
+            if b.min.x > a.max.x {
+                break;
+            }
+
+            if a.distance_to_aabb(b) == Scalar::ZERO {
+                pairs.push([*i.min(j), *i.max(j)]);
+            }
+        }
+    }
+
+    pairs.sort();
+    pairs
+}
+
+fn intersection_curves(a: &[FaceMesh], b: &[FaceMesh]) -> Vec<Segment<3>> {
+    let mut curves = Vec::new();
+
+    for a in a {
+        for b in b {
+            if a.aabb.distance_to_aabb(&b.aabb) > Scalar::ZERO {
+                continue;
+            }
+
+            for (triangle_a, aabb_a) in &a.triangles {
+                for (triangle_b, aabb_b) in &b.triangles {
+                    if aabb_a.distance_to_aabb(aabb_b) > Scalar::ZERO {
+                        continue;
+                    }
+
+                    curves.extend(intersect_triangles(triangle_a, triangle_b));
+                }
+            }
+        }
+    }
+
+    curves
+}
+
+/// Compute the segment along which two triangles cross each other
+///
+/// Returns `None`, if the triangles don't cross. That includes triangles that
+/// only touch, and coplanar triangles.
+fn intersect_triangles(a: &Triangle<3>, b: &Triangle<3>) -> Option<Segment<3>> {
+    let tolerance = ValidationConfig::default().distinct_min_distance;
+
+    let crossing_a = crossing_of_plane(a, b)?;
+    let crossing_b = crossing_of_plane(b, a)?;
+
+    // Both crossings are on the line where the planes of the triangles meet.
+    // Their overlap along that line is where the triangles cross.
+    let direction = a.normal().cross(&b.normal());
+    let [range_a, range_b] = [crossing_a, crossing_b].map(|points| {
+        let mut range = points.map(|point| point.coords.dot(&direction));
+        range.sort();
+        range
+    });
+
+    let start = range_a[0].max(range_b[0]);
+    let end = range_a[1].min(range_b[1]);
+    if start >= end {
+        return None;
+    }
+
+    let [p, q] = crossing_a;
+    let [t_p, t_q] = crossing_a.map(|point| point.coords.dot(&direction));
+    let points = [start, end].map(|t| p + (q - p) * ((t - t_p) / (t_q - t_p)));
+
+    let [start, end] = points;
+    if start.distance_to(&end) < tolerance {
+        return None;
+    }
+
+    Some(Segment::from_points(points))
+}
+
+/// Compute the segment where a triangle crosses the plane of another
+///
+/// Returns `None`, unless the triangle has points on both sides of the plane.
+fn crossing_of_plane(
+    triangle: &Triangle<3>,
+    other: &Triangle<3>,
+) -> Option<[Point<3>; 2]> {
+    let tolerance = ValidationConfig::default().distinct_min_distance;
+
+    let normal = other.normal();
+    let origin = other.points()[0];
+
+    let points = triangle.points();
+    let distances = points.map(|point| {
+        let distance = (point - origin).dot(&normal);
+        if distance.abs() < tolerance {
+            Scalar::ZERO
+        } else {
+            distance
+        }
+    });
+
+    let has_points_on_both_sides = distances.iter().any(|d| *d > Scalar::ZERO)
+        && distances.iter().any(|d| *d < Scalar::ZERO);
+    if !has_points_on_both_sides {
+        return None;
+    }
+
+    let mut crossing = Vec::new();
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+
+        let [p, q] = [points[i], points[j]];
+        let [d_p, d_q] = [distances[i], distances[j]];
+
+        if d_p == Scalar::ZERO {
+            crossing.push(p);
+        }
+        if d_p * d_q < Scalar::ZERO {
+            crossing.push(p + (q - p) * (d_p / (d_p - d_q)));
+        }
+    }
+
+    let [p, q] = crossing.as_slice() else {
+        unreachable!(
+            "Triangle with points on both sides of a plane must cross it \
+            twice"
+        );
+    };
+
+    Some([*p, *q])
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::interference::{Interference, InterferenceKind},
+        objects::Solid,
+        operations::build::{BuildSolid, Tetrahedron},
+        Instance,
+    };
+
+    fn tetrahedron(origin: [f64; 3], size: f64, core: &mut Instance) -> Solid {
+        let [x, y, z] = origin;
+        let Tetrahedron { solid, .. } = Solid::tetrahedron(
+            [
+                [x, y, z],
+                [x, y + size, z],
+                [x + size, y, z],
+                [x, y, z + size],
+            ],
+            core,
+        );
+
+        solid
+    }
+
+    #[test]
+    fn detect_interferences() {
+        let mut core = Instance::new();
+
+        let solids = [
+            tetrahedron([0., 0., 0.], 1., &mut core),
+            tetrahedron([0.2, 0.2, 0.2], 1., &mut core),
+            tetrahedron([5., 5., 5.], 1., &mut core),
+            tetrahedron([0.1, 0.1, 0.1], 0.1, &mut core),
+            tetrahedron([5., 5., 6.], 1., &mut core),
+        ];
+
+        let interferences = Interference::detect(&solids, 0.001);
+        let pairs = interferences
+            .iter()
+            .map(|interference| interference.solids)
+            .collect::<Vec<_>>();
+
+        // The last two tetrahedra touch, but don't interfere.
+        assert_eq!(pairs, [[0, 1], [0, 3]]);
+
+        let InterferenceKind::Intersecting { curves } = &interferences[0].kind
+        else {
+            panic!("Expected boundaries to intersect");
+        };
+        assert!(!curves.is_empty());
+
+        let InterferenceKind::Contained { inner, volume } =
+            interferences[1].kind
+        else {
+            panic!("Expected tetrahedron to be contained");
+        };
+        assert_eq!(inner, 3);
+        assert!((volume - 0.001 / 6.).abs() < Scalar::from_f64(1e-12));
+    }
+}
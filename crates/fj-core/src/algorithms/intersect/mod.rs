@@ -1,9 +1,11 @@
 //! Intersection algorithms
 
 pub mod face_point;
+pub mod path_path;
 pub mod ray_edge;
 pub mod ray_face;
 pub mod ray_segment;
+pub mod solid_point;
 
 mod curve_edge;
 mod curve_face;
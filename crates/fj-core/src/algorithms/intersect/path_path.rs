@@ -0,0 +1,258 @@
+//! Intersection between two paths in 2D
+
+use fj_math::{Circle, Line, Point, Scalar, Sign, Vector};
+
+use crate::geometry::SurfacePath;
+
+use super::Intersect;
+
+impl Intersect for (&SurfacePath, &SurfacePath) {
+    type Intersection = PathPathIntersection;
+
+    fn intersect(self) -> Option<Self::Intersection> {
+        let (a, b) = self;
+
+        match (a, b) {
+            (SurfacePath::Line(a), SurfacePath::Line(b)) => line_line(a, b),
+            (SurfacePath::Line(line), SurfacePath::Circle(circle)) => {
+                line_circle(line, circle)
+            }
+            (SurfacePath::Circle(circle), SurfacePath::Line(line)) => {
+                line_circle(line, circle).map(PathPathIntersection::swap)
+            }
+            (SurfacePath::Circle(a), SurfacePath::Circle(b)) => {
+                circle_circle(a, b)
+            }
+        }
+    }
+}
+
+/// The intersection between two paths
+///
+/// Can be computed using [`Intersect`], implemented for
+/// `(&SurfacePath, &SurfacePath)`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum PathPathIntersection {
+    /// The paths intersect at one or more points
+    ///
+    /// Each intersection point is provided in the path coordinates of both
+    /// paths, in the order the paths were passed in. The points are sorted by
+    /// their coordinate on the first path. Circle coordinates are in the range
+    /// `0.` (inclusive) to `PI * 2.` (exclusive).
+    ///
+    /// If the paths touch, without crossing each other, the point where they
+    /// touch is returned as a single intersection point.
+    Points(Vec<[Point<1>; 2]>),
+
+    /// The paths are coincident
+    Coincident,
+}
+
+impl PathPathIntersection {
+    fn from_points(mut points: Vec<[Point<1>; 2]>) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+
+        points.sort();
+        Some(Self::Points(points))
+    }
+
+    fn swap(self) -> Self {
+        match self {
+            Self::Points(points) => {
+                let points = points.into_iter().map(|[a, b]| [b, a]).collect();
+                Self::from_points(points)
+                    .expect("Swapping points can't remove them")
+            }
+            Self::Coincident => Self::Coincident,
+        }
+    }
+}
+
+fn line_line(a: &Line<2>, b: &Line<2>) -> Option<PathPathIntersection> {
+    let denominator = a.direction().cross2d(&b.direction());
+
+    if denominator == Scalar::ZERO {
+        // The lines are parallel.
+
+        if a.is_coincident_with(b) {
+            return Some(PathPathIntersection::Coincident);
+        }
+
+        return None;
+    }
+
+    let origin_to_origin = b.origin() - a.origin();
+
+    let t_a = origin_to_origin.cross2d(&b.direction()) / denominator;
+    let t_b = origin_to_origin.cross2d(&a.direction()) / denominator;
+
+    PathPathIntersection::from_points(vec![[t_a, t_b].map(|t| [t].into())])
+}
+
+fn line_circle(
+    line: &Line<2>,
+    circle: &Circle<2>,
+) -> Option<PathPathIntersection> {
+    let w = line.origin() - circle.center();
+    let d = line.direction();
+
+    let a = d.dot(&d);
+    let b = w.dot(&d) * 2.;
+    let c = w.dot(&w) - circle.radius() * circle.radius();
+
+    let discriminant = b * b - a * c * 4.;
+
+    let ts = match discriminant.sign() {
+        Sign::Negative => return None,
+        Sign::Zero => vec![-b / (a * 2.)],
+        Sign::Positive => {
+            let root = Scalar::from_f64(discriminant.into_f64().sqrt());
+            vec![(-b - root) / (a * 2.), (-b + root) / (a * 2.)]
+        }
+    };
+
+    let points = ts
+        .into_iter()
+        .map(|t| {
+            let point = line.point_from_line_coords([t]);
+            [Point::from([t]), circle.point_to_circle_coords(point)]
+        })
+        .collect();
+
+    PathPathIntersection::from_points(points)
+}
+
+fn circle_circle(a: &Circle<2>, b: &Circle<2>) -> Option<PathPathIntersection> {
+    let center_to_center = b.center() - a.center();
+    let distance = center_to_center.magnitude();
+
+    let [r_a, r_b] = [a, b].map(|circle| circle.radius());
+
+    if distance == Scalar::ZERO {
+        if r_a == r_b {
+            return Some(PathPathIntersection::Coincident);
+        }
+
+        // The circles are concentric.
+        return None;
+    }
+    if distance > r_a + r_b || distance < (r_a - r_b).abs() {
+        return None;
+    }
+
+    // Distance from the center of `a` to the line that connects both
+    // intersection points, and half the distance between those points.
+    let along = (r_a * r_a - r_b * r_b + distance * distance) / (distance * 2.);
+    let across =
+        Scalar::from_f64((r_a * r_a - along * along).max(0.).into_f64().sqrt());
+
+    let direction = center_to_center / distance;
+    let normal = Vector::from([-direction.v, direction.u]);
+    let base = a.center() + direction * along;
+
+    let mut points = vec![base + normal * across];
+    if across != Scalar::ZERO {
+        points.push(base - normal * across);
+    }
+
+    let points = points
+        .into_iter()
+        .map(|point| [a, b].map(|circle| circle.point_to_circle_coords(point)))
+        .collect();
+
+    PathPathIntersection::from_points(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use fj_math::Point;
+
+    use crate::{algorithms::intersect::Intersect, geometry::SurfacePath};
+
+    use super::PathPathIntersection;
+
+    #[test]
+    fn line_line() {
+        let (a, _) = SurfacePath::line_from_points([[0., 0.], [1., 0.]]);
+        let (b, _) = SurfacePath::line_from_points([[1., -1.], [1., 1.]]);
+
+        assert_eq!(
+            (&a, &b).intersect(),
+            Some(PathPathIntersection::Points(vec![[
+                Point::from([1.]),
+                Point::from([0.5])
+            ]]))
+        );
+
+        let (parallel, _) = SurfacePath::line_from_points([[0., 1.], [1., 1.]]);
+        assert_eq!((&a, &parallel).intersect(), None);
+
+        let (coincident, _) =
+            SurfacePath::line_from_points([[2., 0.], [3., 0.]]);
+        assert_eq!(
+            (&a, &coincident).intersect(),
+            Some(PathPathIntersection::Coincident)
+        );
+    }
+
+    #[test]
+    fn line_circle() {
+        let (line, _) = SurfacePath::line_from_points([[-2., 0.], [2., 0.]]);
+        let circle = SurfacePath::circle_from_center_and_radius([0., 0.], 1.);
+
+        let expected = vec![
+            [Point::from([0.25]), Point::from([FRAC_PI_2 * 2.])],
+            [Point::from([0.75]), Point::from([0.])],
+        ];
+        assert_eq!(
+            (&line, &circle).intersect(),
+            Some(PathPathIntersection::Points(expected))
+        );
+        assert_eq!(
+            (&circle, &line).intersect(),
+            Some(PathPathIntersection::Points(vec![
+                [Point::from([0.]), Point::from([0.75])],
+                [Point::from([FRAC_PI_2 * 2.]), Point::from([0.25])],
+            ]))
+        );
+
+        let (miss, _) = SurfacePath::line_from_points([[-2., 2.], [2., 2.]]);
+        assert_eq!((&miss, &circle).intersect(), None);
+    }
+
+    #[test]
+    fn circle_circle() {
+        let a = SurfacePath::circle_from_center_and_radius([0., 0.], 1.);
+        let b = SurfacePath::circle_from_center_and_radius([2., 0.], 1.);
+        let c = SurfacePath::circle_from_center_and_radius([3., 0.], 1.);
+
+        assert_eq!(
+            (&a, &b).intersect(),
+            Some(PathPathIntersection::Points(vec![[
+                Point::from([0.]),
+                Point::from([FRAC_PI_2 * 2.])
+            ]]))
+        );
+        assert_eq!((&a, &c).intersect(), None);
+        assert_eq!(
+            (&a, &a).intersect(),
+            Some(PathPathIntersection::Coincident)
+        );
+
+        let d = SurfacePath::circle_from_center_and_radius([1., 0.], 1.);
+        let Some(PathPathIntersection::Points(points)) = (&a, &d).intersect()
+        else {
+            panic!("Expected circles to intersect");
+        };
+        assert_eq!(points.len(), 2);
+        for [t_a, t_d] in points {
+            let p_a = a.point_from_path_coords(t_a);
+            let p_d = d.point_from_path_coords(t_d);
+            assert!(p_a.distance_to(&p_d) < 1e-12.into());
+        }
+    }
+}
@@ -94,9 +94,9 @@ impl Intersect for (&HorizontalRayToTheRight<3>, &Face) {
             // solve those equations for `u` and `v` independently.
             //
             // By doing some math, we get the following solutions:
-            let v = (d1y * (orz - opz) + (opy - ory) * d1z)
-                / (d1y * d2z - d2y * d1z);
-            let u = (ory - opy - d2y * v) / d1y;
+            let denom = d1y * d2z - d2y * d1z;
+            let v = (d1y * (orz - opz) + (opy - ory) * d1z) / denom;
+            let u = ((ory - opy) * d2z - d2y * (orz - opz)) / denom;
             let t = opx - orx + d1x * u + d2x * v;
 
             (t, u, v)
@@ -0,0 +1,651 @@
+//! Intersection between solids and points in 3D
+
+use std::f64::consts::TAU;
+
+use fj_math::{Circle, Plane, Point, Scalar, Sign, Vector};
+
+use crate::{
+    algorithms::path::{
+        is_bounded_by_lines, project_onto_path, winding_number as winding_2d,
+    },
+    geometry::GlobalPath,
+    objects::{Face, HalfEdge, Handedness, Shell, Solid},
+    storage::{Handle, ObjectId},
+    validate::ValidationConfig,
+};
+
+use super::{
+    face_point::FacePointIntersection, ray_face::RayFaceIntersection,
+    HorizontalRayToTheRight, Intersect,
+};
+
+impl Intersect for (&Solid, &Point<3>) {
+    type Intersection = SolidPointIntersection;
+
+    fn intersect(self) -> Option<Self::Intersection> {
+        let (solid, point) = self;
+
+        // Check for the boundary first. If the point is on the boundary of any
+        // shell, that's what we return.
+        for shell in solid.shells() {
+            if let Some(intersection) = intersect_boundary(shell, point) {
+                return Some(intersection);
+            }
+        }
+
+        // A point within a cavity is inside of two shells: the outer one, and
+        // the one that bounds the cavity. By counting the shells, we don't
+        // need to rely on the orientation of the cavity's shell.
+        let num_enclosing_shells = solid
+            .shells()
+            .iter()
+            .filter(|shell| winding_number(shell, point) != 0)
+            .count();
+
+        if num_enclosing_shells % 2 == 1 {
+            Some(SolidPointIntersection::PointIsInsideSolid)
+        } else {
+            None
+        }
+    }
+}
+
+impl Intersect for (&Shell, &Point<3>) {
+    type Intersection = SolidPointIntersection;
+
+    fn intersect(self) -> Option<Self::Intersection> {
+        let (shell, point) = self;
+
+        if let Some(intersection) = intersect_boundary(shell, point) {
+            return Some(intersection);
+        }
+
+        if winding_number(shell, point) != 0 {
+            Some(SolidPointIntersection::PointIsInsideSolid)
+        } else {
+            None
+        }
+    }
+}
+
+/// The intersection between a solid and a point
+///
+/// Can be computed using [`Intersect`], implemented for `(&Solid, &Point<3>)`
+/// and `(&Shell, &Point<3>)`. If the point is outside of the solid, there is no
+/// intersection.
+///
+/// Faces can be on planar surfaces, or on surfaces that are swept circles, like
+/// the walls of a cylinder or a hole. They can be bounded by line segments and
+/// circular arcs.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum SolidPointIntersection {
+    /// The point is inside of the solid
+    PointIsInsideSolid,
+
+    /// The point is on a face of the solid
+    PointIsOnFace(Handle<Face>),
+
+    /// The point is coincident with an edge
+    PointIsOnEdge(Handle<HalfEdge>),
+
+    /// The point is coincident with a vertex
+    PointIsOnVertex(Point<3>),
+}
+
+fn intersect_boundary(
+    shell: &Shell,
+    point: &Point<3>,
+) -> Option<SolidPointIntersection> {
+    let tolerance = ValidationConfig::default().distinct_min_distance;
+
+    for face in shell.faces() {
+        for point_surface in surface_coords(face, point) {
+            let point_projected = face
+                .surface()
+                .geometry()
+                .point_from_surface_coords(point_surface);
+
+            if point.distance_to(&point_projected) >= tolerance {
+                continue;
+            }
+
+            let intersection = match face_point(face, point_surface) {
+                Some(FacePointIntersection::PointIsInsideFace) => {
+                    SolidPointIntersection::PointIsOnFace(face.clone())
+                }
+                Some(FacePointIntersection::PointIsOnEdge(half_edge)) => {
+                    SolidPointIntersection::PointIsOnEdge(half_edge)
+                }
+                Some(FacePointIntersection::PointIsOnVertex(vertex)) => {
+                    SolidPointIntersection::PointIsOnVertex(
+                        face.surface()
+                            .geometry()
+                            .point_from_surface_coords(vertex),
+                    )
+                }
+                None => continue,
+            };
+
+            return Some(intersection);
+        }
+    }
+
+    None
+}
+
+/// Count how often the shell winds around the point
+///
+/// The point must not be on the boundary of the shell.
+///
+/// This casts a ray from the point, and counts the faces it passes through.
+/// Each face counts as `1` or `-1`, depending on whether the ray leaves it
+/// towards the front or the back side.
+///
+/// If the ray hits an edge or a vertex, all faces that share them report a hit.
+/// Those are grouped, and only the overall direction of the group is counted.
+/// If the ray passes through the boundary there, the group counts as one face.
+/// If it only grazes it, the faces cancel each other out.
+fn winding_number(shell: &Shell, point: &Point<3>) -> i32 {
+    let tolerance = ValidationConfig::default().distinct_min_distance;
+    let ray = HorizontalRayToTheRight { origin: *point };
+
+    let mut winding_number = 0;
+    let mut edge_hits: Vec<(ObjectId, Point<3>, i32)> = Vec::new();
+    let mut vertex_hits: Vec<(Point<3>, i32)> = Vec::new();
+
+    for face in shell.faces() {
+        for (hit, position, direction) in ray_face_hits(&ray, face) {
+            match hit {
+                RayFaceIntersection::RayHitsFace => {
+                    winding_number += direction;
+                }
+                RayFaceIntersection::RayHitsFaceAndAreParallel => {
+                    // The ray passes along the face, without passing through
+                    // it. Any faces that it does pass through, next to this
+                    // one, will report their own hits.
+                }
+                RayFaceIntersection::RayHitsEdge(half_edge) => {
+                    // Sibling half-edges share the same curve. That's how we
+                    // recognize the hits on the same edge from different
+                    // faces. A curved edge can be hit in multiple places.
+                    let curve = half_edge.curve().id();
+                    match edge_hits.iter_mut().find(|(other, at, _)| {
+                        *other == curve && at.distance_to(&position) < tolerance
+                    }) {
+                        Some((_, _, sum)) => *sum += direction,
+                        None => {
+                            edge_hits.push((curve, position, direction));
+                        }
+                    }
+                }
+                RayFaceIntersection::RayHitsVertex(vertex) => {
+                    let vertex = face
+                        .surface()
+                        .geometry()
+                        .point_from_surface_coords(vertex);
+
+                    match vertex_hits.iter_mut().find(|(other, _)| {
+                        other.distance_to(&vertex) < tolerance
+                    }) {
+                        Some((_, sum)) => *sum += direction,
+                        None => {
+                            vertex_hits.push((vertex, direction));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let sums_of_groups = edge_hits
+        .iter()
+        .map(|(_, _, sum)| sum)
+        .chain(vertex_hits.iter().map(|(_, sum)| sum));
+    for sum in sums_of_groups {
+        winding_number += sum.signum();
+    }
+
+    winding_number
+}
+
+/// Find the places where the ray passes through a face
+///
+/// Returns each hit, together with its position, and the direction in which
+/// the ray passes through the face there.
+fn ray_face_hits(
+    ray: &HorizontalRayToTheRight<3>,
+    face: &Face,
+) -> Vec<(RayFaceIntersection, Point<3>, i32)> {
+    let direction_at = |point_surface: Point<2>| {
+        sign_to_int(normal(face, point_surface).dot(&ray.direction()))
+    };
+
+    if is_bounded_by_lines(face) {
+        if let SurfaceShape::Plane(plane) = surface_shape(face) {
+            let Some(hit) = (ray, face).intersect() else {
+                return Vec::new();
+            };
+
+            // The position only matters for hits on edges, and those can't
+            // happen if the ray runs parallel to the face.
+            let position = ray_plane_hits(ray, &plane)
+                .into_iter()
+                .next()
+                .unwrap_or(ray.origin);
+            let direction = direction_at(plane.project_point(position));
+
+            return vec![(hit, position, direction)];
+        }
+    }
+
+    let positions = match surface_shape(face) {
+        SurfaceShape::Plane(plane) => {
+            let origin = plane.origin();
+            if plane.is_parallel_to_vector(&ray.direction()) {
+                // If the ray runs within the plane, it passes along the face
+                // without passing through it. Otherwise, it doesn't hit it.
+                let distance = (ray.origin - origin).dot(&plane.normal());
+                if distance == Scalar::ZERO
+                    && face_point(face, plane.project_point(ray.origin))
+                        .is_some()
+                {
+                    return vec![(
+                        RayFaceIntersection::RayHitsFaceAndAreParallel,
+                        ray.origin,
+                        0,
+                    )];
+                }
+
+                return Vec::new();
+            }
+
+            ray_plane_hits(ray, &plane)
+        }
+        SurfaceShape::SweptCircle { circle, path } => {
+            ray_swept_circle_hits(ray, &circle, path)
+        }
+    };
+
+    let mut hits = Vec::new();
+
+    for position in positions {
+        for point_surface in surface_coords(face, &position) {
+            let hit = match face_point(face, point_surface) {
+                Some(FacePointIntersection::PointIsInsideFace) => {
+                    RayFaceIntersection::RayHitsFace
+                }
+                Some(FacePointIntersection::PointIsOnEdge(half_edge)) => {
+                    RayFaceIntersection::RayHitsEdge(half_edge)
+                }
+                Some(FacePointIntersection::PointIsOnVertex(vertex)) => {
+                    RayFaceIntersection::RayHitsVertex(vertex)
+                }
+                None => continue,
+            };
+
+            hits.push((hit, position, direction_at(point_surface)));
+            break;
+        }
+    }
+
+    hits
+}
+
+/// Intersect a face with a point in its surface coordinates
+///
+/// Uses [`FacePointIntersection`], if possible. That only supports faces that
+/// are bounded by line segments, which is why this falls back to a more
+/// general check for other faces.
+fn face_point(face: &Face, point: Point<2>) -> Option<FacePointIntersection> {
+    if is_bounded_by_lines(face) {
+        return (face, &point).intersect();
+    }
+
+    let tolerance = ValidationConfig::default().distinct_min_distance;
+    let surface = face.surface().geometry();
+    let point_global = surface.point_from_surface_coords(point);
+
+    let half_edges = face
+        .region()
+        .all_cycles()
+        .flat_map(|cycle| cycle.half_edges())
+        .collect::<Vec<_>>();
+
+    for half_edge in &half_edges {
+        let vertex = half_edge.start_position();
+        let vertex_global = surface.point_from_surface_coords(vertex);
+        if vertex_global.distance_to(&point_global) < tolerance {
+            return Some(FacePointIntersection::PointIsOnVertex(vertex));
+        }
+    }
+
+    for half_edge in &half_edges {
+        let (_, coord) = project_onto_path(
+            &half_edge.path(),
+            half_edge.boundary().inner,
+            point,
+        );
+        let nearest = surface.point_from_surface_coords(
+            half_edge.path().point_from_path_coords([coord]),
+        );
+        if nearest.distance_to(&point_global) < tolerance {
+            return Some(FacePointIntersection::PointIsOnEdge(
+                (*half_edge).clone(),
+            ));
+        }
+    }
+
+    let winding_number = face
+        .region()
+        .all_cycles()
+        .map(|cycle| {
+            let half_edges =
+                cycle.half_edges().iter().cloned().collect::<Vec<_>>();
+            winding_2d(&half_edges, point)
+        })
+        .sum::<i64>();
+
+    (winding_number != 0).then_some(FacePointIntersection::PointIsInsideFace)
+}
+
+/// The shape of a surface, in the form that is most useful here
+enum SurfaceShape {
+    Plane(Plane),
+    SweptCircle { circle: Circle<3>, path: Vector<3> },
+}
+
+fn surface_shape(face: &Face) -> SurfaceShape {
+    let geometry = face.surface().geometry();
+
+    match geometry.u {
+        GlobalPath::Circle(circle) => SurfaceShape::SweptCircle {
+            circle,
+            path: geometry.v,
+        },
+        GlobalPath::Line(line) => SurfaceShape::Plane(Plane::from_parametric(
+            line.origin(),
+            line.direction(),
+            geometry.v,
+        )),
+    }
+}
+
+/// Compute the surface coordinates of a point on the surface of a face
+///
+/// Points that are not on the surface are mapped to a nearby point on it. The
+/// u-coordinate of a swept circle is only defined up to full turns, so this
+/// returns multiple candidates in that case. Only one of them, if any, is
+/// within the face.
+fn surface_coords(face: &Face, point: &Point<3>) -> Vec<Point<2>> {
+    match surface_shape(face) {
+        SurfaceShape::Plane(plane) => vec![plane.project_point(*point)],
+        SurfaceShape::SweptCircle { circle, path } => {
+            let (v, [x, y]) = swept_circle_coords(&circle, path, *point);
+            let u = y.atan2(x).into_f64().rem_euclid(TAU);
+
+            [u, u - TAU, u + TAU]
+                .map(|u| Point::from([u, v.into_f64()]))
+                .to_vec()
+        }
+    }
+}
+
+/// Decompose a point relative to a swept circle
+///
+/// Moves the point along the path of the sweep, into the plane of the circle.
+/// Returns how far it was moved, in units of the path, and its position in the
+/// plane, in units of the circle's `a` and `b` vectors. The point is on the
+/// surface, if that position is at distance `1` from the origin.
+fn swept_circle_coords(
+    circle: &Circle<3>,
+    path: Vector<3>,
+    point: Point<3>,
+) -> (Scalar, [Scalar; 2]) {
+    let normal = circle.a().cross(&circle.b());
+
+    let v = (point - circle.center()).dot(&normal) / path.dot(&normal);
+    let in_plane = point - path * v - circle.center();
+
+    let [a, b] = [circle.a(), circle.b()];
+    let [x, y] = [a, b].map(|axis| in_plane.dot(&axis) / axis.dot(&axis));
+
+    (v, [x, y])
+}
+
+/// Compute the normal of a face's surface at the provided surface point
+///
+/// The normal points towards the front side of the face.
+fn normal(face: &Face, point: Point<2>) -> Vector<3> {
+    let normal = match surface_shape(face) {
+        SurfaceShape::Plane(plane) => plane.normal(),
+        SurfaceShape::SweptCircle { circle, path } => {
+            let (sin, cos) = point.u.sin_cos();
+            let tangent = circle.b() * cos - circle.a() * sin;
+            tangent.cross(&path)
+        }
+    };
+
+    match face.coord_handedness() {
+        Handedness::RightHanded => normal,
+        Handedness::LeftHanded => -normal,
+    }
+}
+
+fn ray_plane_hits(
+    ray: &HorizontalRayToTheRight<3>,
+    plane: &Plane,
+) -> Vec<Point<3>> {
+    let direction = ray.direction();
+    let normal = plane.normal();
+
+    let denominator = direction.dot(&normal);
+    if denominator == Scalar::ZERO {
+        return Vec::new();
+    }
+
+    let t = (plane.origin() - ray.origin).dot(&normal) / denominator;
+    if t < Scalar::ZERO {
+        return Vec::new();
+    }
+
+    vec![ray.origin + direction * t]
+}
+
+fn ray_swept_circle_hits(
+    ray: &HorizontalRayToTheRight<3>,
+    circle: &Circle<3>,
+    path: Vector<3>,
+) -> Vec<Point<3>> {
+    // In the coordinates returned by `swept_circle_coords`, the ray is a line,
+    // and the surface is the unit circle. Points on the ray are on the surface,
+    // where `|origin + direction * t| = 1`.
+    let (_, origin) = swept_circle_coords(circle, path, ray.origin);
+    let (_, end) =
+        swept_circle_coords(circle, path, ray.origin + ray.direction());
+    let direction = [end[0] - origin[0], end[1] - origin[1]];
+
+    let a = direction[0] * direction[0] + direction[1] * direction[1];
+    let b = (origin[0] * direction[0] + origin[1] * direction[1]) * 2.;
+    let c = origin[0] * origin[0] + origin[1] * origin[1] - 1.;
+
+    // If the ray runs along the path, or only touches the surface, it doesn't
+    // pass through it.
+    let discriminant = b * b - a * c * 4.;
+    if a == Scalar::ZERO || discriminant <= Scalar::ZERO {
+        return Vec::new();
+    }
+
+    let root = Scalar::from_f64(discriminant.into_f64().sqrt());
+    [(-b - root) / (a * 2.), (-b + root) / (a * 2.)]
+        .into_iter()
+        .filter(|t| *t >= Scalar::ZERO)
+        .map(|t| ray.origin + ray.direction() * t)
+        .collect()
+}
+
+fn sign_to_int(scalar: Scalar) -> i32 {
+    match scalar.sign() {
+        Sign::Negative => -1,
+        Sign::Zero => 0,
+        Sign::Positive => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        algorithms::intersect::Intersect,
+        geometry::GlobalPath,
+        objects::{Cycle, Region, Sketch, Solid},
+        operations::{
+            build::{
+                BuildCycle, BuildRegion, BuildSketch, BuildSolid, Tetrahedron,
+            },
+            reverse::Reverse,
+            sweep::SweepSketch,
+            update::{UpdateRegion, UpdateSketch},
+        },
+        Instance,
+    };
+
+    use super::SolidPointIntersection;
+
+    fn tetrahedron(core: &mut Instance) -> Tetrahedron {
+        Solid::tetrahedron(
+            [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]],
+            core,
+        )
+    }
+
+    #[test]
+    fn point_inside_and_outside() {
+        let mut core = Instance::new();
+        let Tetrahedron { solid, .. } = tetrahedron(&mut core);
+
+        assert_eq!(
+            (&solid, &Point::from([0.1, 0.1, 0.1])).intersect(),
+            Some(SolidPointIntersection::PointIsInsideSolid)
+        );
+        assert_eq!((&solid, &Point::from([1., 1., 1.])).intersect(), None);
+        assert_eq!((&solid, &Point::from([-1., 0.25, 0.25])).intersect(), None);
+    }
+
+    #[test]
+    fn ray_grazes_edge() {
+        let mut core = Instance::new();
+        let Tetrahedron { solid, .. } = tetrahedron(&mut core);
+
+        // The ray passes the edge between two faces, without entering.
+        assert_eq!((&solid, &Point::from([-1., 0.5, 0.5])).intersect(), None);
+    }
+
+    #[test]
+    fn point_on_boundary() {
+        let mut core = Instance::new();
+        let Tetrahedron { solid, shell } = tetrahedron(&mut core);
+
+        let on_face = (&solid, &Point::from([0.25, 0.25, 0.])).intersect();
+        assert!(matches!(
+            on_face,
+            Some(SolidPointIntersection::PointIsOnFace(face))
+                if face == shell.abc.face
+        ));
+
+        let on_edge = (&solid, &Point::from([0.5, 0., 0.])).intersect();
+        assert!(matches!(
+            on_edge,
+            Some(SolidPointIntersection::PointIsOnEdge(_))
+        ));
+
+        assert_eq!(
+            (&solid, &Point::from([0., 0., 1.])).intersect(),
+            Some(SolidPointIntersection::PointIsOnVertex(Point::from([
+                0., 0., 1.
+            ])))
+        );
+    }
+
+    #[test]
+    fn swept_circle() {
+        let mut core = Instance::new();
+
+        let surface = core.services.objects.surfaces.xy_plane();
+        let solid = Sketch::empty()
+            .add_regions(
+                [Region::circle(Point::from([0., 0.]), 1., &mut core)],
+                &mut core,
+            )
+            .sweep_sketch(surface, [0., 0., 2.], &mut core);
+
+        // The ray from the center passes through the seam of the curved face.
+        for point in [[0., 0., 1.], [0.5, -0.3, 1.5], [-0.9, 0., 0.1]] {
+            assert_eq!(
+                (&solid, &Point::from(point)).intersect(),
+                Some(SolidPointIntersection::PointIsInsideSolid),
+                "{point:?}"
+            );
+        }
+
+        // The rays from these points touch the curved face, or run along the
+        // top face, without entering the solid.
+        for point in [[2., 0., 1.], [0., 0., 3.], [0.9, 0.9, 1.], [-2., 1., 1.]]
+        {
+            assert_eq!((&solid, &Point::from(point)).intersect(), None);
+        }
+        assert_eq!((&solid, &Point::from([-2., 0., 2.])).intersect(), None);
+
+        let on_curved_face = (&solid, &Point::from([0., 1., 1.])).intersect();
+        assert!(matches!(
+            on_curved_face,
+            Some(SolidPointIntersection::PointIsOnFace(face))
+                if matches!(face.surface().geometry().u, GlobalPath::Circle(_))
+        ));
+
+        let on_top_face = (&solid, &Point::from([0.5, 0., 2.])).intersect();
+        assert!(matches!(
+            on_top_face,
+            Some(SolidPointIntersection::PointIsOnFace(_))
+        ));
+
+        let on_edge = (&solid, &Point::from([0., -1., 2.])).intersect();
+        assert!(matches!(
+            on_edge,
+            Some(SolidPointIntersection::PointIsOnEdge(_))
+        ));
+    }
+
+    #[test]
+    fn solid_with_hole() {
+        let mut core = Instance::new();
+
+        let surface = core.services.objects.surfaces.xy_plane();
+        let hole = Cycle::circle([0., 0.], 1., &mut core).reverse(&mut core);
+        let solid = Sketch::empty()
+            .add_regions(
+                [Region::polygon(
+                    [[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]],
+                    &mut core,
+                )
+                .add_interiors([hole], &mut core)],
+                &mut core,
+            )
+            .sweep_sketch(surface, [0., 0., 1.], &mut core);
+
+        assert_eq!((&solid, &Point::from([0., 0., 0.5])).intersect(), None);
+        assert_eq!(
+            (&solid, &Point::from([1.5, 0., 0.5])).intersect(),
+            Some(SolidPointIntersection::PointIsInsideSolid)
+        );
+        assert_eq!(
+            (&solid, &Point::from([-1.5, 0.5, 0.5])).intersect(),
+            Some(SolidPointIntersection::PointIsInsideSolid)
+        );
+        assert!(matches!(
+            (&solid, &Point::from([-1., 0., 0.5])).intersect(),
+            Some(SolidPointIntersection::PointIsOnFace(_))
+        ));
+    }
+}
@@ -0,0 +1,563 @@
+//! Compute mass properties of objects
+//!
+//! See [`MassProperties`].
+
+use fj_interop::Mesh;
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{Face, HalfEdge, Handedness, Shell, Solid},
+};
+
+/// Compute the mass properties of an object
+///
+/// All mass properties assume a uniform density of `1`. Multiply volume and
+/// inertia tensor by the actual density, to get the mass and the actual inertia
+/// tensor.
+///
+/// # Implementation Note
+///
+/// Faces, shells, and solids are integrated over their exact geometry, without
+/// approximating them first. The result is exact for planar faces that are
+/// bounded by line segments. Curved surfaces and circular edges are integrated
+/// using numerical quadrature, which is accurate to within floating point
+/// precision.
+///
+/// To compute the mass properties of a triangulated approximation instead, use
+/// the implementation for [`Mesh`]. The mesh of an object can be computed at a
+/// given tolerance, using [`Triangulate`].
+///
+/// [`Triangulate`]: crate::algorithms::triangulate::Triangulate
+pub trait MassProperties {
+    /// Compute the mass properties of the object
+    fn mass_properties(&self) -> PhysicalProperties;
+}
+
+impl MassProperties for Face {
+    fn mass_properties(&self) -> PhysicalProperties {
+        PhysicalProperties::from_area(integrate_face(self))
+    }
+}
+
+impl MassProperties for Shell {
+    fn mass_properties(&self) -> PhysicalProperties {
+        PhysicalProperties::from_volume(integrate_shell(self))
+    }
+}
+
+impl MassProperties for Solid {
+    fn mass_properties(&self) -> PhysicalProperties {
+        let mut integrals = Integrals::default();
+        for shell in self.shells() {
+            integrals.add(&integrate_shell(shell), Scalar::ONE);
+        }
+
+        PhysicalProperties::from_volume(integrals)
+    }
+}
+
+impl MassProperties for Mesh<Point<3>> {
+    fn mass_properties(&self) -> PhysicalProperties {
+        let mut integrals = Integrals::default();
+
+        for triangle in self.triangles() {
+            let [a, b, c] = triangle.inner.points().map(|point| point.coords);
+            let sum = a + b + c;
+
+            let area = (b - a).cross(&(c - a)).magnitude() / 2.;
+            let det = a.dot(&b.cross(&c));
+
+            // Second moments of a triangle and of the tetrahedron that it forms
+            // with the origin. Both follow the same pattern.
+            let mut second = [[Scalar::ZERO; 3]; 3];
+            for vector in [a, b, c, sum] {
+                add_outer_product(&mut second, vector, Scalar::ONE);
+            }
+
+            let triangle = Integrals {
+                area,
+                area_first: sum * area / 3.,
+                area_second: second.map(|row| row.map(|s| s * area / 12.)),
+                volume: det / 6.,
+                volume_first: sum * det / 24.,
+                volume_second: second.map(|row| row.map(|s| s * det / 120.)),
+            };
+
+            integrals.add(&triangle, Scalar::ONE);
+        }
+
+        PhysicalProperties::from_volume(integrals)
+    }
+}
+
+/// The mass properties of an object
+///
+/// Computed by [`MassProperties`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PhysicalProperties {
+    /// The volume of the object
+    ///
+    /// This is `0` for faces. For shells that are not closed, the value is
+    /// meaningless.
+    pub volume: Scalar,
+
+    /// The surface area of the object
+    pub area: Scalar,
+
+    /// The center of mass of the object
+    ///
+    /// This is the centroid of the volume, unless the object has no volume
+    /// (like a face). Then it is the centroid of its surface area.
+    pub centroid: Point<3>,
+
+    /// The inertia tensor, relative to the center of mass
+    ///
+    /// Like the center of mass, this is computed from the volume, unless the
+    /// object has no volume. Then the object is treated like a thin sheet,
+    /// with the mass being its surface area.
+    pub inertia: [[Scalar; 3]; 3],
+}
+
+impl PhysicalProperties {
+    fn from_volume(integrals: Integrals) -> Self {
+        if integrals.volume == Scalar::ZERO {
+            return Self::from_area(integrals);
+        }
+
+        let (centroid, inertia) = centroid_and_inertia(
+            integrals.volume,
+            integrals.volume_first,
+            integrals.volume_second,
+        );
+
+        Self {
+            volume: integrals.volume,
+            area: integrals.area,
+            centroid,
+            inertia,
+        }
+    }
+
+    fn from_area(integrals: Integrals) -> Self {
+        let (centroid, inertia) = if integrals.area == Scalar::ZERO {
+            (Point::origin(), [[Scalar::ZERO; 3]; 3])
+        } else {
+            centroid_and_inertia(
+                integrals.area,
+                integrals.area_first,
+                integrals.area_second,
+            )
+        };
+
+        Self {
+            volume: Scalar::ZERO,
+            area: integrals.area,
+            centroid,
+            inertia,
+        }
+    }
+}
+
+fn centroid_and_inertia(
+    mass: Scalar,
+    first: Vector<3>,
+    second: [[Scalar; 3]; 3],
+) -> (Point<3>, [[Scalar; 3]; 3]) {
+    let centroid = first / mass;
+
+    // Move the second moments from the origin to the centroid.
+    let mut second = second;
+    add_outer_product(&mut second, centroid, -mass);
+
+    let trace = second[0][0] + second[1][1] + second[2][2];
+
+    let mut inertia = second.map(|row| row.map(|s| -s));
+    for (i, row) in inertia.iter_mut().enumerate() {
+        row[i] += trace;
+    }
+
+    (Point::origin() + centroid, inertia)
+}
+
+fn add_outer_product(
+    matrix: &mut [[Scalar; 3]; 3],
+    vector: Vector<3>,
+    factor: Scalar,
+) {
+    for (i, row) in matrix.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value += vector.components[i] * vector.components[j] * factor;
+        }
+    }
+}
+
+/// Integrals over the surface and the enclosed volume of an object
+///
+/// All integrals are relative to the origin. The volume integrals are computed
+/// from the surface, using the divergence theorem. They only make sense for
+/// closed surfaces.
+#[derive(Clone, Copy, Default)]
+struct Integrals {
+    area: Scalar,
+    area_first: Vector<3>,
+    area_second: [[Scalar; 3]; 3],
+    volume: Scalar,
+    volume_first: Vector<3>,
+    volume_second: [[Scalar; 3]; 3],
+}
+
+impl Integrals {
+    /// Compute the integrands at a point on a surface
+    ///
+    /// `normal` is the cross product of the surface's partial derivatives. Its
+    /// magnitude is the area element of the surface at that point.
+    fn integrands(point: Point<3>, normal: Vector<3>) -> Self {
+        let p = point.coords.components;
+        let n = normal.components;
+
+        let area = normal.magnitude();
+
+        let mut area_second = [[Scalar::ZERO; 3]; 3];
+        add_outer_product(&mut area_second, point.coords, area);
+
+        // For each of the volume integrals, we need a vector field whose
+        // divergence is the integrand. Integrating the flux of that field
+        // through the surface, gives us the integral over the volume.
+        let volume = point.coords.dot(&normal) / 3.;
+        let volume_first =
+            Vector::from([0, 1, 2].map(|i| p[i] * p[i] * n[i] / 2.));
+
+        let mut volume_second = [[Scalar::ZERO; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                volume_second[i][j] = if i == j {
+                    p[i] * p[i] * p[i] * n[i] / 3.
+                } else {
+                    p[i] * p[i] * p[j] * n[i] / 2.
+                };
+            }
+        }
+
+        Self {
+            area,
+            area_first: point.coords * area,
+            area_second,
+            volume,
+            volume_first,
+            volume_second,
+        }
+    }
+
+    fn add(&mut self, other: &Self, factor: Scalar) {
+        self.area += other.area * factor;
+        self.area_first = self.area_first + other.area_first * factor;
+        self.volume += other.volume * factor;
+        self.volume_first = self.volume_first + other.volume_first * factor;
+
+        for i in 0..3 {
+            for j in 0..3 {
+                self.area_second[i][j] += other.area_second[i][j] * factor;
+                self.volume_second[i][j] += other.volume_second[i][j] * factor;
+            }
+        }
+    }
+}
+
+fn integrate_shell(shell: &Shell) -> Integrals {
+    let mut integrals = Integrals::default();
+    for face in shell.faces() {
+        integrals.add(&integrate_face(face), Scalar::ONE);
+    }
+
+    integrals
+}
+
+/// Integrate over a face
+///
+/// All surfaces are linear along their v-axis. This makes it possible to turn
+/// the integral over the face into an integral along its boundary (using
+/// Green's theorem), which can be computed numerically without approximating
+/// the face.
+///
+/// For an integrand `h(u, v)`, we integrate `-Q(u, v) du` along the boundary,
+/// where `Q(u, v)` is the integral of `h(u, s)` from `s = 0` to `s = v`. Since
+/// the integrands are polynomials in `v`, `Q` is computed exactly.
+fn integrate_face(face: &Face) -> Integrals {
+    let surface = face.surface().geometry();
+
+    let mut integrals = Integrals::default();
+
+    for half_edge in face
+        .region()
+        .all_cycles()
+        .flat_map(|cycle| cycle.half_edges())
+    {
+        let [start, end] = half_edge.boundary().inner.map(|point| point.t);
+
+        let num_segments = num_segments(half_edge, &surface);
+        let segment_length = (end - start) / Scalar::from_u64(num_segments);
+
+        for i in 0..num_segments {
+            let segment_start = start + segment_length * Scalar::from_u64(i);
+            let half_length = segment_length / 2.;
+
+            for (node, weight) in GAUSS_LEGENDRE {
+                let t = segment_start + half_length * (node + 1.);
+
+                let point = half_edge.path().point_from_path_coords([t]);
+                let du = path_tangent(&half_edge.path(), t).u;
+
+                let mut q = Integrals::default();
+                for (node, weight) in GAUSS_LEGENDRE {
+                    let s = point.v * (node + 1.) / 2.;
+                    q.add(
+                        &integrands_on_surface(&surface, point.u, s),
+                        point.v * weight / 2.,
+                    );
+                }
+
+                integrals.add(&q, -half_length * weight * du);
+            }
+        }
+    }
+
+    // The boundary of a face with a left-handed coordinate system winds the
+    // other way, which flips the sign of all integrals. That is correct for
+    // the volume integrals, as the normal of the face is flipped too. The area
+    // integrals need to stay positive.
+    if face.coord_handedness() == Handedness::LeftHanded {
+        integrals.area = -integrals.area;
+        integrals.area_first = -integrals.area_first;
+        integrals.area_second =
+            integrals.area_second.map(|row| row.map(|s| -s));
+    }
+
+    integrals
+}
+
+fn integrands_on_surface(
+    surface: &SurfaceGeometry,
+    u: Scalar,
+    v: Scalar,
+) -> Integrals {
+    let point = surface.point_from_surface_coords([u, v]);
+
+    let du = match surface.u {
+        GlobalPath::Circle(circle) => {
+            let (sin, cos) = u.sin_cos();
+            circle.b() * cos - circle.a() * sin
+        }
+        GlobalPath::Line(line) => line.direction(),
+    };
+    let normal = du.cross(&surface.v);
+
+    Integrals::integrands(point, normal)
+}
+
+fn path_tangent(path: &SurfacePath, t: Scalar) -> Vector<2> {
+    match path {
+        SurfacePath::Circle(circle) => {
+            let (sin, cos) = t.sin_cos();
+            circle.b() * cos - circle.a() * sin
+        }
+        SurfacePath::Line(line) => line.direction(),
+    }
+}
+
+/// Number of segments to split a half-edge into, for numerical integration
+///
+/// The integrands are polynomials along straight edges on planar surfaces, and
+/// those are integrated exactly. Wherever circles are involved, the quadrature
+/// rule is accurate to within floating point precision, as long as each
+/// segment covers no more than an eighth of a circle.
+fn num_segments(half_edge: &HalfEdge, surface: &SurfaceGeometry) -> u64 {
+    let [start, end] = half_edge.boundary().inner.map(|point| point.t);
+    let range = (end - start).abs();
+
+    let (angle_along_path, length_along_path) = match half_edge.path() {
+        SurfacePath::Circle(circle) => (range, range * circle.radius()),
+        SurfacePath::Line(line) => {
+            (Scalar::ZERO, range * line.direction().magnitude())
+        }
+    };
+    let angle_along_surface = match surface.u {
+        GlobalPath::Circle(_) => length_along_path,
+        GlobalPath::Line(_) => Scalar::ZERO,
+    };
+
+    let angle = angle_along_path + angle_along_surface;
+    let max_angle = Scalar::PI / 4.;
+
+    let num_segments = (angle / max_angle).ceil().into_f64() as u64;
+    num_segments.max(1)
+}
+
+/// Nodes and weights of the 8-point Gauss-Legendre quadrature rule
+///
+/// The nodes are within `-1` and `1`. The rule is exact for polynomials up to
+/// degree 15.
+const GAUSS_LEGENDRE: [(f64, f64); 8] = [
+    (-0.960_289_856_497_536_3, 0.101_228_536_290_376_26),
+    (-0.796_666_477_413_626_7, 0.222_381_034_453_374_47),
+    (-0.525_532_409_916_329, 0.313_706_645_877_887_3),
+    (-0.183_434_642_495_649_8, 0.362_683_783_378_362),
+    (0.183_434_642_495_649_8, 0.362_683_783_378_362),
+    (0.525_532_409_916_329, 0.313_706_645_877_887_3),
+    (0.796_666_477_413_626_7, 0.222_381_034_453_374_47),
+    (0.960_289_856_497_536_3, 0.101_228_536_290_376_26),
+];
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        algorithms::{
+            approx::Tolerance, mass_properties::PhysicalProperties,
+            triangulate::Triangulate,
+        },
+        objects::{Region, Sketch, Solid},
+        operations::{
+            build::{BuildRegion, BuildSketch, BuildSolid, Tetrahedron},
+            insert::Insert,
+            sweep::SweepSketch,
+            update::{UpdateSketch, UpdateSolid},
+        },
+        Instance,
+    };
+
+    use super::MassProperties;
+
+    #[test]
+    fn tetrahedron() {
+        let mut core = Instance::new();
+
+        let Tetrahedron { solid, shell } = Solid::tetrahedron(
+            [[0., 0., 0.], [0., 1., 0.], [1., 0., 0.], [0., 0., 1.]],
+            &mut core,
+        );
+
+        let properties = solid.mass_properties();
+        assert_approx_eq(properties.volume, 1. / 6.);
+        assert_approx_eq(
+            properties.area,
+            Scalar::from_f64(1.5 + 3f64.sqrt() / 2.),
+        );
+        assert_point_approx_eq(properties.centroid, [0.25, 0.25, 0.25]);
+
+        // Inertia tensor of the unit tetrahedron, relative to its centroid.
+        let diagonal = 1. / 80.;
+        let off_diagonal = 1. / 480.;
+        assert_inertia_approx_eq(
+            properties.inertia,
+            [
+                [diagonal, off_diagonal, off_diagonal],
+                [off_diagonal, diagonal, off_diagonal],
+                [off_diagonal, off_diagonal, diagonal],
+            ],
+        );
+
+        let face = shell.abc.face.mass_properties();
+        assert_approx_eq(face.volume, 0.);
+        assert_approx_eq(face.area, 0.5);
+        assert_point_approx_eq(face.centroid, [1. / 3., 1. / 3., 0.]);
+
+        let mesh = (&solid, Tolerance::from_scalar(0.001).unwrap())
+            .triangulate()
+            .mass_properties();
+        assert_properties_approx_eq(mesh, properties);
+    }
+
+    #[test]
+    fn tetrahedron_with_cavity() {
+        let mut core = Instance::new();
+
+        let outer = Solid::tetrahedron(
+            [[0., 0., 0.], [0., 2., 0.], [2., 0., 0.], [0., 0., 2.]],
+            &mut core,
+        );
+        // The faces of the cavity point into the cavity, away from the
+        // material. That's the opposite order from the outer tetrahedron.
+        let inner = Solid::tetrahedron(
+            [
+                [0.1, 0.1, 0.1],
+                [0.2, 0.1, 0.1],
+                [0.1, 0.2, 0.1],
+                [0.1, 0.1, 0.2],
+            ],
+            &mut core,
+        );
+
+        let solid = outer
+            .solid
+            .add_shells([inner.shell.shell], &mut core)
+            .insert(&mut core);
+
+        assert_approx_eq(solid.mass_properties().volume, 8. / 6. - 0.001 / 6.);
+    }
+
+    #[test]
+    fn cylinder() {
+        let mut core = Instance::new();
+
+        let bottom_surface = core.services.objects.surfaces.xy_plane();
+        let solid = Sketch::empty()
+            .add_regions(
+                [Region::circle(Point::from([1., 2.]), 1., &mut core)],
+                &mut core,
+            )
+            .sweep_sketch(bottom_surface, [0., 0., 2.], &mut core);
+
+        let properties = solid.mass_properties();
+        assert_approx_eq(properties.volume, Scalar::PI * 2.);
+        assert_approx_eq(properties.area, Scalar::PI * 6.);
+        assert_point_approx_eq(properties.centroid, [1., 2., 1.]);
+
+        let mass = Scalar::PI * 2.;
+        let radial = mass * 7. / 12.;
+        let axial = mass / 2.;
+        assert_inertia_approx_eq(
+            properties.inertia,
+            [
+                [radial, Scalar::ZERO, Scalar::ZERO],
+                [Scalar::ZERO, radial, Scalar::ZERO],
+                [Scalar::ZERO, Scalar::ZERO, axial],
+            ],
+        );
+    }
+
+    fn assert_properties_approx_eq(
+        a: PhysicalProperties,
+        b: PhysicalProperties,
+    ) {
+        assert_approx_eq(a.volume, b.volume);
+        assert_approx_eq(a.area, b.area);
+        assert_point_approx_eq(a.centroid, b.centroid);
+        assert_inertia_approx_eq(a.inertia, b.inertia);
+    }
+
+    fn assert_inertia_approx_eq(
+        a: [[Scalar; 3]; 3],
+        b: [[impl Into<Scalar> + Copy; 3]; 3],
+    ) {
+        for (a, b) in a.into_iter().zip(b) {
+            for (a, b) in a.into_iter().zip(b) {
+                assert_approx_eq(a, b);
+            }
+        }
+    }
+
+    fn assert_point_approx_eq(a: Point<3>, b: impl Into<Point<3>>) {
+        let b = b.into();
+        assert!(
+            a.distance_to(&b) < Scalar::from_f64(1e-12),
+            "{a:?} != {b:?}"
+        );
+    }
+
+    fn assert_approx_eq(a: Scalar, b: impl Into<Scalar>) {
+        let b = b.into();
+        assert!((a - b).abs() < Scalar::from_f64(1e-12), "{a:?} != {b:?}");
+    }
+}
@@ -12,5 +12,10 @@
 
 pub mod approx;
 pub mod bounding_volume;
+pub mod constraints;
+pub mod distance;
+pub mod interference;
 pub mod intersect;
+pub mod mass_properties;
+pub(crate) mod path;
 pub mod triangulate;
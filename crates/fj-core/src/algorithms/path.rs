@@ -0,0 +1,460 @@
+//! Geometric helpers for paths in surface coordinates
+//!
+//! Used by operations that need to divide paths, or trace and classify cycles
+//! of half-edges, like splitting faces, boolean operations, and offsetting.
+
+use std::f64::consts::TAU;
+
+use fj_math::{Point, Scalar, Vector};
+use itertools::Itertools;
+
+use crate::{
+    geometry::SurfacePath,
+    objects::{Cycle, Face, HalfEdge, Handedness},
+    storage::Handle,
+};
+
+/// Divide a path into pieces, at the provided coordinates
+pub(crate) fn divide_path(
+    path: &SurfacePath,
+    boundary: Option<[Point<1>; 2]>,
+    mut coords: Vec<Scalar>,
+    tolerance: Scalar,
+) -> Vec<[Scalar; 2]> {
+    let is_circle = matches!(path, SurfacePath::Circle(_));
+
+    let (min, max) = match boundary {
+        Some([a, b]) => (a.t.min(b.t), a.t.max(b.t)),
+        None => (Scalar::ZERO, Scalar::TAU),
+    };
+    let is_closed = is_circle && max - min >= Scalar::TAU;
+
+    if is_circle {
+        for coord in &mut coords {
+            *coord = min + (*coord - min).into_f64().rem_euclid(TAU);
+        }
+    }
+    if boundary.is_some() {
+        coords.retain(|coord| *coord >= min && *coord <= max);
+
+        if !is_closed {
+            coords.extend([min, max]);
+        }
+    }
+
+    coords.sort();
+    coords.dedup_by(|a, b| {
+        let [a, b] = [a, b].map(|coord| path.point_from_path_coords([*coord]));
+        a.distance_to(&b) < tolerance
+    });
+
+    if is_closed {
+        if let (Some(&first), Some(&last)) = (coords.first(), coords.last()) {
+            let [first, last] = [first + Scalar::TAU, last]
+                .map(|coord| path.point_from_path_coords([coord]));
+            if coords.len() > 1 && first.distance_to(&last) < tolerance {
+                coords.pop();
+            }
+        }
+        if coords.is_empty() {
+            coords.push(min);
+        }
+
+        let mut pieces = coords
+            .iter()
+            .tuple_windows()
+            .map(|(&a, &b)| [a, b])
+            .collect::<Vec<_>>();
+        pieces.push([coords[coords.len() - 1], coords[0] + Scalar::TAU]);
+
+        return pieces;
+    }
+
+    coords
+        .iter()
+        .tuple_windows()
+        .map(|(&a, &b)| [a, b])
+        .collect()
+}
+
+/// Project a point onto a bounded path
+///
+/// Returns the distance of the point from the bounded path, and the coordinate
+/// of the nearest point on the path.
+pub(crate) fn project_onto_path(
+    path: &SurfacePath,
+    boundary: [Point<1>; 2],
+    point: Point<2>,
+) -> (Scalar, Scalar) {
+    let [a, b] = boundary.map(|point| point.t);
+    let (min, max) = (a.min(b), a.max(b));
+
+    let coord = match path {
+        SurfacePath::Circle(circle) => {
+            let coord = circle.point_to_circle_coords(point).t;
+            let coord = min + (coord - min).into_f64().rem_euclid(TAU);
+
+            if coord <= max {
+                coord
+            } else {
+                // The point is closer to one of the ends.
+                let [distance_a, distance_b] = [a, b].map(|coord| {
+                    circle.point_from_circle_coords([coord]).distance_to(&point)
+                });
+                if distance_a < distance_b {
+                    a
+                } else {
+                    b
+                }
+            }
+        }
+        SurfacePath::Line(line) => {
+            line.point_to_line_coords(point).t.clamp(min, max)
+        }
+    };
+
+    let distance = path.point_from_path_coords([coord]).distance_to(&point);
+    (distance, coord)
+}
+
+/// Convert a point into path coordinates
+pub(crate) fn point_to_path_coords(
+    path: &SurfacePath,
+    point: Point<2>,
+) -> Scalar {
+    match path {
+        SurfacePath::Circle(circle) => circle.point_to_circle_coords(point).t,
+        SurfacePath::Line(line) => line.point_to_line_coords(point).t,
+    }
+}
+
+/// Compute the direction of a half-edge at its start or end
+fn tangent(half_edge: &HalfEdge, at_end: bool) -> Vector<2> {
+    let [start, end] = half_edge.boundary().inner;
+    let coord = if at_end { end } else { start };
+
+    let direction = path_direction(&half_edge.path(), coord.t);
+
+    if end < start {
+        -direction
+    } else {
+        direction
+    }
+}
+
+/// Compute the direction of a path at the provided coordinate
+///
+/// This is the direction in which the path coordinate increases.
+pub(crate) fn path_direction(path: &SurfacePath, coord: Scalar) -> Vector<2> {
+    match path {
+        SurfacePath::Circle(circle) => {
+            let (sin, cos) = coord.sin_cos();
+            circle.b() * cos - circle.a() * sin
+        }
+        SurfacePath::Line(line) => line.direction(),
+    }
+}
+
+/// Compute the signed curvature of a half-edge
+///
+/// The curvature is positive, if the half-edge turns counter-clockwise.
+fn curvature(half_edge: &HalfEdge) -> Scalar {
+    match half_edge.path() {
+        SurfacePath::Circle(circle) => {
+            let [start, end] = half_edge.boundary().inner.map(|point| point.t);
+            let curvature = Scalar::ONE / circle.radius();
+
+            if (end - start) * circle.a().cross2d(&circle.b()) > Scalar::ZERO {
+                curvature
+            } else {
+                -curvature
+            }
+        }
+        SurfacePath::Line(_) => Scalar::ZERO,
+    }
+}
+
+/// Determine whether all half-edges of a face are line segments
+pub(crate) fn is_bounded_by_lines(face: &Face) -> bool {
+    face.region()
+        .all_cycles()
+        .flat_map(|cycle| cycle.half_edges())
+        .all(|half_edge| matches!(half_edge.path(), SurfacePath::Line(_)))
+}
+
+/// Angles that differ by less than this are considered to be the same
+pub(crate) const TURN_EPSILON: f64 = 1e-9;
+
+/// Determine how sharply the boundary of the face turns towards the face
+///
+/// The turn is defined by an incoming half-edge, and an outgoing one. Returns
+/// the angle between the incoming half-edge, reversed, and the outgoing one. If
+/// the half-edges leave the vertex in the same direction, the second value
+/// tells them apart. Lower values mean sharper turns.
+pub(crate) fn turn(
+    incoming: &HalfEdge,
+    outgoing: &HalfEdge,
+    handedness: Handedness,
+) -> [Scalar; 2] {
+    let [back, forward] = [-tangent(incoming, true), tangent(outgoing, false)]
+        .map(|vector| vector.v.atan2(vector.u));
+    let [back_curvature, forward_curvature] =
+        [-curvature(incoming), curvature(outgoing)];
+
+    let (angle, bend) = match handedness {
+        Handedness::RightHanded => {
+            (back - forward, back_curvature - forward_curvature)
+        }
+        Handedness::LeftHanded => {
+            (forward - back, forward_curvature - back_curvature)
+        }
+    };
+    let angle = angle.into_f64().rem_euclid(TAU);
+
+    // An outgoing half-edge that leaves in the direction we came from is the
+    // sharpest turn, if it bends towards the face. Otherwise, including if it
+    // is the sibling of the incoming half-edge, it is the last option.
+    if !(TURN_EPSILON..=TAU - TURN_EPSILON).contains(&angle) {
+        if bend > Scalar::ZERO {
+            return [Scalar::ZERO, bend];
+        }
+
+        return [Scalar::TAU, bend];
+    }
+
+    [Scalar::from_f64(angle), bend]
+}
+
+/// Compute the winding number of a cycle of half-edges around a point
+///
+/// The point must not be on the cycle.
+pub(crate) fn winding_number(
+    half_edges: &[Handle<HalfEdge>],
+    point: Point<2>,
+) -> i64 {
+    let mut angle = Scalar::ZERO;
+
+    let angle_of_chord = |a: Point<2>, b: Point<2>| {
+        let [a, b] = [a, b].map(|p| p - point);
+        a.cross2d(&b).atan2(a.dot(&b))
+    };
+
+    for half_edge in half_edges {
+        let [start, end] = half_edge.boundary().inner.map(|point| point.t);
+
+        match half_edge.path() {
+            SurfacePath::Circle(circle) => {
+                // Divide the arc into parts that are small enough, that the
+                // point can't be both inside the circle and on the far side of
+                // its center, as seen from the chord.
+                let num_parts = ((end - start).abs() / (Scalar::PI / 2.))
+                    .ceil()
+                    .max(1.)
+                    .into_u64();
+
+                for i in 0..num_parts {
+                    let [a, b] = [i, i + 1].map(|j| {
+                        start
+                            + (end - start) * Scalar::from_u64(j)
+                                / Scalar::from_u64(num_parts)
+                    });
+                    let [p_a, p_b] = [a, b]
+                        .map(|coord| circle.point_from_circle_coords([coord]));
+
+                    angle += angle_of_chord(p_a, p_b);
+
+                    // If the point is between the arc and its chord, the arc
+                    // passes it on the other side.
+                    let chord = p_b - p_a;
+                    let is_inside_circle =
+                        point.distance_to(&circle.center()) < circle.radius();
+                    let is_beyond_chord = chord.cross2d(&(point - p_a))
+                        * chord.cross2d(&(circle.center() - p_a))
+                        < Scalar::ZERO;
+
+                    if is_inside_circle && is_beyond_chord {
+                        let direction =
+                            (b - a) * circle.a().cross2d(&circle.b());
+                        if direction > Scalar::ZERO {
+                            angle += Scalar::TAU;
+                        } else {
+                            angle -= Scalar::TAU;
+                        }
+                    }
+                }
+            }
+            SurfacePath::Line(line) => {
+                let [a, b] = [start, end]
+                    .map(|coord| line.point_from_line_coords([coord]));
+                angle += angle_of_chord(a, b);
+            }
+        }
+    }
+
+    (angle / Scalar::TAU).round().into_f64() as i64
+}
+
+/// The half-edges that bound a region: its exterior, and its interiors
+pub(crate) type RegionHalfEdges =
+    (Vec<Handle<HalfEdge>>, Vec<Vec<Handle<HalfEdge>>>);
+
+/// Trace the cycles formed by half-edges, and assemble them into regions
+///
+/// `connects(i, j)` must tell whether half-edge `j` starts where half-edge `i`
+/// ends. The regions must be on the left of the half-edges, if `handedness` is
+/// right-handed, on the right otherwise. Where more than two half-edges meet,
+/// the cycles are traced such that they are as small as possible.
+///
+/// Cycles that wind around their region are its exterior. The others bound
+/// holes, and each hole is assigned to the smallest exterior that contains it.
+pub(crate) fn trace_regions(
+    half_edges: &[Handle<HalfEdge>],
+    connects: impl Fn(usize, usize) -> bool,
+    handedness: Handedness,
+) -> Vec<RegionHalfEdges> {
+    // Following the sharpest turn towards the region at each vertex yields the
+    // smallest cycles.
+    let mut is_used = vec![false; half_edges.len()];
+    let mut cycles = Vec::new();
+
+    for first in 0..half_edges.len() {
+        if is_used[first] {
+            continue;
+        }
+
+        let mut cycle = Vec::new();
+        let mut current = first;
+
+        loop {
+            is_used[current] = true;
+            cycle.push(half_edges[current].clone());
+
+            let incoming = &half_edges[current];
+
+            // If the start of the cycle is the only candidate left, the cycle
+            // is complete.
+            let next = half_edges
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| {
+                    (!is_used[*i] || *i == first) && connects(current, *i)
+                })
+                .min_by(|(_, a), (_, b)| {
+                    let [a, b] = [a, b]
+                        .map(|outgoing| turn(incoming, outgoing, handedness));
+
+                    if (a[0] - b[0]).abs() < Scalar::from_f64(TURN_EPSILON) {
+                        a[1].cmp(&b[1])
+                    } else {
+                        a[0].cmp(&b[0])
+                    }
+                })
+                .map(|(i, _)| i)
+                .expect("Expected half-edges to form cycles");
+
+            if next == first {
+                break;
+            }
+
+            current = next;
+        }
+
+        cycles.push(cycle);
+    }
+
+    let orientation = match handedness {
+        Handedness::RightHanded => Scalar::ONE,
+        Handedness::LeftHanded => -Scalar::ONE,
+    };
+    let (exteriors, interiors): (Vec<_>, Vec<_>) = cycles
+        .into_iter()
+        .map(|cycle| {
+            let area =
+                Cycle::new(cycle.iter().cloned()).signed_area() * orientation;
+            (cycle, area)
+        })
+        .partition(|(_, area)| *area > Scalar::ZERO);
+
+    let mut holes = vec![Vec::new(); exteriors.len()];
+    for (interior, _) in interiors {
+        let half_edge = &interior[0];
+        let [start, end] = half_edge.boundary().inner;
+        let point = half_edge
+            .path()
+            .point_from_path_coords([(start.t + end.t) / 2.]);
+
+        let containing_exterior = exteriors
+            .iter()
+            .enumerate()
+            .filter(|(_, (exterior, _))| winding_number(exterior, point) != 0)
+            .min_by_key(|(_, (_, area))| *area)
+            .map(|(i, _)| i)
+            .expect("Expected interior cycle to be within an exterior cycle");
+
+        holes[containing_exterior].push(interior);
+    }
+
+    exteriors
+        .into_iter()
+        .zip(holes)
+        .map(|((exterior, _), interiors)| (exterior, interiors))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        geometry::SurfacePath, objects::Region, operations::build::BuildRegion,
+        Instance,
+    };
+
+    use super::{divide_path, winding_number};
+
+    #[test]
+    fn divide_circle() {
+        let path = SurfacePath::circle_from_center_and_radius([0., 0.], 1.);
+        let tolerance = Scalar::from(1e-9);
+
+        // A complete circle is divided into pieces that wrap around.
+        let coords = [1., 4.].map(Scalar::from).to_vec();
+        let pieces = divide_path(&path, None, coords, tolerance);
+        assert_eq!(
+            pieces,
+            [[1., 4.], [4., 1. + TAU]].map(|piece| piece.map(Scalar::from))
+        );
+
+        // Coordinates outside of a boundary are ignored, and its ends become
+        // the ends of the first and last piece.
+        let boundary = Some([Point::from([0.]), Point::from([2.])]);
+        let coords = [1., 3.].map(Scalar::from).to_vec();
+        let pieces = divide_path(&path, boundary, coords, tolerance);
+        assert_eq!(
+            pieces,
+            [[0., 1.], [1., 2.]].map(|piece| piece.map(Scalar::from))
+        );
+    }
+
+    #[test]
+    fn winding_number_of_circle() {
+        let mut core = Instance::new();
+
+        let region = Region::circle(Point::from([0., 0.]), 1., &mut core);
+        let half_edges = region
+            .exterior()
+            .half_edges()
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // A point between an arc and its chord is still inside the circle.
+        for (point, expected) in [([0., 0.], 1), ([0.99, 0.], 1), ([2., 0.], 0)]
+        {
+            let point = Point::from(point);
+            assert_eq!(winding_number(&half_edges, point), expected);
+        }
+    }
+}
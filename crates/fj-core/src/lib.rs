@@ -85,6 +85,7 @@ pub mod algorithms;
 pub mod geometry;
 pub mod objects;
 pub mod operations;
+pub mod persistence;
 pub mod queries;
 pub mod services;
 pub mod storage;
@@ -94,6 +94,19 @@ macro_rules! any_object {
                 }
             }
 
+            impl TryFrom<AnyObject<Stored>> for Handle<$ty> {
+                type Error = AnyObject<Stored>;
+
+                fn try_from(
+                    object: AnyObject<Stored>,
+                ) -> Result<Self, Self::Error> {
+                    match object {
+                        AnyObject::$ty(handle) => Ok(handle.0),
+                        object => Err(object),
+                    }
+                }
+            }
+
             impl From<(Handle<$ty>, $ty)> for AnyObject<AboutToBeStored> {
                 fn from((handle, object): (Handle<$ty>, $ty)) -> Self {
                     Self::$ty((handle.into(), object))
@@ -30,53 +30,51 @@ impl Cycle {
     /// two possible windings, depending on the direction you look at the
     /// surface that the cycle is defined on from.
     pub fn winding(&self) -> Winding {
-        // The cycle could be made up of one or two circles. If that is the
-        // case, the winding of the cycle is determined by the winding of the
-        // first circle.
-        if self.half_edges.len() < 3 {
-            let first = self
-                .half_edges()
-                .iter()
-                .next()
-                .expect("Invalid cycle: expected at least one edge");
+        let area = self.signed_area();
 
-            let [a, b] = first.boundary().inner;
-            let edge_direction_positive = a < b;
-
-            let circle = match first.path() {
-                SurfacePath::Circle(circle) => circle,
-                SurfacePath::Line(_) => unreachable!(
-                    "Invalid cycle: less than 3 edges, but not all are circles"
-                ),
-            };
-            let cross_positive = circle.a().cross2d(&circle.b()) > Scalar::ZERO;
-
-            if edge_direction_positive == cross_positive {
-                return Winding::Ccw;
-            } else {
-                return Winding::Cw;
-            }
+        if area > Scalar::ZERO {
+            return Winding::Ccw;
+        }
+        if area < Scalar::ZERO {
+            return Winding::Cw;
         }
 
-        // Now that we got the special case out of the way, we can treat the
-        // cycle as a polygon:
-        // https://stackoverflow.com/a/1165943
+        unreachable!("Encountered invalid cycle: {self:#?}");
+    }
 
+    /// Compute the signed area enclosed by the cycle, in surface coordinates
+    ///
+    /// The area is positive, if the cycle is wound counter-clockwise, negative
+    /// otherwise. Circular arcs are taken into account exactly.
+    pub fn signed_area(&self) -> Scalar {
+        // Green's theorem: The area is half the integral of `u dv - v du`
+        // along the cycle.
         let mut sum = Scalar::ZERO;
 
-        for (a, b) in self.half_edges().pairs() {
-            let [a, b] = [a, b].map(|edge| edge.start_position());
+        for half_edge in self.half_edges() {
+            let [start, end] = half_edge.boundary().inner.map(|point| point.t);
 
-            sum += (b.u - a.u) * (b.v + a.v);
-        }
+            match half_edge.path() {
+                SurfacePath::Circle(circle) => {
+                    let c = circle.center().coords;
+                    let [a, b] = [circle.a(), circle.b()];
 
-        if sum > Scalar::ZERO {
-            return Winding::Cw;
-        }
-        if sum < Scalar::ZERO {
-            return Winding::Ccw;
+                    let (sin_start, cos_start) = start.sin_cos();
+                    let (sin_end, cos_end) = end.sin_cos();
+
+                    sum += c.cross2d(&a) * (cos_end - cos_start)
+                        + c.cross2d(&b) * (sin_end - sin_start)
+                        + a.cross2d(&b) * (end - start);
+                }
+                SurfacePath::Line(line) => {
+                    let [start, end] = [start, end].map(|coord| {
+                        line.point_from_line_coords([coord]).coords
+                    });
+                    sum += start.cross2d(&end);
+                }
+            }
         }
 
-        unreachable!("Encountered invalid cycle: {self:#?}");
+        sum / Scalar::TWO
     }
 }
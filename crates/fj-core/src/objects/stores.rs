@@ -2,7 +2,7 @@ use fj_math::Vector;
 
 use crate::{
     geometry::{GlobalPath, SurfaceGeometry},
-    storage::{Handle, Store},
+    storage::{Handle, Iter, Store},
 };
 
 use super::{
@@ -71,6 +71,11 @@ impl Surfaces {
         self.store.insert(handle, surface);
     }
 
+    /// Iterate over all surfaces in the store
+    pub fn iter(&self) -> Iter<Surface> {
+        self.store.iter()
+    }
+
     /// Access the xy-plane
     pub fn xy_plane(&self) -> Handle<Surface> {
         self.xy_plane.clone()
@@ -0,0 +1,525 @@
+//! # Boolean operations on 2d shapes
+//!
+//! See [`Boolean`], which is currently the only trait in this module, for more
+//! information.
+
+use fj_interop::Color;
+use fj_math::{Scalar, Winding};
+
+use crate::{
+    algorithms::{
+        intersect::{path_path::PathPathIntersection, Intersect},
+        path::{
+            divide_path, path_direction, point_to_path_coords,
+            project_onto_path, trace_regions, winding_number,
+        },
+    },
+    geometry::SurfacePath,
+    objects::{Cycle, HalfEdge, Handedness, Region, Sketch},
+    storage::Handle,
+    validate::ValidationConfig,
+    Instance,
+};
+
+use super::{build::BuildHalfEdge, insert::Insert};
+
+/// Combine [`Region`]s and [`Sketch`]es using boolean operations
+///
+/// All regions involved in an operation must lie on the same surface. The
+/// result is a new [`Sketch`], made up of as many regions as are required to
+/// represent it. Each of those regions has an exterior cycle that winds
+/// counter-clockwise, and interior cycles that wind clockwise.
+///
+/// The regions of a sketch are assumed not to overlap each other.
+pub trait Boolean {
+    /// Compute the union of this shape and another
+    #[must_use]
+    fn union(&self, other: &Self, core: &mut Instance) -> Sketch;
+
+    /// Compute the difference of this shape and another
+    ///
+    /// The result contains everything that is covered by this shape, but not
+    /// by the other one.
+    #[must_use]
+    fn difference(&self, other: &Self, core: &mut Instance) -> Sketch;
+
+    /// Compute the intersection of this shape and another
+    #[must_use]
+    fn intersection(&self, other: &Self, core: &mut Instance) -> Sketch;
+}
+
+impl Boolean for Region {
+    fn union(&self, other: &Self, core: &mut Instance) -> Sketch {
+        combine(&[self], &[other], Operation::Union, self.color(), core)
+    }
+
+    fn difference(&self, other: &Self, core: &mut Instance) -> Sketch {
+        combine(&[self], &[other], Operation::Difference, self.color(), core)
+    }
+
+    fn intersection(&self, other: &Self, core: &mut Instance) -> Sketch {
+        combine(
+            &[self],
+            &[other],
+            Operation::Intersection,
+            self.color(),
+            core,
+        )
+    }
+}
+
+impl Boolean for Sketch {
+    fn union(&self, other: &Self, core: &mut Instance) -> Sketch {
+        combine_sketches(self, other, Operation::Union, core)
+    }
+
+    fn difference(&self, other: &Self, core: &mut Instance) -> Sketch {
+        combine_sketches(self, other, Operation::Difference, core)
+    }
+
+    fn intersection(&self, other: &Self, core: &mut Instance) -> Sketch {
+        combine_sketches(self, other, Operation::Intersection, core)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Union,
+    Difference,
+    Intersection,
+}
+
+/// How a piece of one shape's boundary relates to the other shape
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Classification {
+    /// The piece is outside of the other shape
+    Outside,
+
+    /// The piece is inside of the other shape
+    Inside,
+
+    /// The piece is on the boundary of the other shape, facing the same way
+    Same,
+
+    /// The piece is on the boundary of the other shape, facing the other way
+    Opposite,
+}
+
+/// A piece of the boundary of a shape
+///
+/// The coordinates are given in the order in which the piece is traversed,
+/// which means the shape is always on its left.
+#[derive(Clone, Copy)]
+pub(super) struct Piece {
+    pub(super) path: SurfacePath,
+    pub(super) coords: [Scalar; 2],
+}
+
+/// A boundary half-edge of a shape, and whether it must be reversed
+///
+/// Half-edges must be reversed, if they belong to a region whose exterior
+/// winds clockwise. This way, the shape is on the left of all half-edges.
+type BoundaryHalfEdge = (Handle<HalfEdge>, bool);
+
+fn combine_sketches(
+    a: &Sketch,
+    b: &Sketch,
+    operation: Operation,
+    core: &mut Instance,
+) -> Sketch {
+    let [a, b] = [a, b].map(|sketch| {
+        sketch
+            .regions()
+            .iter()
+            .map(|region| &**region)
+            .collect::<Vec<_>>()
+    });
+    let color = a.iter().find_map(|region| region.color());
+
+    combine(&a, &b, operation, color, core)
+}
+
+fn combine(
+    a: &[&Region],
+    b: &[&Region],
+    operation: Operation,
+    color: Option<Color>,
+    core: &mut Instance,
+) -> Sketch {
+    let tolerance = ValidationConfig::default().distinct_min_distance;
+
+    let [boundary_a, boundary_b] = [a, b].map(boundary);
+    let [cycles_a, cycles_b] = [a, b].map(|regions| {
+        regions
+            .iter()
+            .flat_map(|region| region.all_cycles())
+            .map(|cycle| cycle.half_edges().iter().cloned().collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    });
+
+    // Divide both boundaries where they meet each other, then decide which of
+    // the resulting pieces bound the result.
+    let mut pieces = Vec::new();
+    for (boundary, other, other_cycles, is_a) in [
+        (&boundary_a, &boundary_b, &cycles_b, true),
+        (&boundary_b, &boundary_a, &cycles_a, false),
+    ] {
+        for piece in divide_boundary(boundary, other, tolerance) {
+            let classification =
+                classify(&piece, other, other_cycles, tolerance);
+
+            let keep = match (operation, is_a, classification) {
+                (Operation::Union, true, Classification::Outside)
+                | (Operation::Union, true, Classification::Same)
+                | (Operation::Union, false, Classification::Outside)
+                | (Operation::Intersection, true, Classification::Inside)
+                | (Operation::Intersection, true, Classification::Same)
+                | (Operation::Intersection, false, Classification::Inside)
+                | (Operation::Difference, true, Classification::Outside)
+                | (Operation::Difference, true, Classification::Opposite) => {
+                    Some(piece)
+                }
+                (Operation::Difference, false, Classification::Inside) => {
+                    let [start, end] = piece.coords;
+                    Some(Piece {
+                        path: piece.path,
+                        coords: [end, start],
+                    })
+                }
+                _ => None,
+            };
+
+            pieces.extend(keep);
+        }
+    }
+
+    sketch_from_pieces(pieces, color, tolerance, core)
+}
+
+/// Assemble a sketch from the pieces that make up its boundary
+///
+/// The sketch must be on the left of every piece, and the pieces must form
+/// closed cycles. Where more than two pieces meet, the cycles are traced such
+/// that they are as small as possible.
+pub(super) fn sketch_from_pieces(
+    pieces: Vec<Piece>,
+    color: Option<Color>,
+    tolerance: Scalar,
+    core: &mut Instance,
+) -> Sketch {
+    let half_edges = pieces
+        .into_iter()
+        .map(|piece| {
+            let [start, end] = piece.coords;
+            HalfEdge::unjoined(piece.path, [[start], [end]], core).insert(core)
+        })
+        .collect::<Vec<_>>();
+
+    // Every piece ends where another one starts, since the pieces are taken
+    // from closed boundaries, which are divided at the same points on both
+    // sides.
+    let end_points = half_edges
+        .iter()
+        .map(|half_edge| {
+            let [_, end] = half_edge.boundary().inner;
+            half_edge.path().point_from_path_coords(end)
+        })
+        .collect::<Vec<_>>();
+    let start_points = half_edges
+        .iter()
+        .map(|half_edge| half_edge.start_position())
+        .collect::<Vec<_>>();
+
+    let regions = trace_regions(
+        &half_edges,
+        |i, j| start_points[j].distance_to(&end_points[i]) < tolerance,
+        Handedness::RightHanded,
+    )
+    .into_iter()
+    .map(|(exterior, interiors)| {
+        let exterior = Cycle::new(exterior).insert(core);
+        let interiors = interiors
+            .into_iter()
+            .map(|interior| Cycle::new(interior).insert(core))
+            .collect::<Vec<_>>();
+
+        Region::new(exterior, interiors, color).insert(core)
+    })
+    .collect::<Vec<_>>();
+
+    Sketch::new(regions)
+}
+
+/// Collect the boundary half-edges of the provided regions
+fn boundary(regions: &[&Region]) -> Vec<BoundaryHalfEdge> {
+    regions
+        .iter()
+        .flat_map(|region| {
+            let reverse = region.exterior().winding() == Winding::Cw;
+
+            region.all_cycles().flat_map(move |cycle| {
+                cycle
+                    .half_edges()
+                    .iter()
+                    .map(move |half_edge| (half_edge.clone(), reverse))
+            })
+        })
+        .collect()
+}
+
+/// Divide a boundary into pieces, wherever it meets the other boundary
+fn divide_boundary(
+    boundary: &[BoundaryHalfEdge],
+    other: &[BoundaryHalfEdge],
+    tolerance: Scalar,
+) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+
+    for (half_edge, reverse) in boundary {
+        let path = half_edge.path();
+        let mut coords = Vec::new();
+
+        for (other, _) in other {
+            match (&path, &other.path()).intersect() {
+                Some(PathPathIntersection::Points(points)) => {
+                    for [coord, _] in points {
+                        let point = path.point_from_path_coords(coord);
+                        let (distance, _) = project_onto_path(
+                            &other.path(),
+                            other.boundary().inner,
+                            point,
+                        );
+
+                        if distance < tolerance {
+                            coords.push(coord.t);
+                        }
+                    }
+                }
+                Some(PathPathIntersection::Coincident) => {
+                    for coord in other.boundary().inner {
+                        let point = other.path().point_from_path_coords(coord);
+                        coords.push(point_to_path_coords(&path, point));
+                    }
+                }
+                None => {}
+            }
+        }
+
+        let [start, end] = half_edge.boundary().inner.map(|point| point.t);
+        let forward = (start < end) != *reverse;
+
+        for [min, max] in divide_path(
+            &path,
+            Some(half_edge.boundary().inner),
+            coords,
+            tolerance,
+        ) {
+            let coords = if forward { [min, max] } else { [max, min] };
+            pieces.push(Piece { path, coords });
+        }
+    }
+
+    pieces
+}
+
+/// Determine how a piece of one boundary relates to the other shape
+fn classify(
+    piece: &Piece,
+    other: &[BoundaryHalfEdge],
+    other_cycles: &[Vec<Handle<HalfEdge>>],
+    tolerance: Scalar,
+) -> Classification {
+    let [start, end] = piece.coords;
+    let middle = (start + end) / Scalar::TWO;
+    let point = piece.path.point_from_path_coords([middle]);
+
+    for (half_edge, reverse) in other {
+        let (distance, coord) = project_onto_path(
+            &half_edge.path(),
+            half_edge.boundary().inner,
+            point,
+        );
+        if distance >= tolerance {
+            continue;
+        }
+
+        let [other_start, other_end] =
+            half_edge.boundary().inner.map(|point| point.t);
+        let other_forward = (other_start < other_end) != *reverse;
+
+        let mut direction = path_direction(&piece.path, middle);
+        if end < start {
+            direction = -direction;
+        }
+        let mut other_direction = path_direction(&half_edge.path(), coord);
+        if !other_forward {
+            other_direction = -other_direction;
+        }
+
+        if direction.dot(&other_direction) > Scalar::ZERO {
+            return Classification::Same;
+        }
+        return Classification::Opposite;
+    }
+
+    let winding_number = other_cycles
+        .iter()
+        .map(|half_edges| winding_number(half_edges, point))
+        .sum::<i64>();
+
+    if winding_number == 0 {
+        Classification::Outside
+    } else {
+        Classification::Inside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::Scalar;
+
+    use crate::{
+        objects::{Region, Sketch},
+        operations::{build::BuildRegion, sweep::SweepSketch},
+        validate::Validate,
+        Instance,
+    };
+
+    use super::Boolean;
+
+    #[test]
+    fn overlapping_squares() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let a = Region::polygon(
+            [[0., 0.], [2., 0.], [2., 2.], [0., 2.]],
+            &mut core,
+        );
+        let b = Region::polygon(
+            [[1., 1.], [3., 1.], [3., 3.], [1., 3.]],
+            &mut core,
+        );
+
+        let union = a.union(&b, &mut core);
+        assert_regions(&union, &[(7., 0)], &mut core)?;
+
+        let difference = a.difference(&b, &mut core);
+        assert_regions(&difference, &[(3., 0)], &mut core)?;
+
+        let intersection = a.intersection(&b, &mut core);
+        assert_regions(&intersection, &[(1., 0)], &mut core)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn square_containing_circle() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let square = Region::polygon(
+            [[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]],
+            &mut core,
+        );
+        let circle = Region::circle([0., 0.], 1., &mut core);
+
+        let union = square.union(&circle, &mut core);
+        assert_regions(&union, &[(16., 0)], &mut core)?;
+
+        let difference = square.difference(&circle, &mut core);
+        assert_regions(&difference, &[(16. - PI, 1)], &mut core)?;
+
+        let intersection = square.intersection(&circle, &mut core);
+        assert_regions(&intersection, &[(PI, 0)], &mut core)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn square_and_circle_crossing_it() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let square = Region::polygon(
+            [[0., 0.], [2., 0.], [2., 2.], [0., 2.]],
+            &mut core,
+        );
+        let circle = Region::circle([2., 1.], 1., &mut core);
+
+        // The circle is centered on the right edge of the square, so half of
+        // it overlaps the square.
+        let union = square.union(&circle, &mut core);
+        assert_regions(&union, &[(4. + PI / 2., 0)], &mut core)?;
+
+        let difference = square.difference(&circle, &mut core);
+        assert_regions(&difference, &[(4. - PI / 2., 0)], &mut core)?;
+
+        let intersection = square.intersection(&circle, &mut core);
+        assert_regions(&intersection, &[(PI / 2., 0)], &mut core)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn difference_splitting_square() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let square = Region::polygon(
+            [[0., 0.], [3., 0.], [3., 3.], [0., 3.]],
+            &mut core,
+        );
+        let bar = Region::polygon(
+            [[1., -1.], [2., -1.], [2., 4.], [1., 4.]],
+            &mut core,
+        );
+
+        let difference = square.difference(&bar, &mut core);
+        assert_regions(&difference, &[(3., 0), (3., 0)], &mut core)?;
+
+        Ok(())
+    }
+
+    /// Check the area and number of holes of each region of the sketch
+    ///
+    /// Also validates the sketch, as well as the solid it sweeps into, which
+    /// checks that all cycles are closed.
+    fn assert_regions(
+        sketch: &Sketch,
+        expected: &[(f64, usize)],
+        core: &mut Instance,
+    ) -> anyhow::Result<()> {
+        let mut regions = sketch
+            .regions()
+            .iter()
+            .map(|region| {
+                let area =
+                    region.interiors().iter().fold(
+                        region.exterior().signed_area(),
+                        |area, interior| area + interior.signed_area(),
+                    );
+                (area, region.interiors().len())
+            })
+            .collect::<Vec<_>>();
+        regions.sort();
+
+        assert_eq!(regions.len(), expected.len());
+        for ((area, num_holes), (expected_area, expected_holes)) in
+            regions.into_iter().zip(expected)
+        {
+            assert!(
+                (area - Scalar::from(*expected_area)).abs()
+                    < Scalar::from(1e-6),
+                "Unexpected area: {area} (expected {expected_area})"
+            );
+            assert_eq!(num_holes, *expected_holes);
+        }
+
+        sketch.validate_and_return_first_error()?;
+
+        let surface = core.services.objects.surfaces.xy_plane();
+        let solid = sketch.sweep_sketch(surface, [0., 0., 1.], core);
+        solid.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use fj_math::{Point, Scalar};
+use fj_math::{Point, Scalar, Vector};
 use itertools::Itertools;
 
 use crate::{
@@ -45,6 +45,346 @@ pub trait BuildCycle {
 
         Cycle::new(edges)
     }
+
+    /// Start building a cycle out of lines and arcs
+    ///
+    /// See [`CycleBuilder`].
+    fn builder(start: impl Into<Point<2>>) -> CycleBuilder {
+        CycleBuilder {
+            start: start.into(),
+            segments: Vec::new(),
+        }
+    }
 }
 
 impl BuildCycle for Cycle {}
+
+/// Build a [`Cycle`] out of lines and arcs
+///
+/// Starts at a point, then adds one segment after the other, each starting
+/// where the previous one ended. Created by [`BuildCycle::builder`].
+#[derive(Clone, Debug)]
+pub struct CycleBuilder {
+    start: Point<2>,
+    segments: Vec<Segment>,
+}
+
+impl CycleBuilder {
+    /// Add a line segment that ends at the provided point
+    #[must_use]
+    pub fn line_to(mut self, end: impl Into<Point<2>>) -> Self {
+        self.segments.push(Segment {
+            end: end.into(),
+            angle: Scalar::ZERO,
+        });
+        self
+    }
+
+    /// Add an arc with the provided radius, that ends at the provided point
+    ///
+    /// The arc is the shorter one of the two possible arcs. A positive radius
+    /// results in a counter-clockwise arc, a negative radius in a clockwise
+    /// one.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the radius is too small to connect the current point to
+    /// `end`.
+    #[must_use]
+    pub fn arc_to(
+        mut self,
+        end: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+    ) -> Self {
+        let end = end.into();
+        let radius = radius.into();
+
+        let half_chord = (end - self.current()).magnitude() / 2.;
+        assert!(
+            half_chord <= radius.abs(),
+            "Radius {radius:?} is too small to reach {end:?}"
+        );
+
+        let angle = (half_chord / radius.abs()).into_f64().asin() * 2.;
+        let angle = Scalar::from_f64(angle) * radius.sign().to_scalar();
+
+        self.segments.push(Segment { end, angle });
+        self
+    }
+
+    /// Add an arc that passes through `through` and ends at `end`
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the current point, `through`, and `end` are on a line.
+    #[must_use]
+    pub fn arc_through(
+        mut self,
+        through: impl Into<Point<2>>,
+        end: impl Into<Point<2>>,
+    ) -> Self {
+        let start = self.current();
+        let [through, end] = [through.into(), end.into()];
+
+        let [a, b] = [through - start, end - start];
+        assert!(
+            a.cross2d(&b) != Scalar::ZERO,
+            "Can't build arc through points on a line"
+        );
+
+        // An inscribed angle is half of the central angle that spans the
+        // same arc. The angle at `through`, between the lines to `start` and
+        // `end`, spans the arc that *doesn't* contain `through`.
+        let [to_start, to_end] = [start - through, end - through];
+        let inscribed = to_end.cross2d(&to_start).atan2(to_end.dot(&to_start));
+        let angle = if inscribed > Scalar::ZERO {
+            Scalar::TAU - inscribed * 2.
+        } else {
+            -Scalar::TAU - inscribed * 2.
+        };
+
+        self.segments.push(Segment { end, angle });
+        self
+    }
+
+    /// Add an arc that continues tangentially from the previous segment
+    ///
+    /// If `end` lies on the tangent of the previous segment, a line segment is
+    /// added instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if there is no previous segment.
+    #[must_use]
+    pub fn tangent_arc_to(mut self, end: impl Into<Point<2>>) -> Self {
+        let end = end.into();
+
+        let tangent = self
+            .end_tangent()
+            .expect("Need previous segment to add tangent arc");
+        let chord = end - self.current();
+
+        let angle = tangent.cross2d(&chord).atan2(tangent.dot(&chord)) * 2.;
+
+        self.segments.push(Segment { end, angle });
+        self
+    }
+
+    /// Close the cycle with a line segment back to the start
+    ///
+    /// Does nothing, if the last segment already ends at the start, or if there
+    /// are no segments yet.
+    #[must_use]
+    pub fn close(self) -> Self {
+        let start = self.start;
+
+        if self.current() == start {
+            self
+        } else {
+            self.line_to(start)
+        }
+    }
+
+    /// Build the cycle
+    ///
+    /// Creates a half-edge for each segment, using [`HalfEdge::line_segment`]
+    /// and [`HalfEdge::arc`]. If the last segment doesn't end at the start, the
+    /// cycle is closed with a line segment, as if [`CycleBuilder::close`] had
+    /// been called.
+    pub fn build(self, core: &mut Instance) -> Cycle {
+        let mut start = self.start;
+
+        let half_edges = self
+            .close()
+            .segments
+            .into_iter()
+            .map(|Segment { end, angle }| {
+                let half_edge = if angle == Scalar::ZERO {
+                    HalfEdge::line_segment([start, end], None, core)
+                } else {
+                    HalfEdge::arc(start, end, angle, core)
+                };
+                start = end;
+
+                half_edge.insert(core)
+            })
+            .collect::<Vec<_>>();
+
+        Cycle::new(half_edges)
+    }
+
+    fn current(&self) -> Point<2> {
+        self.segments
+            .last()
+            .map(|segment| segment.end)
+            .unwrap_or(self.start)
+    }
+
+    fn end_tangent(&self) -> Option<Vector<2>> {
+        let (last, previous) = self.segments.split_last()?;
+        let start = previous
+            .last()
+            .map(|segment| segment.end)
+            .unwrap_or(self.start);
+
+        // The tangent at the end of an arc is its chord, rotated by half of
+        // the arc's angle.
+        let chord = last.end - start;
+        let (sin, cos) = (last.angle / 2.).sin_cos();
+
+        Some(Vector::from([
+            chord.u * cos - chord.v * sin,
+            chord.u * sin + chord.v * cos,
+        ]))
+    }
+}
+
+/// A segment of a [`CycleBuilder`]
+///
+/// The segment starts where the previous one ends. An angle of zero means it's
+/// a line segment. Otherwise, it's an arc with the given angle, in radians,
+/// measured counter-clockwise.
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    end: Point<2>,
+    angle: Scalar,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::{Point, Scalar};
+
+    use crate::{
+        geometry::SurfacePath, objects::Cycle, validate::Validate, Instance,
+    };
+
+    use super::BuildCycle;
+
+    #[test]
+    fn lines() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let cycle = Cycle::builder([0., 0.])
+            .line_to([1., 0.])
+            .line_to([1., 1.])
+            .line_to([0., 1.])
+            .close()
+            .build(&mut core);
+
+        assert_ends(&cycle, [[1., 0.], [1., 1.], [0., 1.], [0., 0.]]);
+        assert_eq!(cycle.signed_area(), Scalar::ONE);
+        cycle.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn build_closes_cycle() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let cycle = Cycle::builder([0., 0.])
+            .line_to([1., 0.])
+            .line_to([1., 1.])
+            .build(&mut core);
+
+        assert_ends(&cycle, [[1., 0.], [1., 1.], [0., 0.]]);
+        cycle.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn arcs() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        // A circle of radius 1 around the origin, made from two half circles.
+        let cycle = Cycle::builder([1., 0.])
+            .arc_to([-1., 0.], 1.)
+            .arc_through([0., -1.], [1., 0.])
+            .build(&mut core);
+
+        assert_ends(&cycle, [[-1., 0.], [1., 0.]]);
+        assert_circles(&cycle, [0., 0.], 1.);
+        assert_approx_eq(cycle.signed_area(), PI);
+        cycle.validate_and_return_first_error()?;
+
+        // A negative radius results in a clockwise arc.
+        let cycle = Cycle::builder([1., 0.])
+            .arc_to([-1., 0.], -1.)
+            .build(&mut core);
+        assert_approx_eq(cycle.signed_area(), -PI / 2.);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tangent_arcs() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        // A slot: Two parallel lines, connected by half circles.
+        let cycle = Cycle::builder([0., 0.])
+            .line_to([2., 0.])
+            .tangent_arc_to([2., 2.])
+            .line_to([0., 2.])
+            .tangent_arc_to([0., 0.])
+            .build(&mut core);
+
+        assert_ends(&cycle, [[2., 0.], [2., 2.], [0., 2.], [0., 0.]]);
+        assert_approx_eq(cycle.signed_area(), 4. + PI);
+        cycle.validate_and_return_first_error()?;
+
+        // An end point on the tangent results in a line segment.
+        let cycle = Cycle::builder([0., 0.])
+            .line_to([1., 0.])
+            .tangent_arc_to([2., 0.])
+            .build(&mut core);
+        assert!(cycle
+            .half_edges()
+            .iter()
+            .all(|half_edge| matches!(half_edge.path(), SurfacePath::Line(_))));
+
+        Ok(())
+    }
+
+    fn assert_ends<const N: usize>(cycle: &Cycle, expected: [[f64; 2]; N]) {
+        let ends = cycle
+            .half_edges()
+            .iter()
+            .map(|half_edge| {
+                let [_, end] = half_edge.boundary().inner;
+                half_edge.path().point_from_path_coords(end)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(ends.len(), N);
+        for (end, expected) in ends.into_iter().zip(expected) {
+            let distance = end.distance_to(&Point::from(expected));
+            assert!(distance < Scalar::from(1e-9), "{end:?} != {expected:?}");
+        }
+    }
+
+    fn assert_circles(
+        cycle: &Cycle,
+        center: impl Into<Point<2>>,
+        radius: impl Into<Scalar>,
+    ) {
+        let (center, radius) = (center.into(), radius.into());
+
+        for half_edge in cycle.half_edges() {
+            let SurfacePath::Circle(circle) = half_edge.path() else {
+                panic!("Expected arc");
+            };
+            assert!(circle.center().distance_to(&center) < Scalar::from(1e-9));
+            assert_approx_eq(circle.radius(), radius.into_f64());
+        }
+    }
+
+    fn assert_approx_eq(a: Scalar, b: f64) {
+        assert!(
+            (a - Scalar::from(b)).abs() < Scalar::from(1e-9),
+            "{a} != {b}"
+        );
+    }
+}
@@ -25,9 +25,10 @@ mod shell;
 mod sketch;
 mod solid;
 mod surface;
+mod text;
 
 pub use self::{
-    cycle::BuildCycle,
+    cycle::{BuildCycle, CycleBuilder},
     face::{BuildFace, Polygon},
     half_edge::BuildHalfEdge,
     region::BuildRegion,
@@ -35,4 +36,5 @@ pub use self::{
     sketch::BuildSketch,
     solid::{BuildSolid, Tetrahedron},
     surface::BuildSurface,
+    text::{Font, FontError},
 };
@@ -1,4 +1,14 @@
-use crate::objects::Sketch;
+use fj_math::{Scalar, Winding};
+
+use crate::{
+    algorithms::path::winding_number,
+    objects::{Cycle, Region, Sketch},
+    operations::{insert::Insert, reverse::Reverse},
+    storage::Handle,
+    Instance,
+};
+
+use super::text::{self, Font};
 
 /// Build a [`Sketch`]
 ///
@@ -10,6 +20,113 @@ pub trait BuildSketch {
     fn empty() -> Sketch {
         Sketch::new([])
     }
+
+    /// Create a sketch from closed cycles, that can be nested within each other
+    ///
+    /// Cycles that are nested within an even number of other cycles (including
+    /// zero) become the exteriors of regions. The others become holes, in the
+    /// region whose exterior immediately contains them. This is how the
+    /// nonzero and even-odd fill rules of most file formats agree, for shapes
+    /// whose outlines don't intersect.
+    ///
+    /// The winding of the provided cycles doesn't matter. Cycles are reversed
+    /// as required.
+    fn from_cycles(
+        cycles: impl IntoIterator<Item = Cycle>,
+        core: &mut Instance,
+    ) -> Sketch {
+        let cycles = cycles.into_iter().collect::<Vec<_>>();
+
+        let containers = cycles
+            .iter()
+            .enumerate()
+            .map(|(i, cycle)| {
+                let half_edge = cycle.half_edges().first();
+                let [start, end] = half_edge.boundary().inner;
+                let point = half_edge
+                    .path()
+                    .point_from_path_coords([(start.t + end.t) / 2.]);
+
+                cycles
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, other)| {
+                        let half_edges = other
+                            .half_edges()
+                            .iter()
+                            .cloned()
+                            .collect::<Vec<_>>();
+                        i != *j && winding_number(&half_edges, point) != 0
+                    })
+                    .map(|(j, _)| j)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut holes = vec![Vec::new(); cycles.len()];
+        for (i, containers_of_cycle) in containers.iter().enumerate() {
+            if containers_of_cycle.len() % 2 == 0 {
+                continue;
+            }
+
+            // The exterior that immediately contains the hole is the one that
+            // is nested most deeply.
+            let exterior = containers_of_cycle
+                .iter()
+                .copied()
+                .max_by_key(|&container| containers[container].len())
+                .expect("Hole must be contained in at least one cycle");
+            holes[exterior].push(i);
+        }
+
+        let mut regions = Vec::new();
+        for (i, _) in containers
+            .iter()
+            .enumerate()
+            .filter(|(_, containers)| containers.len() % 2 == 0)
+        {
+            let exterior = with_winding(&cycles[i], Winding::Ccw, core);
+            let interiors = holes[i]
+                .iter()
+                .map(|&hole| with_winding(&cycles[hole], Winding::Cw, core))
+                .collect::<Vec<_>>();
+
+            regions.push(Region::new(exterior, interiors, None).insert(core));
+        }
+
+        Sketch::new(regions)
+    }
+
+    /// Create a sketch from text
+    ///
+    /// The text starts at the origin, with the baseline of its first line
+    /// running along the u-axis. `size` is the font size, the height of an em
+    /// in model units. Line breaks start a new line below the previous one.
+    /// Characters that the font doesn't contain are skipped.
+    ///
+    /// Each glyph becomes one or more regions, with holes for the counters of
+    /// letters like "o" or "A". Curves in the glyph outlines are approximated
+    /// by straight lines.
+    fn text(
+        text: &str,
+        font: &Font,
+        size: impl Into<Scalar>,
+        core: &mut Instance,
+    ) -> Sketch {
+        text::text(text, font, size.into(), core)
+    }
 }
 
 impl BuildSketch for Sketch {}
+
+fn with_winding(
+    cycle: &Cycle,
+    winding: Winding,
+    core: &mut Instance,
+) -> Handle<Cycle> {
+    if cycle.winding() == winding {
+        cycle.clone().insert(core)
+    } else {
+        cycle.reverse(core).insert(core)
+    }
+}
@@ -0,0 +1,264 @@
+use fj_math::{Point, Scalar, Vector};
+use ttf_parser::OutlineBuilder;
+
+use crate::{
+    objects::{Cycle, Sketch},
+    operations::build::{BuildCycle, BuildSketch},
+    Instance,
+};
+
+/// A TrueType or OpenType font, that text can be built from
+///
+/// See [`BuildSketch::text`].
+///
+/// [`BuildSketch::text`]: super::BuildSketch::text
+#[derive(Clone, Debug)]
+pub struct Font {
+    data: Vec<u8>,
+}
+
+impl Font {
+    /// Load a font from the contents of a font file
+    ///
+    /// If the file is a font collection, the first font in it is loaded.
+    pub fn from_bytes(data: impl Into<Vec<u8>>) -> Result<Self, FontError> {
+        let data = data.into();
+        ttf_parser::Face::parse(&data, 0)?;
+        Ok(Self { data })
+    }
+
+    fn face(&self) -> ttf_parser::Face {
+        ttf_parser::Face::parse(&self.data, 0)
+            .expect("Font data has been validated on construction")
+    }
+}
+
+/// Error loading a [`Font`]
+#[derive(Debug, thiserror::Error)]
+pub enum FontError {
+    /// The font data could not be parsed
+    #[error("Failed to parse font")]
+    Parse(#[from] ttf_parser::FaceParsingError),
+}
+
+/// Build the regions that make up the provided text
+///
+/// Each glyph outline is flattened into straight lines, deviating from the
+/// curves of the outline by no more than a thousandth of the font size.
+pub(super) fn text(
+    text: &str,
+    font: &Font,
+    size: Scalar,
+    core: &mut Instance,
+) -> Sketch {
+    let face = font.face();
+
+    let units_per_em = f64::from(face.units_per_em());
+    let scale = size.into_f64() / units_per_em;
+    let line_height = f64::from(face.ascender()) - f64::from(face.descender())
+        + f64::from(face.line_gap());
+
+    let mut outlines = GlyphOutlines {
+        tolerance: units_per_em / 1000.,
+        ..GlyphOutlines::default()
+    };
+    let mut cursor = Vector::from([0., 0.]);
+
+    for c in text.chars() {
+        if c == '\n' {
+            cursor = Vector::from([0., cursor.v.into_f64() - line_height]);
+            continue;
+        }
+
+        let Some(glyph) = face.glyph_index(c) else {
+            continue;
+        };
+
+        outlines.offset = cursor;
+        face.outline_glyph(glyph, &mut outlines);
+        outlines.close();
+
+        let advance = face.glyph_hor_advance(glyph).unwrap_or(0);
+        cursor = cursor + Vector::from([f64::from(advance), 0.]);
+    }
+
+    let cycles = outlines
+        .contours
+        .into_iter()
+        .map(|contour| {
+            let points = contour.into_iter().map(|point| Point {
+                coords: point.coords * scale,
+            });
+            Cycle::polygon(points, core)
+        })
+        .collect::<Vec<_>>();
+
+    Sketch::from_cycles(cycles, core)
+}
+
+/// Collects glyph outlines as polygons
+#[derive(Default)]
+struct GlyphOutlines {
+    /// The maximum distance of the polygon from the curves of the outline
+    tolerance: f64,
+
+    /// The position of the current glyph, in font units
+    offset: Vector<2>,
+
+    current: Vec<Point<2>>,
+    contours: Vec<Vec<Point<2>>>,
+}
+
+impl GlyphOutlines {
+    fn point(&self, x: f32, y: f32) -> Point<2> {
+        Point::from([f64::from(x), f64::from(y)]) + self.offset
+    }
+
+    fn last(&self) -> Point<2> {
+        *self
+            .current
+            .last()
+            .expect("Outline must start with `move_to`")
+    }
+
+    fn push(&mut self, point: Point<2>) {
+        if self.current.last() != Some(&point) {
+            self.current.push(point);
+        }
+    }
+
+    /// Add the points of a curve, sampled at `num_segments` equal intervals
+    fn push_curve(
+        &mut self,
+        num_segments: Scalar,
+        curve: impl Fn(Scalar) -> Point<2>,
+    ) {
+        let num_segments = num_segments.ceil().max(Scalar::ONE).into_u64();
+
+        for i in 1..=num_segments {
+            let t = Scalar::from_u64(i) / Scalar::from_u64(num_segments);
+            self.push(curve(t));
+        }
+    }
+
+    /// Compute the number of segments required to approximate a curve
+    ///
+    /// Expects an upper bound for the magnitude of the curve's second
+    /// derivative. The distance between a curve and a straight segment
+    /// approximating it is at most an eighth of that, times the square of the
+    /// segment's length in curve parameters.
+    fn num_segments(&self, second_derivative: Scalar) -> Scalar {
+        Scalar::from_f64(
+            (second_derivative.into_f64() / self.tolerance / 8.).sqrt(),
+        )
+    }
+}
+
+impl OutlineBuilder for GlyphOutlines {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        let point = self.point(x, y);
+        self.current.push(point);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let point = self.point(x, y);
+        self.push(point);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let [p0, p1, p2] = [self.last(), self.point(x1, y1), self.point(x, y)];
+
+        let second_derivative =
+            (p0.coords - p1.coords * 2. + p2.coords).magnitude() * 2.;
+        let num_segments = self.num_segments(second_derivative);
+
+        self.push_curve(num_segments, |t| {
+            let s = Scalar::ONE - t;
+            let coords = p0.coords * (s * s)
+                + p1.coords * (s * t * 2.)
+                + p2.coords * (t * t);
+            Point { coords }
+        });
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let [p0, p1, p2, p3] = [
+            self.last(),
+            self.point(x1, y1),
+            self.point(x2, y2),
+            self.point(x, y),
+        ];
+
+        let second_derivative = (p0.coords - p1.coords * 2. + p2.coords)
+            .magnitude()
+            .max((p1.coords - p2.coords * 2. + p3.coords).magnitude())
+            * 6.;
+        let num_segments = self.num_segments(second_derivative);
+
+        self.push_curve(num_segments, |t| {
+            let s = Scalar::ONE - t;
+            let coords = p0.coords * (s * s * s)
+                + p1.coords * (s * s * t * 3.)
+                + p2.coords * (s * t * t * 3.)
+                + p3.coords * (t * t * t);
+            Point { coords }
+        });
+    }
+
+    fn close(&mut self) {
+        let mut contour = std::mem::take(&mut self.current);
+
+        if contour.len() > 1 && contour.first() == contour.last() {
+            contour.pop();
+        }
+        if contour.len() >= 3 {
+            self.contours.push(contour);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::Sketch, operations::build::BuildSketch, validate::Validate,
+        Instance,
+    };
+
+    use super::Font;
+
+    #[test]
+    fn o_has_hole() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let sketch = Sketch::text("o", &font()?, 1., &mut core);
+
+        assert_eq!(sketch.regions().len(), 1);
+        assert_eq!(sketch.regions().first().interiors().len(), 1);
+        sketch.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn i_has_two_regions() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let sketch = Sketch::text("i", &font()?, 1., &mut core);
+
+        assert_eq!(sketch.regions().len(), 2);
+        for region in sketch.regions() {
+            assert!(region.interiors().is_empty());
+        }
+        sketch.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    fn font() -> anyhow::Result<Font> {
+        let data = include_bytes!(
+            "../../../../fj-viewer/src/graphics/fonts/B612-Regular.ttf"
+        );
+        Ok(Font::from_bytes(data.as_slice())?)
+    }
+}
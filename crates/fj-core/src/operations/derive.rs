@@ -0,0 +1,37 @@
+//! Record which objects were derived from which other objects
+//!
+//! See [`Provenance`] for more information.
+//!
+//! [`Provenance`]: crate::services::Provenance
+
+use crate::{
+    objects::{AnyObject, Stored},
+    storage::Handle,
+    Instance,
+};
+
+/// Record that an object was derived from another
+pub trait DeriveFrom {
+    /// Record that this object was derived from `original`
+    ///
+    /// Returns the object unchanged, so this can be chained with the operation
+    /// that created it.
+    #[must_use]
+    fn derive_from<T>(self, original: &Handle<T>, core: &mut Instance) -> Self
+    where
+        Handle<T>: Into<AnyObject<Stored>>;
+}
+
+impl<O> DeriveFrom for Handle<O>
+where
+    Handle<O>: Into<AnyObject<Stored>>,
+{
+    fn derive_from<T>(self, original: &Handle<T>, core: &mut Instance) -> Self
+    where
+        Handle<T>: Into<AnyObject<Stored>>,
+    {
+        core.services
+            .derive_object(original.clone().into(), self.clone().into());
+        self
+    }
+}
@@ -0,0 +1,310 @@
+//! # Operations to imprint shells onto each other
+//!
+//! See [`Imprint`], which is currently the only trait in this module, for more
+//! information.
+
+use fj_math::{Circle, Plane, Point, Scalar};
+
+use crate::{
+    algorithms::{intersect::FaceFaceIntersection, path::is_bounded_by_lines},
+    geometry::{CurveBoundary, GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{Face, Shell},
+    validate::ValidationConfig,
+    Instance,
+};
+
+use super::split::SplitFace;
+
+/// Imprint the edges of one [`Shell`] onto another
+pub trait Imprint {
+    /// Imprint another shell onto this one
+    ///
+    /// Computes where the faces of the other shell meet the faces of this
+    /// shell, and splits the faces of this shell along those curves. No
+    /// material is removed or added. This shell keeps its shape, but its faces
+    /// are divided wherever the other shell crosses or touches them.
+    ///
+    /// Faces of the other shell that cross a face of this shell imprint the
+    /// curve along which they cross it. Faces that lie in the same plane as a
+    /// face of this shell imprint their boundary, marking the area where both
+    /// faces are in contact.
+    ///
+    /// Edges of this shell that are crossed by the imprinted curves are split,
+    /// together with their siblings. All new edges are joined to their
+    /// siblings, leaving the shell valid.
+    ///
+    /// # Implementation Note
+    ///
+    /// Only faces on planar surfaces are supported. Faces that cross each other
+    /// must be bounded by line segments only, as that is what
+    /// [`FaceFaceIntersection`] currently supports. Other pairs of faces are
+    /// ignored.
+    #[must_use]
+    fn imprint(&self, other: &Shell, core: &mut Instance) -> Self;
+}
+
+impl Imprint for Shell {
+    fn imprint(&self, other: &Shell, core: &mut Instance) -> Self {
+        let paths_per_face = self
+            .faces()
+            .iter()
+            .map(|face| {
+                let mut paths = Vec::new();
+
+                for other_face in other.faces() {
+                    if !is_planar(face) || !is_planar(other_face) {
+                        continue;
+                    }
+
+                    if let Some(boundary) =
+                        boundary_if_coplanar(face, other_face)
+                    {
+                        paths.extend(boundary);
+                        continue;
+                    }
+
+                    if !is_bounded_by_lines(face)
+                        || !is_bounded_by_lines(other_face)
+                    {
+                        continue;
+                    }
+
+                    let Some(intersection) =
+                        FaceFaceIntersection::compute([face, other_face])
+                    else {
+                        continue;
+                    };
+
+                    let [path, _] = intersection.intersection_curves;
+                    for interval in intersection.intersection_intervals {
+                        let boundary =
+                            CurveBoundary::from([interval.start, interval.end]);
+                        paths.push((path, Some(boundary)));
+                    }
+                }
+
+                paths
+            })
+            .collect::<Vec<_>>();
+
+        // Splitting a face puts the new faces into its place within the shell.
+        // Neighboring faces are updated, but stay where they are. By going
+        // through the faces back to front, we make sure that the faces that
+        // have yet to be split are still where we expect them.
+        let mut shell = self.clone();
+        for (i, paths) in paths_per_face.into_iter().enumerate().rev() {
+            if paths.is_empty() {
+                continue;
+            }
+
+            let face = shell
+                .faces()
+                .nth(i)
+                .expect("Splitting faces must not reduce their number")
+                .clone();
+
+            (shell, _) = shell.split_face_along_paths(&face, paths, core);
+        }
+
+        shell
+    }
+}
+
+fn is_planar(face: &Face) -> bool {
+    matches!(face.surface().geometry().u, GlobalPath::Line(_))
+}
+
+fn plane_from_surface(surface: &SurfaceGeometry) -> Plane {
+    let GlobalPath::Line(line) = surface.u else {
+        unreachable!("Only planar surfaces are being imprinted");
+    };
+
+    Plane::from_parametric(line.origin(), line.direction(), surface.v)
+}
+
+/// Return the boundary of the other face, if both faces are coplanar
+///
+/// The boundary is returned in the surface coordinates of `face`.
+fn boundary_if_coplanar(
+    face: &Face,
+    other: &Face,
+) -> Option<Vec<(SurfacePath, Option<CurveBoundary<Point<1>>>)>> {
+    let tolerance = ValidationConfig::default().distinct_min_distance;
+
+    let surface = face.surface().geometry();
+    let other_surface = other.surface().geometry();
+
+    let plane = plane_from_surface(&surface);
+    let other_plane = plane_from_surface(&other_surface);
+
+    let is_coplanar = other_plane.three_point_form().iter().all(|point| {
+        (*point - plane.origin()).dot(&plane.normal()).abs() < tolerance
+    });
+    if !is_coplanar {
+        return None;
+    }
+
+    let mut boundary = Vec::new();
+
+    for half_edge in other
+        .region()
+        .all_cycles()
+        .flat_map(|cycle| cycle.half_edges())
+    {
+        let [start, end] = half_edge.boundary().inner;
+
+        match half_edge.path() {
+            SurfacePath::Circle(circle) => {
+                let center = plane.project_point(
+                    other_surface.point_from_surface_coords(circle.center()),
+                );
+                let [a, b] = [circle.a(), circle.b()].map(|vector| {
+                    plane.project_vector(
+                        other_surface.vector_from_surface_coords(vector),
+                    )
+                });
+
+                // Circles stay circles, only if the coordinate systems of
+                // both surfaces are compatible.
+                let epsilon = Scalar::from_f64(1e-9) * a.magnitude();
+                let is_circle = (a.magnitude() - b.magnitude()).abs() < epsilon
+                    && a.dot(&b).abs() < epsilon * a.magnitude();
+                if !is_circle {
+                    continue;
+                }
+
+                let mut circle =
+                    Circle::from_center_and_radius(center, a.magnitude());
+                if a.cross2d(&b) < Scalar::ZERO {
+                    circle = circle.reverse();
+                }
+
+                let (sin, cos) = start.t.sin_cos();
+                let start_point = center + a * cos + b * sin;
+                let start_coord = circle.point_to_circle_coords(start_point);
+                let end_coord = start_coord + (end - start);
+
+                boundary.push((
+                    SurfacePath::Circle(circle),
+                    Some(CurveBoundary::from([start_coord, end_coord])),
+                ));
+            }
+            SurfacePath::Line(line) => {
+                let points = [start, end].map(|coord| {
+                    plane.project_point(
+                        other_surface.point_from_surface_coords(
+                            line.point_from_line_coords(coord),
+                        ),
+                    )
+                });
+
+                let (path, coords) = SurfacePath::line_from_points(points);
+                boundary.push((path, Some(CurveBoundary::from(coords))));
+            }
+        }
+    }
+
+    Some(boundary)
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::{Scalar, Vector};
+
+    use crate::{
+        algorithms::mass_properties::MassProperties,
+        objects::{Region, Shell, Sketch},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            sweep::SweepSketch,
+            transform::TransformObject,
+            update::UpdateSketch,
+        },
+        validate::Validate,
+        Instance,
+    };
+
+    use super::Imprint;
+
+    #[test]
+    fn imprint_overlapping_cuboids() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let a = cuboid([2., 2., 2.], [0., 0., 0.], &mut core);
+        let b = cuboid([2., 2., 2.], [1., 1., 1.], &mut core);
+
+        // `b` crosses the three faces of `a` that face in positive direction.
+        // Each of those is split into a square where `b` enters `a`, and the
+        // rest of the face.
+        let imprinted = a.imprint(&b, &mut core);
+
+        assert_eq!(imprinted.faces().len(), 9);
+        assert_eq!(area(&imprinted), area(&a));
+        imprinted.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn imprint_coplanar_faces() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let a = cuboid([2., 2., 2.], [0., 0., 0.], &mut core);
+        let b = cuboid([1., 1., 1.], [0.5, 0.5, 2.], &mut core);
+
+        // The bottom face of `b` sits on the top face of `a`, and imprints its
+        // boundary there. The top face of `a` becomes a face with a hole, and
+        // the face that fills that hole.
+        let imprinted = a.imprint(&b, &mut core);
+
+        assert_eq!(imprinted.faces().len(), 7);
+        let mut num_interiors = imprinted
+            .faces()
+            .iter()
+            .map(|face| face.region().interiors().len())
+            .collect::<Vec<_>>();
+        num_interiors.sort();
+        assert_eq!(num_interiors, [0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(area(&imprinted), area(&a));
+        imprinted.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn imprint_distant_cuboid() {
+        let mut core = Instance::new();
+
+        let a = cuboid([1., 1., 1.], [0., 0., 0.], &mut core);
+        let b = cuboid([1., 1., 1.], [3., 0., 0.], &mut core);
+
+        assert_eq!(a.imprint(&b, &mut core), a);
+    }
+
+    fn cuboid(
+        size: [f64; 3],
+        position: [f64; 3],
+        core: &mut Instance,
+    ) -> Shell {
+        let [x, y, z] = size;
+
+        let surface = core.services.objects.surfaces.xy_plane();
+        let solid = Sketch::empty()
+            .add_regions(
+                [Region::polygon([[0., 0.], [x, 0.], [x, y], [0., y]], core)],
+                core,
+            )
+            .sweep_sketch(surface, [0., 0., z], core);
+
+        solid
+            .shells()
+            .only()
+            .translate(Vector::from(position), core)
+            .clone_object()
+    }
+
+    /// Compute the area of the shell, rounded to cancel out numerical errors
+    fn area(shell: &Shell) -> Scalar {
+        (shell.mass_properties().area * 1e9).round() / 1e9
+    }
+}
@@ -38,11 +38,15 @@
 //! assume that the code in question is outdated. Feel free to open an issue or
 //! send a pull request!
 
+pub mod boolean;
 pub mod build;
+pub mod derive;
 pub mod holes;
+pub mod imprint;
 pub mod insert;
 pub mod join;
 pub mod merge;
+pub mod offset;
 pub mod presentation;
 pub mod replace;
 pub mod reverse;
@@ -0,0 +1,674 @@
+//! # Operations to offset 2d shapes
+//!
+//! See [`Offset`], which is currently the only trait in this module, for more
+//! information.
+
+use fj_interop::Color;
+use fj_math::{Circle, Line, Point, Scalar, Vector, Winding};
+
+use crate::{
+    algorithms::{
+        intersect::{path_path::PathPathIntersection, Intersect},
+        path::{
+            divide_path, path_direction, point_to_path_coords,
+            project_onto_path, TURN_EPSILON,
+        },
+    },
+    geometry::SurfacePath,
+    objects::{Cycle, Region, Sketch},
+    validate::ValidationConfig,
+    Instance,
+};
+
+use super::boolean::{sketch_from_pieces, Piece};
+
+/// Offset the boundary of a 2d shape
+///
+/// Every line and arc of the boundary is moved by the offset distance, along
+/// its normal. Where this opens gaps between neighboring edges, at the convex
+/// corners of the shape, those are closed using the selected [`Join`]. Parts
+/// of the offset boundary that end up closer to the original boundary than the
+/// offset distance, as happens at concave corners, or where a shape becomes
+/// too thin, are trimmed.
+///
+/// The result is a new [`Sketch`], as offsetting can split a shape into
+/// multiple regions, or merge parts of it, creating new holes.
+pub trait Offset {
+    /// Offset the boundary by the provided distance
+    ///
+    /// A positive distance grows the shape, a negative distance shrinks it.
+    #[must_use]
+    fn offset(
+        &self,
+        distance: impl Into<Scalar>,
+        join: Join,
+        core: &mut Instance,
+    ) -> Sketch;
+}
+
+impl Offset for Cycle {
+    /// Offset the boundary by the provided distance
+    ///
+    /// The cycle is treated as the exterior of a region, meaning it is grown,
+    /// if the distance is positive, regardless of its winding.
+    fn offset(
+        &self,
+        distance: impl Into<Scalar>,
+        join: Join,
+        core: &mut Instance,
+    ) -> Sketch {
+        let reverse = self.winding() == Winding::Cw;
+        let cycles = vec![cycle_pieces(self, reverse)];
+
+        offset(cycles, distance.into(), join, None, core)
+    }
+}
+
+impl Offset for Region {
+    fn offset(
+        &self,
+        distance: impl Into<Scalar>,
+        join: Join,
+        core: &mut Instance,
+    ) -> Sketch {
+        let reverse = self.exterior().winding() == Winding::Cw;
+        let cycles = self
+            .all_cycles()
+            .map(|cycle| cycle_pieces(cycle, reverse))
+            .collect();
+
+        offset(cycles, distance.into(), join, self.color(), core)
+    }
+}
+
+/// How to close the gaps that offsetting opens at convex corners
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Join {
+    /// Close the gap with an arc around the original corner
+    Round,
+
+    /// Extend the neighboring edges, until they meet in a sharp corner
+    ///
+    /// If the corner is very sharp, the point where the edges meet would be
+    /// very far from the original corner. If it's further away than
+    /// [`MITER_LIMIT`] times the offset distance, the corner is cut off at
+    /// that distance instead.
+    Miter,
+}
+
+/// The maximum distance of a mitered corner from the original one
+///
+/// This is relative to the offset distance. See [`Join::Miter`].
+pub const MITER_LIMIT: f64 = 4.;
+
+/// Convert a cycle into pieces, that have the shape on their left
+fn cycle_pieces(cycle: &Cycle, reverse: bool) -> Vec<Piece> {
+    let mut pieces = cycle
+        .half_edges()
+        .iter()
+        .map(|half_edge| {
+            let [start, end] = half_edge.boundary().inner.map(|point| point.t);
+            let coords = if reverse { [end, start] } else { [start, end] };
+
+            Piece {
+                path: half_edge.path(),
+                coords,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if reverse {
+        pieces.reverse();
+    }
+
+    pieces
+}
+
+fn offset(
+    cycles: Vec<Vec<Piece>>,
+    distance: Scalar,
+    join: Join,
+    color: Option<Color>,
+    core: &mut Instance,
+) -> Sketch {
+    let tolerance = ValidationConfig::default().distinct_min_distance;
+
+    let mut pieces = Vec::new();
+    for cycle in &cycles {
+        pieces.extend(offset_cycle(cycle, distance, join, tolerance));
+    }
+
+    // The offset pieces might intersect each other. Divide them there, then
+    // remove all that are too close to the original boundary.
+    let original = cycles.iter().flatten().collect::<Vec<_>>();
+    let pieces = divide_pieces(&pieces, tolerance)
+        .into_iter()
+        .filter(|piece| {
+            let [_, point, _] = points(piece);
+
+            original.iter().all(|original| {
+                let (distance_to_original, _) = project_onto_path(
+                    &original.path,
+                    original.coords.map(|coord| Point::from([coord])),
+                    point,
+                );
+                distance_to_original > distance.abs() - tolerance
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Parts of the offset boundary can overlap. Where they overlap in the same
+    // direction, they must only be kept once. Where they overlap in opposite
+    // directions, they cancel each other out.
+    let mut kept: Vec<Piece> = Vec::new();
+    for piece in pieces {
+        let [start, middle, end] = points(&piece);
+
+        let opposite = kept.iter().position(|other| {
+            let [other_start, other_middle, other_end] = points(other);
+            other_middle.distance_to(&middle) < tolerance
+                && other_start.distance_to(&end) < tolerance
+                && other_end.distance_to(&start) < tolerance
+        });
+        if let Some(i) = opposite {
+            kept.remove(i);
+            continue;
+        }
+
+        let is_duplicate = kept.iter().any(|other| {
+            points(other)
+                .into_iter()
+                .zip([start, middle, end])
+                .all(|(a, b)| a.distance_to(&b) < tolerance)
+        });
+        if !is_duplicate {
+            kept.push(piece);
+        }
+    }
+
+    let pieces = merge_lines(kept, tolerance);
+    sketch_from_pieces(pieces, color, tolerance, core)
+}
+
+/// Merge straight pieces that continue each other in the same direction
+///
+/// Dividing the offset boundary leaves behind many of those. Pieces are only
+/// merged, if no other pieces start or end where they meet.
+fn merge_lines(mut pieces: Vec<Piece>, tolerance: Scalar) -> Vec<Piece> {
+    loop {
+        let merge = pieces.iter().enumerate().find_map(|(i, a)| {
+            let [a_start, _, a_end] = points(a);
+
+            let num_meeting = pieces
+                .iter()
+                .flat_map(|piece| {
+                    let [start, _, end] = points(piece);
+                    [start, end]
+                })
+                .filter(|point| point.distance_to(&a_end) < tolerance)
+                .count();
+            if num_meeting != 2 {
+                return None;
+            }
+
+            pieces.iter().enumerate().find_map(|(j, b)| {
+                let [b_start, _, b_end] = points(b);
+
+                let is_continuation = i != j
+                    && matches!(a.path, SurfacePath::Line(_))
+                    && matches!(b.path, SurfacePath::Line(_))
+                    && b_start.distance_to(&a_end) < tolerance;
+                if !is_continuation {
+                    return None;
+                }
+
+                let [a_direction, b_direction] =
+                    [(a_start, a_end), (b_start, b_end)]
+                        .map(|(start, end)| (end - start).normalize());
+                let is_straight = a_direction.dot(&b_direction) > Scalar::ZERO
+                    && a_direction.cross2d(&b_direction).abs()
+                        < Scalar::from_f64(TURN_EPSILON);
+
+                is_straight.then(|| (i, j, line(a_start, b_end, tolerance)))
+            })
+        });
+
+        let Some((i, j, merged)) = merge else {
+            break;
+        };
+
+        let [first, second] = [i.max(j), i.min(j)];
+        pieces.remove(first);
+        pieces.remove(second);
+        pieces.extend(merged);
+    }
+
+    pieces
+}
+
+/// Compute the start, middle, and end points of a piece
+fn points(piece: &Piece) -> [Point<2>; 3] {
+    let [start, end] = piece.coords;
+    [start, (start + end) / Scalar::TWO, end]
+        .map(|coord| piece.path.point_from_path_coords([coord]))
+}
+
+/// Offset a single cycle, without trimming the result
+fn offset_cycle(
+    cycle: &[Piece],
+    distance: Scalar,
+    join: Join,
+    tolerance: Scalar,
+) -> Vec<Piece> {
+    let offsets = cycle
+        .iter()
+        .map(|piece| offset_piece(piece, distance, tolerance))
+        .collect::<Vec<_>>();
+
+    let mut pieces = Vec::new();
+
+    for (i, (piece, offset)) in cycle.iter().zip(&offsets).enumerate() {
+        let next_index = (i + 1) % cycle.len();
+        let (next, next_offset) = (&cycle[next_index], &offsets[next_index]);
+
+        let [_, end] = piece.coords;
+        let [next_start, _] = next.coords;
+        let [start_of_next_offset, _] = next_offset.points();
+        let [_, end_of_offset] = offset.points();
+
+        let corner = piece.path.point_from_path_coords([end]);
+        let [incoming, outgoing] = [(piece, end), (next, next_start)]
+            .map(|(piece, coord)| direction(piece, coord).normalize());
+
+        if let OffsetPiece::Piece(piece) = offset {
+            pieces.push(*piece);
+        }
+
+        let (from, to) = (end_of_offset, start_of_next_offset);
+        if from.distance_to(&to) < tolerance {
+            continue;
+        }
+
+        let turn = incoming.cross2d(&outgoing);
+        let is_collapsed = matches!(offset, OffsetPiece::Collapsed(_))
+            || matches!(next_offset, OffsetPiece::Collapsed(_));
+        let is_convex = !is_collapsed
+            && turn * distance > Scalar::ZERO
+            && turn.abs() > Scalar::from_f64(TURN_EPSILON);
+
+        if !is_convex {
+            // The offset edges overlap, or one of them has collapsed. Connect
+            // them via the original corner. Those connecting lines are too
+            // close to the original boundary, so they will be trimmed.
+            pieces.extend(line(from, corner, tolerance));
+            pieces.extend(line(corner, to, tolerance));
+            continue;
+        }
+
+        match join {
+            Join::Round => {
+                let circle =
+                    Circle::from_center_and_radius(corner, distance.abs());
+                let [start, end] = [from, to]
+                    .map(|point| circle.point_to_circle_coords(point).t);
+
+                let angle = if turn > Scalar::ZERO {
+                    (end - start).into_f64().rem_euclid(Scalar::TAU.into_f64())
+                } else {
+                    -(start - end).into_f64().rem_euclid(Scalar::TAU.into_f64())
+                };
+
+                pieces.push(Piece {
+                    path: SurfacePath::Circle(circle),
+                    coords: [start, start + angle],
+                });
+            }
+            Join::Miter => {
+                // Where the tangent lines through both ends of the gap meet.
+                let along_incoming = (to - from).cross2d(&outgoing) / turn;
+                let miter = from + incoming * along_incoming;
+
+                let limit = distance.abs() * Scalar::from_f64(MITER_LIMIT);
+                if miter.distance_to(&corner) > limit {
+                    // Cut off the corner, perpendicular to its bisector. A
+                    // straight line between both ends of the gap would be
+                    // closer to the original corner than the offset distance,
+                    // and would be trimmed.
+                    let bisector =
+                        ((from - corner) + (to - corner)).normalize();
+                    let cut = corner + bisector * limit;
+
+                    let [start_of_cut, end_of_cut] =
+                        [(from, incoming), (to, outgoing)].map(
+                            |(point, direction)| {
+                                let along = (cut - point).dot(&bisector)
+                                    / direction.dot(&bisector);
+                                point + direction * along
+                            },
+                        );
+
+                    pieces.extend(line(from, start_of_cut, tolerance));
+                    pieces.extend(line(start_of_cut, end_of_cut, tolerance));
+                    pieces.extend(line(end_of_cut, to, tolerance));
+                } else {
+                    pieces.extend(line(from, miter, tolerance));
+                    pieces.extend(line(miter, to, tolerance));
+                }
+            }
+        }
+    }
+
+    pieces
+}
+
+/// The result of offsetting a single piece
+enum OffsetPiece {
+    Piece(Piece),
+
+    /// An arc that shrunk to nothing, leaving only its center
+    Collapsed(Point<2>),
+}
+
+impl OffsetPiece {
+    fn points(&self) -> [Point<2>; 2] {
+        match self {
+            Self::Piece(piece) => piece
+                .coords
+                .map(|coord| piece.path.point_from_path_coords([coord])),
+            Self::Collapsed(center) => [*center, *center],
+        }
+    }
+}
+
+/// Move a piece to its right, or to its left, if the distance is negative
+fn offset_piece(
+    piece: &Piece,
+    distance: Scalar,
+    tolerance: Scalar,
+) -> OffsetPiece {
+    let [start, end] = piece.coords;
+    let normal = {
+        let direction = direction(piece, start).normalize();
+        Vector::from([direction.v, -direction.u])
+    };
+
+    match piece.path {
+        SurfacePath::Line(line) => {
+            let line = Line::from_origin_and_direction(
+                line.origin() + normal * distance,
+                line.direction(),
+            );
+
+            OffsetPiece::Piece(Piece {
+                path: SurfacePath::Line(line),
+                coords: piece.coords,
+            })
+        }
+        SurfacePath::Circle(circle) => {
+            let radial =
+                piece.path.point_from_path_coords([start]) - circle.center();
+            let radius = circle.radius()
+                + distance * normal.dot(&radial).sign().to_scalar();
+
+            if radius < tolerance {
+                return OffsetPiece::Collapsed(circle.center());
+            }
+
+            // Re-create the circle, instead of scaling `a` and `b`, to keep
+            // their lengths exactly equal. This requires converting the
+            // coordinates.
+            let offset =
+                Circle::from_center_and_radius(circle.center(), radius);
+            let phase = circle.a().v.atan2(circle.a().u);
+            let orientation =
+                circle.a().cross2d(&circle.b()).sign().to_scalar();
+
+            OffsetPiece::Piece(Piece {
+                path: SurfacePath::Circle(offset),
+                coords: [start, end].map(|coord| phase + coord * orientation),
+            })
+        }
+    }
+}
+
+/// Compute the direction in which a piece is traversed
+fn direction(piece: &Piece, coord: Scalar) -> Vector<2> {
+    let [start, end] = piece.coords;
+    let direction = path_direction(&piece.path, coord);
+
+    if end < start {
+        -direction
+    } else {
+        direction
+    }
+}
+
+/// Create a straight piece between two points, unless they coincide
+fn line(from: Point<2>, to: Point<2>, tolerance: Scalar) -> Option<Piece> {
+    if from.distance_to(&to) < tolerance {
+        return None;
+    }
+
+    let (path, [start, end]) = SurfacePath::line_from_points([from, to]);
+    Some(Piece {
+        path,
+        coords: [start.t, end.t],
+    })
+}
+
+/// Divide pieces wherever they intersect each other
+fn divide_pieces(pieces: &[Piece], tolerance: Scalar) -> Vec<Piece> {
+    let boundaries = pieces
+        .iter()
+        .map(|piece| piece.coords.map(|coord| Point::from([coord])))
+        .collect::<Vec<_>>();
+
+    let mut divided = Vec::new();
+
+    for (i, piece) in pieces.iter().enumerate() {
+        let mut coords = Vec::new();
+
+        for (j, other) in pieces.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+
+            match (&piece.path, &other.path).intersect() {
+                Some(PathPathIntersection::Points(points)) => {
+                    for [coord, _] in points {
+                        let point = piece.path.point_from_path_coords(coord);
+                        let (distance, _) = project_onto_path(
+                            &other.path,
+                            boundaries[j],
+                            point,
+                        );
+
+                        if distance < tolerance {
+                            coords.push(coord.t);
+                        }
+                    }
+                }
+                Some(PathPathIntersection::Coincident) => {
+                    for coord in other.coords {
+                        let point = other.path.point_from_path_coords([coord]);
+                        coords.push(point_to_path_coords(&piece.path, point));
+                    }
+                }
+                None => {}
+            }
+        }
+
+        let [start, end] = piece.coords;
+        for [min, max] in
+            divide_path(&piece.path, Some(boundaries[i]), coords, tolerance)
+        {
+            let coords = if start < end { [min, max] } else { [max, min] };
+            divided.push(Piece {
+                path: piece.path,
+                coords,
+            });
+        }
+    }
+
+    divided
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::Scalar;
+
+    use crate::{
+        objects::{Cycle, Region, Sketch},
+        operations::{
+            build::{BuildCycle, BuildRegion},
+            insert::Insert,
+            sweep::SweepSketch,
+        },
+        validate::Validate,
+        Instance,
+    };
+
+    use super::{Join, Offset, MITER_LIMIT};
+
+    #[test]
+    fn square_outward_round() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let square = square([0., 0.], 2., &mut core);
+        let sketch = square.offset(1., Join::Round, &mut core);
+
+        assert_regions(&sketch, &[(12. + PI, 0)], &mut core)
+    }
+
+    #[test]
+    fn square_outward_miter() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let square = square([0., 0.], 2., &mut core);
+        let sketch = square.offset(1., Join::Miter, &mut core);
+
+        assert_regions(&sketch, &[(16., 0)], &mut core)
+    }
+
+    #[test]
+    fn square_with_hole_inward() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let region = square_with_hole(&mut core);
+        let sketch = region.offset(-0.25, Join::Miter, &mut core);
+
+        // The exterior shrinks to 3.5 x 3.5, the hole grows to 1.5 x 1.5.
+        assert_regions(&sketch, &[(10., 1)], &mut core)
+    }
+
+    #[test]
+    fn square_with_hole_collapsing() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let region = square_with_hole(&mut core);
+        let sketch = region.offset(0.6, Join::Miter, &mut core);
+
+        // The hole is 1 x 1, so it disappears, once its edges have moved by
+        // more than half of that.
+        assert_regions(&sketch, &[(5.2 * 5.2, 0)], &mut core)
+    }
+
+    #[test]
+    fn sharp_corner_exceeding_miter_limit() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        // The corner at the origin is so sharp, that mitering it would place
+        // the new corner about 20 times the offset distance away.
+        let distance = 0.5;
+        let triangle =
+            Region::polygon([[0., 0.], [10., 0.], [10., 1.]], &mut core);
+        let sketch = triangle.offset(distance, Join::Miter, &mut core);
+
+        assert_eq!(sketch.regions().len(), 1);
+        let region = sketch.regions().first();
+        let min_u = region
+            .exterior()
+            .half_edges()
+            .iter()
+            .map(|half_edge| half_edge.start_position().u)
+            .min()
+            .unwrap();
+        // The corner is cut off at the miter limit, perpendicular to its
+        // bisector, which points almost exactly along the negative u-axis.
+        // Mitering the corner would place it at about -10.
+        let limit = Scalar::from(-distance * MITER_LIMIT);
+        assert!(min_u > limit - Scalar::from(0.05));
+        assert!(min_u < limit + Scalar::from(0.05));
+
+        validate(&sketch, &mut core)
+    }
+
+    fn square(min: [f64; 2], size: f64, core: &mut Instance) -> Region {
+        let [u, v] = min;
+        Region::polygon(
+            [[u, v], [u + size, v], [u + size, v + size], [u, v + size]],
+            core,
+        )
+    }
+
+    fn square_with_hole(core: &mut Instance) -> Region {
+        let exterior =
+            Cycle::polygon([[0., 0.], [4., 0.], [4., 4.], [0., 4.]], core)
+                .insert(core);
+        let interior = Cycle::polygon(
+            [[1.5, 1.5], [1.5, 2.5], [2.5, 2.5], [2.5, 1.5]],
+            core,
+        )
+        .insert(core);
+
+        Region::new(exterior, [interior], None)
+    }
+
+    /// Check the area and number of holes of each region of the sketch
+    fn assert_regions(
+        sketch: &Sketch,
+        expected: &[(f64, usize)],
+        core: &mut Instance,
+    ) -> anyhow::Result<()> {
+        let mut regions = sketch
+            .regions()
+            .iter()
+            .map(|region| {
+                let area =
+                    region.interiors().iter().fold(
+                        region.exterior().signed_area(),
+                        |area, interior| area + interior.signed_area(),
+                    );
+                (area, region.interiors().len())
+            })
+            .collect::<Vec<_>>();
+        regions.sort();
+
+        assert_eq!(regions.len(), expected.len());
+        for ((area, num_holes), (expected_area, expected_holes)) in
+            regions.into_iter().zip(expected)
+        {
+            assert!(
+                (area - Scalar::from(*expected_area)).abs()
+                    < Scalar::from(1e-6),
+                "Unexpected area: {area} (expected {expected_area})"
+            );
+            assert_eq!(num_holes, *expected_holes);
+        }
+
+        validate(sketch, core)
+    }
+
+    /// Validate the sketch, and the solid it sweeps into
+    fn validate(sketch: &Sketch, core: &mut Instance) -> anyhow::Result<()> {
+        sketch.validate_and_return_first_error()?;
+
+        let surface = core.services.objects.surfaces.xy_plane();
+        let solid = sketch.sweep_sketch(surface, [0., 0., 1.], core);
+        solid.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+}
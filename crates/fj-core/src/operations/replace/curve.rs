@@ -4,7 +4,7 @@ use crate::{
     objects::{
         Curve, Cycle, Face, HalfEdge, IsObject, Region, Shell, Sketch, Solid,
     },
-    operations::{insert::Insert, update::UpdateHalfEdge},
+    operations::{derive::DeriveFrom, insert::Insert, update::UpdateHalfEdge},
     storage::Handle,
     Instance,
 };
@@ -52,13 +52,15 @@ impl ReplaceCurve for Cycle {
         let mut replacement_happened = false;
 
         let mut half_edges = Vec::new();
-        for half_edge in self.half_edges() {
+        for handle in self.half_edges() {
             let half_edge =
-                half_edge.replace_curve(original, replacement.clone(), core);
+                handle.replace_curve(original, replacement.clone(), core);
             replacement_happened |= half_edge.was_updated();
             half_edges.push(
                 half_edge
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(handle, core)
+                    })
                     .into_inner(),
             );
         }
@@ -86,13 +88,15 @@ impl ReplaceCurve for Region {
         replacement_happened |= exterior.was_updated();
 
         let mut interiors = Vec::new();
-        for cycle in self.interiors() {
+        for handle in self.interiors() {
             let cycle =
-                cycle.replace_curve(original, replacement.clone(), core);
+                handle.replace_curve(original, replacement.clone(), core);
             replacement_happened |= cycle.was_updated();
             interiors.push(
                 cycle
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(handle, core)
+                    })
                     .into_inner(),
             );
         }
@@ -100,7 +104,9 @@ impl ReplaceCurve for Region {
         if replacement_happened {
             ReplaceOutput::Updated(Region::new(
                 exterior
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(self.exterior(), core)
+                    })
                     .into_inner(),
                 interiors,
                 self.color(),
@@ -121,13 +127,15 @@ impl ReplaceCurve for Sketch {
         let mut replacement_happened = false;
 
         let mut regions = Vec::new();
-        for region in self.regions() {
+        for handle in self.regions() {
             let region =
-                region.replace_curve(original, replacement.clone(), core);
+                handle.replace_curve(original, replacement.clone(), core);
             replacement_happened |= region.was_updated();
             regions.push(
                 region
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(handle, core)
+                    })
                     .into_inner(),
             );
         }
@@ -153,7 +161,9 @@ impl ReplaceCurve for Face {
             ReplaceOutput::Updated(Face::new(
                 self.surface().clone(),
                 region
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(self.region(), core)
+                    })
                     .into_inner(),
             ))
         } else {
@@ -172,12 +182,15 @@ impl ReplaceCurve for Shell {
         let mut replacement_happened = false;
 
         let mut faces = Vec::new();
-        for face in self.faces() {
-            let face = face.replace_curve(original, replacement.clone(), core);
+        for handle in self.faces() {
+            let face =
+                handle.replace_curve(original, replacement.clone(), core);
             replacement_happened |= face.was_updated();
             faces.push(
-                face.map_updated(|updated| updated.insert(core))
-                    .into_inner(),
+                face.map_updated(|updated| {
+                    updated.insert(core).derive_from(handle, core)
+                })
+                .into_inner(),
             );
         }
 
@@ -199,13 +212,15 @@ impl ReplaceCurve for Solid {
         let mut replacement_happened = false;
 
         let mut shells = Vec::new();
-        for shell in self.shells() {
+        for handle in self.shells() {
             let shell =
-                shell.replace_curve(original, replacement.clone(), core);
+                handle.replace_curve(original, replacement.clone(), core);
             replacement_happened |= shell.was_updated();
             shells.push(
                 shell
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(handle, core)
+                    })
                     .into_inner(),
             );
         }
@@ -2,7 +2,7 @@ use std::ops::Deref;
 
 use crate::{
     objects::{Cycle, Face, HalfEdge, IsObject, Region, Shell, Sketch, Solid},
-    operations::insert::Insert,
+    operations::{derive::DeriveFrom, insert::Insert},
     storage::Handle,
     Instance,
 };
@@ -30,11 +30,16 @@ impl ReplaceHalfEdge for Cycle {
         &self,
         original: &Handle<HalfEdge>,
         replacements: [Handle<HalfEdge>; N],
-        _: &mut Instance,
+        core: &mut Instance,
     ) -> ReplaceOutput<Self, Self::BareObject> {
         if let Some(half_edges) =
-            self.half_edges().replace(original, replacements)
+            self.half_edges().replace(original, replacements.clone())
         {
+            for replacement in replacements {
+                core.services
+                    .derive_object(original.clone().into(), replacement.into());
+            }
+
             ReplaceOutput::Updated(Cycle::new(half_edges))
         } else {
             ReplaceOutput::Original(self.clone())
@@ -59,13 +64,15 @@ impl ReplaceHalfEdge for Region {
         replacement_happened |= exterior.was_updated();
 
         let mut interiors = Vec::new();
-        for cycle in self.interiors() {
+        for handle in self.interiors() {
             let cycle =
-                cycle.replace_half_edge(original, replacements.clone(), core);
+                handle.replace_half_edge(original, replacements.clone(), core);
             replacement_happened |= cycle.was_updated();
             interiors.push(
                 cycle
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(handle, core)
+                    })
                     .into_inner(),
             );
         }
@@ -73,7 +80,9 @@ impl ReplaceHalfEdge for Region {
         if replacement_happened {
             ReplaceOutput::Updated(Region::new(
                 exterior
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(self.exterior(), core)
+                    })
                     .into_inner(),
                 interiors,
                 self.color(),
@@ -94,13 +103,15 @@ impl ReplaceHalfEdge for Sketch {
         let mut replacement_happened = false;
 
         let mut regions = Vec::new();
-        for region in self.regions() {
+        for handle in self.regions() {
             let region =
-                region.replace_half_edge(original, replacements.clone(), core);
+                handle.replace_half_edge(original, replacements.clone(), core);
             replacement_happened |= region.was_updated();
             regions.push(
                 region
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(handle, core)
+                    })
                     .into_inner(),
             );
         }
@@ -128,7 +139,9 @@ impl ReplaceHalfEdge for Face {
             ReplaceOutput::Updated(Face::new(
                 self.surface().clone(),
                 region
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(self.region(), core)
+                    })
                     .into_inner(),
             ))
         } else {
@@ -147,13 +160,15 @@ impl ReplaceHalfEdge for Shell {
         let mut replacement_happened = false;
 
         let mut faces = Vec::new();
-        for face in self.faces() {
+        for handle in self.faces() {
             let face =
-                face.replace_half_edge(original, replacements.clone(), core);
+                handle.replace_half_edge(original, replacements.clone(), core);
             replacement_happened |= face.was_updated();
             faces.push(
-                face.map_updated(|updated| updated.insert(core))
-                    .into_inner(),
+                face.map_updated(|updated| {
+                    updated.insert(core).derive_from(handle, core)
+                })
+                .into_inner(),
             );
         }
 
@@ -175,13 +190,15 @@ impl ReplaceHalfEdge for Solid {
         let mut replacement_happened = false;
 
         let mut shells = Vec::new();
-        for shell in self.shells() {
+        for handle in self.shells() {
             let shell =
-                shell.replace_half_edge(original, replacements.clone(), core);
+                handle.replace_half_edge(original, replacements.clone(), core);
             replacement_happened |= shell.was_updated();
             shells.push(
                 shell
-                    .map_updated(|updated| updated.insert(core))
+                    .map_updated(|updated| {
+                        updated.insert(core).derive_from(handle, core)
+                    })
                     .into_inner(),
             );
         }
@@ -1,14 +1,21 @@
-use crate::{objects::Region, operations::insert::Insert, Instance};
+use crate::{
+    objects::Region,
+    operations::{derive::DeriveFrom, insert::Insert},
+    Instance,
+};
 
 use super::{Reverse, ReverseCurveCoordinateSystems};
 
 impl Reverse for Region {
     fn reverse(&self, core: &mut Instance) -> Self {
-        let exterior = self.exterior().reverse(core).insert(core);
-        let interiors = self
-            .interiors()
-            .iter()
-            .map(|cycle| cycle.reverse(core).insert(core));
+        let exterior = self
+            .exterior()
+            .reverse(core)
+            .insert(core)
+            .derive_from(self.exterior(), core);
+        let interiors = self.interiors().iter().map(|cycle| {
+            cycle.reverse(core).insert(core).derive_from(cycle, core)
+        });
 
         Region::new(exterior, interiors, self.color())
     }
@@ -19,9 +26,13 @@ impl ReverseCurveCoordinateSystems for Region {
         let exterior = self
             .exterior()
             .reverse_curve_coordinate_systems(core)
-            .insert(core);
+            .insert(core)
+            .derive_from(self.exterior(), core);
         let interiors = self.interiors().iter().map(|cycle| {
-            cycle.reverse_curve_coordinate_systems(core).insert(core)
+            cycle
+                .reverse_curve_coordinate_systems(core)
+                .insert(core)
+                .derive_from(cycle, core)
         });
 
         Region::new(exterior, interiors, self.color())
@@ -1,11 +1,20 @@
 use fj_interop::ext::ArrayExt;
-use fj_math::Point;
+use fj_math::{Point, Scalar};
 use itertools::Itertools;
 
 use crate::{
-    objects::{Face, HalfEdge, Shell},
+    algorithms::{
+        intersect::{path_path::PathPathIntersection, Intersect},
+        path::{
+            divide_path, point_to_path_coords, project_onto_path,
+            trace_regions, winding_number,
+        },
+    },
+    geometry::{CurveBoundary, SurfacePath},
+    objects::{Cycle, Face, HalfEdge, Region, Shell, Vertex},
     operations::{
         build::{BuildFace, BuildHalfEdge},
+        derive::DeriveFrom,
         insert::Insert,
         presentation::SetColor,
         split::SplitEdge,
@@ -14,6 +23,7 @@ use crate::{
         },
     },
     storage::Handle,
+    validate::ValidationConfig,
     Instance,
 };
 
@@ -32,9 +42,8 @@ pub trait SplitFace: Sized {
     /// # Implementation Note
     ///
     /// The way the split line is specified is rather inconvenient, and not very
-    /// flexible. This is an artifact of the current implementation, and more
-    /// flexible and convenient ways to split the face (like an arbitrary curve)
-    /// can be provided later.
+    /// flexible. See [`SplitFace::split_face_along_path`], for a more flexible
+    /// alternative.
     #[must_use]
     fn split_face(
         &self,
@@ -42,6 +51,58 @@ pub trait SplitFace: Sized {
         line: [(&Handle<HalfEdge>, impl Into<Point<1>>); 2],
         core: &mut Instance,
     ) -> (Self, [Handle<Face>; 2]);
+
+    /// Split the face along a path
+    ///
+    /// The path is defined in the surface coordinates of the face. Every part
+    /// of the path that runs through the inside of the face becomes a new edge,
+    /// dividing the face. This works for lines that cross the face, touch its
+    /// interior cycles, or run through its vertices, as well as for circles
+    /// that lie within the face or cross its boundary.
+    ///
+    /// Returns the updated shell, as well as the faces that replace the
+    /// original face. If the path doesn't divide the face, the shell is
+    /// returned unchanged, together with the original face.
+    ///
+    /// Edges of the face that the path crosses are split, together with their
+    /// siblings in neighboring faces. The color of the original face is carried
+    /// over to all new faces.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the face is not part of the shell.
+    #[must_use]
+    fn split_face_along_path(
+        &self,
+        face: &Handle<Face>,
+        path: SurfacePath,
+        core: &mut Instance,
+    ) -> (Self, Vec<Handle<Face>>);
+
+    /// Split the face along multiple paths
+    ///
+    /// Works like [`SplitFace::split_face_along_path`], but splits the face
+    /// along all provided paths at once. Each path can optionally be restricted
+    /// to a boundary, in path coordinates. Lines without a boundary extend
+    /// through the whole face, circles without a boundary are complete.
+    ///
+    /// Parts of bounded paths that end inside of the face, without meeting
+    /// another path there, can't divide the face. They are ignored.
+    ///
+    /// The new faces take the place of the original face within the shell.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the face is not part of the shell.
+    #[must_use]
+    fn split_face_along_paths(
+        &self,
+        face: &Handle<Face>,
+        paths: impl IntoIterator<
+            Item = (SurfacePath, Option<CurveBoundary<Point<1>>>),
+        >,
+        core: &mut Instance,
+    ) -> (Self, Vec<Handle<Face>>);
 }
 
 impl SplitFace for Shell {
@@ -204,4 +265,588 @@ impl SplitFace for Shell {
 
         (self_, faces)
     }
+
+    fn split_face_along_path(
+        &self,
+        face: &Handle<Face>,
+        path: SurfacePath,
+        core: &mut Instance,
+    ) -> (Self, Vec<Handle<Face>>) {
+        split_face_along_paths(self, face, &[(path, None)], core)
+    }
+
+    fn split_face_along_paths(
+        &self,
+        face: &Handle<Face>,
+        paths: impl IntoIterator<
+            Item = (SurfacePath, Option<CurveBoundary<Point<1>>>),
+        >,
+        core: &mut Instance,
+    ) -> (Self, Vec<Handle<Face>>) {
+        let paths = paths
+            .into_iter()
+            .map(|(path, boundary)| {
+                (path, boundary.map(|boundary| boundary.inner))
+            })
+            .collect::<Vec<_>>();
+
+        split_face_along_paths(self, face, &paths, core)
+    }
+}
+
+/// Split a face along any number of paths
+///
+/// Each path can optionally be restricted to a boundary, in path coordinates.
+/// Unrestricted lines extend through the whole face, unrestricted circles are
+/// complete.
+fn split_face_along_paths(
+    shell: &Shell,
+    face: &Handle<Face>,
+    paths: &[(SurfacePath, Option<[Point<1>; 2]>)],
+    core: &mut Instance,
+) -> (Shell, Vec<Handle<Face>>) {
+    assert!(
+        shell.faces().contains(face),
+        "Face to split must be part of the shell"
+    );
+
+    let tolerance = ValidationConfig::default().distinct_min_distance;
+
+    let boundary = face
+        .region()
+        .all_cycles()
+        .flat_map(|cycle| cycle.half_edges().iter().cloned())
+        .collect::<Vec<_>>();
+
+    // Find the pieces of the paths that divide the face. Those will become the
+    // new edges.
+    let mut pieces = Vec::new();
+    for (i, (path, path_boundary)) in paths.iter().enumerate() {
+        let mut coords = Vec::new();
+
+        for half_edge in &boundary {
+            match (path, &half_edge.path()).intersect() {
+                Some(PathPathIntersection::Points(points)) => {
+                    for [coord, _] in points {
+                        let point = path.point_from_path_coords(coord);
+                        let (distance, _) = project_onto_path(
+                            &half_edge.path(),
+                            half_edge.boundary().inner,
+                            point,
+                        );
+
+                        if distance < tolerance {
+                            coords.push(coord.t);
+                        }
+                    }
+                }
+                Some(PathPathIntersection::Coincident) => {
+                    let [start, end] =
+                        half_edge.boundary().inner.map(|coord| {
+                            half_edge.path().point_from_path_coords(coord)
+                        });
+
+                    for point in [start, end] {
+                        coords.push(point_to_path_coords(path, point));
+                    }
+                }
+                None => {}
+            }
+        }
+
+        for (j, (other, other_boundary)) in paths.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+
+            let Some(PathPathIntersection::Points(points)) =
+                (path, other).intersect()
+            else {
+                continue;
+            };
+
+            for [coord, coord_on_other] in points {
+                if let Some(other_boundary) = other_boundary {
+                    let point = other.point_from_path_coords(coord_on_other);
+                    let (distance, _) =
+                        project_onto_path(other, *other_boundary, point);
+
+                    if distance >= tolerance {
+                        continue;
+                    }
+                }
+
+                coords.push(coord.t);
+            }
+        }
+
+        for [start, end] in divide_path(path, *path_boundary, coords, tolerance)
+        {
+            let middle =
+                path.point_from_path_coords([(start + end) / Scalar::TWO]);
+
+            if face_contains_point(&boundary, face, middle, tolerance) {
+                pieces.push((*path, [start, end]));
+            }
+        }
+    }
+
+    // Pieces that end inside of the face, without meeting another piece there,
+    // can't divide the face. Remove them, until none are left.
+    loop {
+        let endpoints = pieces
+            .iter()
+            .flat_map(|(path, coords): &(SurfacePath, [Scalar; 2])| {
+                coords.map(|coord| path.point_from_path_coords([coord]))
+            })
+            .collect::<Vec<_>>();
+
+        let num_pieces = pieces.len();
+        pieces.retain(|(path, coords)| {
+            coords.iter().all(|&coord| {
+                let point = path.point_from_path_coords([coord]);
+
+                let is_on_boundary = boundary.iter().any(|half_edge| {
+                    let (distance, _) = project_onto_path(
+                        &half_edge.path(),
+                        half_edge.boundary().inner,
+                        point,
+                    );
+                    distance < tolerance
+                });
+                let num_meeting_pieces = endpoints
+                    .iter()
+                    .filter(|endpoint| endpoint.distance_to(&point) < tolerance)
+                    .count();
+
+                is_on_boundary || num_meeting_pieces > 1
+            })
+        });
+
+        if pieces.len() == num_pieces {
+            break;
+        }
+    }
+
+    if pieces.is_empty() {
+        return (shell.clone(), vec![face.clone()]);
+    }
+
+    // Where pieces meet the boundary of the face between vertices, the
+    // boundary needs to be split.
+    let mut splits: Vec<(Handle<HalfEdge>, Vec<Scalar>)> = Vec::new();
+    for (path, coords) in &pieces {
+        for &coord in coords {
+            let point = path.point_from_path_coords([coord]);
+
+            for half_edge in &boundary {
+                let [start, end] = half_edge.boundary().inner.map(|coord| {
+                    half_edge.path().point_from_path_coords(coord)
+                });
+                if start.distance_to(&point) < tolerance
+                    || end.distance_to(&point) < tolerance
+                {
+                    break;
+                }
+
+                let (distance, coord) = project_onto_path(
+                    &half_edge.path(),
+                    half_edge.boundary().inner,
+                    point,
+                );
+                if distance < tolerance {
+                    match splits.iter_mut().find(|(h, _)| h == half_edge) {
+                        Some((_, coords)) => coords.push(coord),
+                        None => splits.push((half_edge.clone(), vec![coord])),
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    let mut shell = shell.clone();
+    let mut tracked_half_edge =
+        face.region().exterior().half_edges().first().clone();
+
+    for (half_edge, mut coords) in splits {
+        let [start, end] = half_edge.boundary().inner.map(|point| point.t);
+
+        coords.sort_by_key(|&coord| (coord - start).abs());
+        coords.dedup_by(|a, b| {
+            let [a, b] = [a, b]
+                .map(|coord| half_edge.path().point_from_path_coords([*coord]));
+            a.distance_to(&b) < tolerance
+        });
+        coords.retain(|&coord| coord != start && coord != end);
+
+        let mut half_edge = half_edge;
+        for coord in coords {
+            let (updated, [[a, b], _]) =
+                shell.split_edge(&half_edge, [coord], core);
+
+            if half_edge == tracked_half_edge {
+                tracked_half_edge = a;
+            }
+
+            shell = updated;
+            half_edge = b;
+        }
+    }
+
+    // The face has been replaced, if any edges have been split. Find its new
+    // version.
+    let face = shell
+        .faces()
+        .iter()
+        .find(|face| {
+            face.region()
+                .exterior()
+                .half_edges()
+                .contains(&tracked_half_edge)
+        })
+        .expect("Updated shell must contain updated face")
+        .clone();
+
+    // Collect all half-edges that are going to bound the new faces, starting
+    // with those of the face itself.
+    let mut half_edges = Vec::new();
+    let mut vertices = Vec::new();
+    for cycle in face.region().all_cycles() {
+        for (half_edge, next) in cycle.half_edges().pairs() {
+            half_edges.push((half_edge.clone(), next.start_vertex().clone()));
+            vertices.push((
+                half_edge.start_position(),
+                half_edge.start_vertex().clone(),
+            ));
+        }
+    }
+
+    for (path, [start, end]) in pieces {
+        let [(start_position, start_vertex), (end_position, end_vertex)] =
+            [start, end].map(|coord| {
+                let point = path.point_from_path_coords([coord]);
+
+                if let Some((position, vertex)) =
+                    vertices.iter().find(|(position, _)| {
+                        position.distance_to(&point) < tolerance
+                    })
+                {
+                    return (*position, vertex.clone());
+                }
+
+                let vertex = Vertex::new().insert(core);
+                vertices.push((point, vertex.clone()));
+                (point, vertex)
+            });
+
+        let is_closed = (end - start).abs() >= Scalar::TAU;
+        if start_vertex.id() == end_vertex.id() && !is_closed {
+            continue;
+        }
+
+        let half_edge = match path {
+            SurfacePath::Circle(_) => {
+                HalfEdge::unjoined(path, [[start], [end]], core)
+            }
+            SurfacePath::Line(_) => HalfEdge::line_segment(
+                [start_position, end_position],
+                None,
+                core,
+            ),
+        }
+        .update_start_vertex(|_, _| start_vertex.clone(), core);
+        let sibling = HalfEdge::from_sibling(&half_edge, end_vertex.clone());
+
+        half_edges.push((half_edge.insert(core), end_vertex));
+        half_edges.push((sibling.insert(core), start_vertex));
+    }
+
+    // Trace the cycles that are formed by the half-edges. The face is on the
+    // left of each half-edge, if the face's coordinate system is right-handed,
+    // on the right otherwise.
+    let (half_edges, end_vertices): (Vec<_>, Vec<_>) =
+        half_edges.into_iter().unzip();
+    let regions = trace_regions(
+        &half_edges,
+        |i, j| half_edges[j].start_vertex().id() == end_vertices[i].id(),
+        face.coord_handedness(),
+    );
+
+    let faces = regions
+        .into_iter()
+        .map(|(exterior, interiors)| {
+            let [exterior, interiors] =
+                [vec![exterior], interiors].map(|cycles| {
+                    cycles
+                        .into_iter()
+                        .map(|half_edges| {
+                            // Reuse existing cycles that didn't change.
+                            face.region()
+                                .all_cycles()
+                                .find(|cycle| {
+                                    cycle.half_edges().len() == half_edges.len()
+                                        && half_edges.iter().all(|half_edge| {
+                                            cycle
+                                                .half_edges()
+                                                .contains(half_edge)
+                                        })
+                                })
+                                .cloned()
+                                .unwrap_or_else(|| {
+                                    Cycle::new(half_edges).insert(core)
+                                })
+                        })
+                        .collect::<Vec<_>>()
+                });
+            let exterior =
+                exterior.into_iter().next().expect("Just created exterior");
+
+            let region =
+                Region::new(exterior, interiors, face.region().color())
+                    .insert(core);
+            Face::new(face.surface().clone(), region)
+                .insert(core)
+                .derive_from(&face, core)
+        })
+        .collect::<Vec<_>>();
+
+    // The new faces take the place of the original one, so the order of the
+    // faces in the shell is preserved.
+    let shell = Shell::new(shell.faces().iter().flat_map(|f| {
+        if f == &face {
+            faces.clone()
+        } else {
+            vec![f.clone()]
+        }
+    }));
+
+    (shell, faces)
+}
+
+/// Determine whether a point is inside of a face, and not on its boundary
+fn face_contains_point(
+    boundary: &[Handle<HalfEdge>],
+    face: &Face,
+    point: Point<2>,
+    tolerance: Scalar,
+) -> bool {
+    let is_on_boundary = boundary.iter().any(|half_edge| {
+        let (distance, _) = project_onto_path(
+            &half_edge.path(),
+            half_edge.boundary().inner,
+            point,
+        );
+        distance < tolerance
+    });
+    if is_on_boundary {
+        return false;
+    }
+
+    let winding_number = face
+        .region()
+        .all_cycles()
+        .map(|cycle| {
+            let half_edges =
+                cycle.half_edges().iter().cloned().collect::<Vec<_>>();
+            winding_number(&half_edges, point)
+        })
+        .sum::<i64>();
+
+    winding_number != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_math::Scalar;
+
+    use crate::{
+        algorithms::mass_properties::MassProperties,
+        geometry::SurfacePath,
+        objects::{Face, Region, Shell, Sketch},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            sweep::SweepSketch,
+            update::UpdateSketch,
+        },
+        queries::Select,
+        storage::Handle,
+        validate::Validate,
+        Instance,
+    };
+
+    use super::SplitFace;
+
+    #[test]
+    fn split_along_crossing_line() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let (shell, face) = cube_and_bottom_face(&mut core);
+        let (path, _) = SurfacePath::line_from_points([[1., -1.], [1., 3.]]);
+        let (shell, faces) =
+            shell.split_face_along_path(&face, path, &mut core);
+
+        assert_eq!(shell.faces().len(), 7);
+        assert_areas(&faces, [2., 2.]);
+        shell.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_along_circle_inside_face() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let (shell, face) = cube_and_bottom_face(&mut core);
+        let path = SurfacePath::circle_from_center_and_radius([1., 1.], 0.5);
+        let (shell, faces) =
+            shell.split_face_along_path(&face, path, &mut core);
+
+        assert_eq!(shell.faces().len(), 7);
+        assert_areas(&faces, [4. - PI / 4., PI / 4.]);
+
+        let mut num_interiors = faces
+            .iter()
+            .map(|face| face.region().interiors().len())
+            .collect::<Vec<_>>();
+        num_interiors.sort();
+        assert_eq!(num_interiors, [0, 1]);
+
+        shell.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_along_line_through_interior_cycle() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let (shell, face) = cube_and_bottom_face(&mut core);
+        let path = SurfacePath::circle_from_center_and_radius([1., 1.], 0.5);
+        let (shell, faces) =
+            shell.split_face_along_path(&face, path, &mut core);
+        let face = faces
+            .into_iter()
+            .find(|face| face.region().interiors().len() == 1)
+            .expect("Expected face with hole");
+
+        let (path, _) = SurfacePath::line_from_points([[1., -1.], [1., 3.]]);
+        let (shell, faces) =
+            shell.split_face_along_path(&face, path, &mut core);
+
+        assert_eq!(shell.faces().len(), 8);
+        let half = (4. - PI / 4.) / 2.;
+        assert_areas(&faces, [half, half]);
+        assert!(faces
+            .iter()
+            .all(|face| face.region().interiors().is_empty()));
+        shell.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_along_line_through_vertices() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        // The diagonal meets the boundary of the face exactly at its vertices.
+        // No edges need to be split, and the new edges leave those vertices in
+        // between the existing ones.
+        let (shell, face) = cube_and_bottom_face(&mut core);
+        let (path, _) = SurfacePath::line_from_points([[0., 0.], [2., 2.]]);
+        let (shell, faces) =
+            shell.split_face_along_path(&face, path, &mut core);
+
+        assert_eq!(shell.faces().len(), 7);
+        assert_areas(&faces, [2., 2.]);
+        assert!(faces.iter().all(|face| face
+            .region()
+            .exterior()
+            .half_edges()
+            .len()
+            == 3));
+        shell.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_along_circle_touching_boundary() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        // The circle touches the boundary of the face at a single point, where
+        // it leaves in the same direction as the boundary. Only its curvature
+        // tells those apart.
+        let (shell, face) = cube_and_bottom_face(&mut core);
+        let path = SurfacePath::circle_from_center_and_radius([1., 0.5], 0.5);
+        let (shell, faces) =
+            shell.split_face_along_path(&face, path, &mut core);
+
+        assert_eq!(shell.faces().len(), 7);
+        assert_areas(&faces, [4. - PI / 4., PI / 4.]);
+        assert!(faces
+            .iter()
+            .all(|face| face.region().interiors().is_empty()));
+        shell.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn path_that_misses_face() {
+        let mut core = Instance::new();
+
+        let (shell, face) = cube_and_bottom_face(&mut core);
+        let (path, _) = SurfacePath::line_from_points([[3., -1.], [3., 3.]]);
+        let (split, faces) =
+            shell.split_face_along_path(&face, path, &mut core);
+
+        assert_eq!(split, shell);
+        assert_eq!(faces, [face]);
+    }
+
+    /// Build a 2x2x1 cuboid, and return its shell and its bottom face
+    ///
+    /// The bottom face is defined on the xy-plane, so its surface coordinates
+    /// match the global x and y coordinates.
+    fn cube_and_bottom_face(core: &mut Instance) -> (Shell, Handle<Face>) {
+        let surface = core.services.objects.surfaces.xy_plane();
+        let solid = Sketch::empty()
+            .add_regions(
+                [Region::polygon(
+                    [[0., 0.], [2., 0.], [2., 2.], [0., 2.]],
+                    core,
+                )],
+                core,
+            )
+            .sweep_sketch(surface, [0., 0., 1.], core);
+
+        let shell = solid.shells().only().clone_object();
+        let face = shell.select_faces().with_normal([0., 0., -1.]).only();
+
+        (shell, face)
+    }
+
+    /// Check the areas of the faces, regardless of their order
+    fn assert_areas<const N: usize>(
+        faces: &[Handle<Face>],
+        mut expected: [f64; N],
+    ) {
+        let mut areas = faces
+            .iter()
+            .map(|face| face.mass_properties().area)
+            .collect::<Vec<_>>();
+        areas.sort();
+        expected.sort_by(f64::total_cmp);
+
+        assert_eq!(areas.len(), N, "Unexpected number of faces: {areas:?}");
+        for (area, expected) in areas.iter().zip(expected) {
+            assert!(
+                (*area - Scalar::from_f64(expected)).abs() < Scalar::from(1e-9),
+                "Unexpected areas: {areas:?}, expected {expected:?}"
+            );
+        }
+    }
 }
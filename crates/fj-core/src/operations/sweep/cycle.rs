@@ -4,8 +4,10 @@ use fj_math::Vector;
 use crate::{
     objects::{Cycle, Face, Surface},
     operations::{
-        build::BuildCycle, join::JoinCycle, sweep::half_edge::SweepHalfEdge,
+        build::BuildCycle, derive::DeriveFrom, insert::Insert, join::JoinCycle,
+        sweep::half_edge::SweepHalfEdge,
     },
+    storage::Handle,
     Instance,
 };
 
@@ -21,7 +23,8 @@ pub trait SweepCycle {
     ///
     /// Sweep the cycle into a set of connected faces. Each half-edge in the
     /// cycle is swept into a face, meaning all resulting faces form a connected
-    /// set of side walls.
+    /// set of side walls. Each face, as well as its top edge, is recorded as
+    /// derived from the half-edge it was swept from.
     ///
     /// Requires the surface that the half-edges of the cycle are defined in,
     /// and optionally the color of the created faces.
@@ -72,6 +75,10 @@ impl SweepCycle for Cycle {
                 core,
             );
 
+            let side_face =
+                side_face.insert(core).derive_from(bottom_half_edge, core);
+            let top_edge = top_edge.derive_from(bottom_half_edge, core);
+
             faces.push(side_face);
 
             top_edges.push((
@@ -94,7 +101,7 @@ pub struct SweptCycle {
     /// The faces created by sweeping each half-edge of the cycle
     ///
     /// See [`SweepCycle::sweep_cycle`] for more information.
-    pub faces: Vec<Face>,
+    pub faces: Vec<Handle<Face>>,
 
     /// A cycle made up of the "top" half-edges of the resulting faces
     ///
@@ -2,7 +2,7 @@ use fj_math::Vector;
 
 use crate::{
     objects::{Face, Shell},
-    operations::insert::Insert,
+    operations::derive::DeriveFrom,
     storage::Handle,
     Instance,
 };
@@ -47,15 +47,18 @@ impl SweepFace for Handle<Face> {
         let path = path.into();
 
         let bottom_face = self;
-        let other_faces = bottom_face
-            .region()
-            .sweep_region(bottom_face.surface(), path, cache, core)
-            .all_faces()
-            .map(|side_face| side_face.insert(core));
+        let swept_region = bottom_face.region().sweep_region(
+            bottom_face.surface(),
+            path,
+            cache,
+            core,
+        );
+        let top_face = swept_region.top_face.derive_from(bottom_face, core);
 
         let mut faces = Vec::new();
         faces.push(bottom_face.clone());
-        faces.extend(other_faces);
+        faces.extend(swept_region.side_faces);
+        faces.push(top_face);
 
         Shell::new(faces)
     }
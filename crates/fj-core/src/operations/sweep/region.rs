@@ -82,7 +82,7 @@ impl SweepRegion for Region {
                 Region::new(top_exterior, top_interiors, self.color())
                     .insert(core);
 
-            Face::new(top_surface, top_region)
+            Face::new(top_surface, top_region).insert(core)
         };
 
         SweptRegion {
@@ -96,7 +96,7 @@ fn sweep_cycle(
     bottom_cycle: &Cycle,
     bottom_surface: &Surface,
     color: Option<Color>,
-    faces: &mut Vec<Face>,
+    faces: &mut Vec<Handle<Face>>,
     path: Vector<3>,
     cache: &mut SweepCache,
     core: &mut Instance,
@@ -119,15 +119,15 @@ fn sweep_cycle(
 /// See [`SweepRegion`].
 pub struct SweptRegion {
     /// The side faces created by the sweep
-    pub side_faces: Vec<Face>,
+    pub side_faces: Vec<Handle<Face>>,
 
     /// The top face created by the sweep
-    pub top_face: Face,
+    pub top_face: Handle<Face>,
 }
 
 impl SweptRegion {
     /// Return an iterator over all of the faces
-    pub fn all_faces(self) -> impl Iterator<Item = Face> {
+    pub fn all_faces(self) -> impl Iterator<Item = Handle<Face>> {
         self.side_faces.into_iter().chain([self.top_face])
     }
 }
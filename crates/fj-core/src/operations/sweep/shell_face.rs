@@ -3,6 +3,7 @@ use fj_math::Vector;
 use crate::{
     objects::{Face, Region, Shell},
     operations::{
+        derive::DeriveFrom,
         insert::Insert,
         reverse::Reverse,
         sweep::{SweepCache, SweepRegion},
@@ -54,10 +55,11 @@ impl SweepFaceOfShell for Shell {
 
         let exterior = face.region().exterior().reverse(core).insert(core);
         let region = Region::new(exterior, [], face.region().color());
-        let faces = region
-            .sweep_region(face.surface(), path, &mut cache, core)
-            .all_faces()
-            .collect::<Vec<_>>();
+        let swept_region =
+            region.sweep_region(face.surface(), path, &mut cache, core);
+        let top_face = swept_region.top_face.derive_from(&face, core);
+
+        let faces = swept_region.side_faces.into_iter().chain([top_face]);
 
         self.remove_face(&face).add_faces(faces, core)
     }
@@ -1,6 +1,6 @@
 use crate::{
     objects::{Cycle, HalfEdge},
-    operations::insert::Insert,
+    operations::{derive::DeriveFrom, insert::Insert},
     storage::Handle,
     Instance,
 };
@@ -64,7 +64,9 @@ impl UpdateCycle for Cycle {
             .half_edges()
             .replace(
                 handle,
-                update(handle, core).map(|object| object.insert(core)),
+                update(handle, core).map(|object| {
+                    object.insert(core).derive_from(handle, core)
+                }),
             )
             .expect("Half-edge not found");
         Cycle::new(edges)
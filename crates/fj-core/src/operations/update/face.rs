@@ -1,6 +1,6 @@
 use crate::{
     objects::{Face, Region},
-    operations::{build::Polygon, insert::Insert},
+    operations::{build::Polygon, derive::DeriveFrom, insert::Insert},
     storage::Handle,
     Instance,
 };
@@ -27,8 +27,10 @@ impl UpdateFace for Face {
     where
         T: Insert<Inserted = Handle<Region>>,
     {
-        let region = update(self.region(), core);
-        Face::new(self.surface().clone(), region.insert(core))
+        let region = update(self.region(), core)
+            .insert(core)
+            .derive_from(self.region(), core);
+        Face::new(self.surface().clone(), region)
     }
 }
 
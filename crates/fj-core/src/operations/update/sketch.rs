@@ -1,6 +1,6 @@
 use crate::{
     objects::{Region, Sketch},
-    operations::insert::Insert,
+    operations::{derive::DeriveFrom, insert::Insert},
     storage::Handle,
     Instance,
 };
@@ -62,7 +62,9 @@ impl UpdateSketch for Sketch {
             .regions()
             .replace(
                 handle,
-                update(handle, core).map(|object| object.insert(core)),
+                update(handle, core).map(|object| {
+                    object.insert(core).derive_from(handle, core)
+                }),
             )
             .expect("Region not found");
         Sketch::new(regions)
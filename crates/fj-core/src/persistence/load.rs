@@ -0,0 +1,323 @@
+use std::collections::BTreeSet;
+
+use fj_interop::Color;
+use fj_math::{Circle, Line, Point, Scalar, Vector};
+
+use crate::{
+    geometry::{CurveBoundary, GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{
+        AnyObject, Curve, Cycle, Face, HalfEdge, Region, Shell, Sketch, Solid,
+        Stored, Surface, Vertex,
+    },
+    operations::insert::Insert,
+    storage::Handle,
+    Instance,
+};
+
+use super::HEADER;
+
+/// Load objects that were written by [`save`] or [`save_all`]
+///
+/// Inserts all objects in the file into `core`, and returns the roots, in the
+/// order in which they were passed to [`save`]. Like any other inserted object,
+/// they are validated using the validation configuration of `core`.
+///
+/// [`save`]: super::save
+/// [`save_all`]: super::save_all
+pub fn load(
+    source: &str,
+    core: &mut Instance,
+) -> Result<Vec<AnyObject<Stored>>, LoadError> {
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    match lines.next() {
+        Some((_, header)) if header == HEADER => {}
+        Some((_, header)) if header.starts_with("fornjot-objects ") => {
+            return Err(LoadError::UnsupportedVersion(header.to_string()));
+        }
+        _ => return Err(LoadError::MissingHeader),
+    }
+
+    let mut objects = Vec::new();
+
+    for (number, line) in lines {
+        let mut line = Tokens {
+            number,
+            tokens: line.split_whitespace(),
+            objects: &objects,
+        };
+
+        let object: AnyObject<Stored> = match line.token()? {
+            "curve" => Curve::new().insert(core).into(),
+            "cycle" => Cycle::new(line.references()?).insert(core).into(),
+            "face" => {
+                let surface = line.reference()?;
+                let region = line.reference()?;
+                Face::new(surface, region).insert(core).into()
+            }
+            "half-edge" => {
+                let path = match line.token()? {
+                    "circle" => {
+                        let [center, a, b] = [(); 3].map(|()| line.vector());
+                        SurfacePath::Circle(line.circle(center?, a?, b?)?)
+                    }
+                    "line" => {
+                        let origin = line.vector()?;
+                        let direction = line.vector()?;
+                        SurfacePath::Line(Line::from_origin_and_direction(
+                            Point { coords: origin },
+                            direction,
+                        ))
+                    }
+                    token => return Err(line.unexpected(token)),
+                };
+                let boundary = line.vector::<2>()?.components.map(|t| [t]);
+                let curve = line.reference()?;
+                let start_vertex = line.reference()?;
+
+                HalfEdge::new(
+                    path,
+                    CurveBoundary::from(boundary.map(Point::from)),
+                    curve,
+                    start_vertex,
+                )
+                .insert(core)
+                .into()
+            }
+            "region" => {
+                let color = match line.token()? {
+                    "-" => None,
+                    token => Some(line.color(token)?),
+                };
+                let exterior = line.reference()?;
+                let interiors = line.references()?;
+                Region::new(exterior, interiors, color).insert(core).into()
+            }
+            "roots" => {
+                let roots = line.objects()?;
+                line.end()?;
+                return Ok(roots);
+            }
+            "shell" => Shell::new(line.references()?).insert(core).into(),
+            "sketch" => Sketch::new(line.references()?).insert(core).into(),
+            "solid" => Solid::new(line.references()?).insert(core).into(),
+            "surface" => {
+                let surfaces = &core.services.objects.surfaces;
+                match line.token()? {
+                    "xy-plane" => surfaces.xy_plane().into(),
+                    "xz-plane" => surfaces.xz_plane().into(),
+                    "yz-plane" => surfaces.yz_plane().into(),
+                    token => {
+                        let u = match token {
+                            "circle" => {
+                                let [center, a, b] =
+                                    [(); 3].map(|()| line.vector());
+                                GlobalPath::Circle(
+                                    line.circle(center?, a?, b?)?,
+                                )
+                            }
+                            "line" => {
+                                let origin = line.vector()?;
+                                let direction = line.vector()?;
+                                GlobalPath::Line(
+                                    Line::from_origin_and_direction(
+                                        Point { coords: origin },
+                                        direction,
+                                    ),
+                                )
+                            }
+                            token => return Err(line.unexpected(token)),
+                        };
+                        let v = line.vector()?;
+
+                        Surface::new(SurfaceGeometry { u, v })
+                            .insert(core)
+                            .into()
+                    }
+                }
+            }
+            "vertex" => Vertex::new().insert(core).into(),
+            token => return Err(line.unexpected(token)),
+        };
+
+        line.end()?;
+        objects.push(object);
+    }
+
+    Err(LoadError::MissingRoots)
+}
+
+/// An error that can occur while loading objects
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    /// The source doesn't start with the header of the format
+    #[error("not a Fornjot object file")]
+    MissingHeader,
+
+    /// The source has been written by an unsupported version of the format
+    #[error("unsupported format version: `{0}`")]
+    UnsupportedVersion(String),
+
+    /// A line doesn't define a valid object
+    #[error("invalid object in line {line}: {message}")]
+    InvalidObject {
+        /// The number of the line, starting at `1`
+        line: usize,
+
+        /// A description of what's wrong with the line
+        message: String,
+    },
+
+    /// The source ends before listing the root objects
+    #[error("missing list of root objects")]
+    MissingRoots,
+}
+
+struct Tokens<'r> {
+    number: usize,
+    tokens: std::str::SplitWhitespace<'r>,
+    objects: &'r [AnyObject<Stored>],
+}
+
+impl<'r> Tokens<'r> {
+    fn token(&mut self) -> Result<&'r str, LoadError> {
+        self.tokens
+            .next()
+            .ok_or_else(|| self.invalid("unexpected end of line"))
+    }
+
+    fn end(&mut self) -> Result<(), LoadError> {
+        match self.tokens.next() {
+            Some(token) => Err(self.unexpected(token)),
+            None => Ok(()),
+        }
+    }
+
+    fn vector<const D: usize>(&mut self) -> Result<Vector<D>, LoadError> {
+        let mut components = [Scalar::ZERO; D];
+
+        for component in &mut components {
+            let token = self.token()?;
+            let number = token
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .ok_or_else(|| {
+                    self.invalid(format!("invalid number `{token}`"))
+                })?;
+            *component = Scalar::from_f64(number);
+        }
+
+        Ok(Vector { components })
+    }
+
+    fn circle<const D: usize>(
+        &self,
+        center: Vector<D>,
+        a: Vector<D>,
+        b: Vector<D>,
+    ) -> Result<Circle<D>, LoadError> {
+        // `Circle::new` panics, if these requirements aren't met. A valid file
+        // meets them, but there's no reason to panic over an invalid one.
+        if a.magnitude() != b.magnitude()
+            || a.magnitude() == Scalar::ZERO
+            || a.dot(&b).into_f64() >= f64::EPSILON
+        {
+            return Err(self.invalid("invalid circle"));
+        }
+
+        Ok(Circle::new(Point { coords: center }, a, b))
+    }
+
+    fn color(&self, token: &str) -> Result<Color, LoadError> {
+        let invalid = || self.invalid(format!("invalid color `{token}`"));
+
+        let hex = token.strip_prefix('#').ok_or_else(invalid)?;
+        if hex.len() != 8 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+
+        let mut color = [0; 4];
+        for (i, channel) in color.iter_mut().enumerate() {
+            *channel = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| invalid())?;
+        }
+
+        Ok(Color(color))
+    }
+
+    fn objects(&mut self) -> Result<Vec<AnyObject<Stored>>, LoadError> {
+        let mut objects = Vec::new();
+
+        for token in self.tokens.by_ref() {
+            let object = token
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| self.objects.get(index))
+                .ok_or_else(|| {
+                    invalid(self.number, format!("invalid object `{token}`"))
+                })?;
+            objects.push(object.clone());
+        }
+
+        Ok(objects)
+    }
+
+    fn reference<T>(&mut self) -> Result<Handle<T>, LoadError>
+    where
+        Handle<T>: TryFrom<AnyObject<Stored>>,
+    {
+        let token = self.token()?;
+        self.object(token)
+    }
+
+    /// Read all remaining tokens as references to distinct objects
+    fn references<T>(&mut self) -> Result<Vec<Handle<T>>, LoadError>
+    where
+        Handle<T>: TryFrom<AnyObject<Stored>>,
+    {
+        let mut ids = BTreeSet::new();
+        let mut handles = Vec::new();
+
+        while let Some(token) = self.tokens.next() {
+            let handle = self.object(token)?;
+            if !ids.insert(handle.id()) {
+                return Err(self.invalid(format!("duplicate object `{token}`")));
+            }
+            handles.push(handle);
+        }
+
+        Ok(handles)
+    }
+
+    fn object<T>(&self, token: &str) -> Result<Handle<T>, LoadError>
+    where
+        Handle<T>: TryFrom<AnyObject<Stored>>,
+    {
+        token
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| self.objects.get(index))
+            .and_then(|object| Handle::<T>::try_from(object.clone()).ok())
+            .ok_or_else(|| self.invalid(format!("invalid reference `{token}`")))
+    }
+
+    fn unexpected(&self, token: &str) -> LoadError {
+        self.invalid(format!("unexpected `{token}`"))
+    }
+
+    fn invalid(&self, message: impl Into<String>) -> LoadError {
+        invalid(self.number, message)
+    }
+}
+
+fn invalid(line: usize, message: impl Into<String>) -> LoadError {
+    LoadError::InvalidObject {
+        line,
+        message: message.into(),
+    }
+}
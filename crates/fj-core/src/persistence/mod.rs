@@ -0,0 +1,165 @@
+//! # Native file format for objects
+//!
+//! Fornjot's own format for writing objects to disk, and loading them back
+//! into an [`Instance`]. Unlike the formats supported by `fj-export`, it is
+//! lossless: Geometry is written with full precision, and objects that are
+//! referenced from multiple places are written once, so this sharing is
+//! preserved when loading them again.
+//!
+//! Use [`save`] to write objects, together with all objects they reference, or
+//! [`save_all`] to write the contents of all stores. Use [`load`] to read them
+//! back.
+//!
+//! ## Format
+//!
+//! The format is plain text, to make it easy to inspect, and to attach to bug
+//! reports. The first line identifies the format and its version:
+//!
+//! ``` text
+//! fornjot-objects 1
+//! ```
+//!
+//! Every following line defines one object. Objects are numbered in the order
+//! in which they appear, starting at `0`, and refer to other objects by their
+//! number. Objects can only refer to objects that appear before them.
+//!
+//! ``` text
+//! curve
+//! vertex
+//! surface xy-plane | xz-plane | yz-plane
+//! surface line <origin> <direction> <v>
+//! surface circle <center> <a> <b> <v>
+//! half-edge line <origin> <direction> <boundary> <curve> <start vertex>
+//! half-edge circle <center> <a> <b> <boundary> <curve> <start vertex>
+//! cycle <half-edges>...
+//! region <color> <exterior> <interiors>...
+//! face <surface> <region>
+//! shell <faces>...
+//! sketch <regions>...
+//! solid <shells>...
+//! ```
+//!
+//! Points and vectors are written as their components. Surfaces are defined in
+//! 3D, and half-edges in the 2D coordinates of their surface. The boundary of a
+//! half-edge consists of its start and end on the curve. Colors are written as
+//! `#rrggbbaa`, or as `-`, if a region has no color. The pre-defined planes
+//! of [`Surfaces`] are written by name, so they are replaced with the
+//! pre-defined planes of the instance they are loaded into.
+//!
+//! The last line lists the objects that were passed to [`save`]:
+//!
+//! ``` text
+//! roots <objects>...
+//! ```
+//!
+//! Only objects are written. Anything that services record about them, like
+//! their [`Provenance`], is not.
+//!
+//! [`Instance`]: crate::Instance
+//! [`Surfaces`]: crate::objects::Surfaces
+//! [`Provenance`]: crate::services::Provenance
+
+mod load;
+mod save;
+
+pub use self::{
+    load::{load, LoadError},
+    save::{save, save_all},
+};
+
+/// The first line of a file, identifying the format and its version
+const HEADER: &str = "fornjot-objects 1";
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::{
+        objects::{Region, Sketch, Solid},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            insert::Insert,
+            sweep::SweepSketch,
+            update::UpdateSketch,
+        },
+        storage::Handle,
+        Instance,
+    };
+
+    use super::{load, save, LoadError};
+
+    #[test]
+    fn save_and_load_solid() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let surface = core.services.objects.surfaces.xy_plane();
+        let solid = Sketch::empty()
+            .add_regions(
+                [
+                    Region::circle(Point::from([1., 2.]), 1., &mut core),
+                    Region::polygon(
+                        [[3., 0.], [5., 0.], [5., 1.], [3., 1.]],
+                        &mut core,
+                    ),
+                ],
+                &mut core,
+            )
+            .sweep_sketch(surface, [0., 0., 2.], &mut core)
+            .insert(&mut core);
+
+        let mut saved = Vec::new();
+        save([solid.clone()], &core, &mut saved)?;
+
+        let mut loaded_core = Instance::new();
+        let roots = load(std::str::from_utf8(&saved)?, &mut loaded_core)?;
+        let [root] = roots.as_slice() else {
+            panic!("Expected exactly one root");
+        };
+        let loaded = Handle::<Solid>::try_from(root.clone())
+            .expect("Expected root to be a solid");
+
+        // Saving the loaded solid again only results in the same output, if
+        // all geometry and all shared objects have been preserved.
+        let mut saved_again = Vec::new();
+        save([loaded.clone()], &loaded_core, &mut saved_again)?;
+        assert_eq!(
+            std::str::from_utf8(&saved)?,
+            std::str::from_utf8(&saved_again)?
+        );
+
+        // The pre-defined planes are replaced with those of the new instance.
+        let bottom_surface = loaded.shells().first().faces().first().surface();
+        assert_eq!(
+            bottom_surface.id(),
+            loaded_core.services.objects.surfaces.xy_plane().id(),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_invalid_reference() {
+        let mut core = Instance::new();
+
+        let source = "fornjot-objects 1\nvertex\ncycle 0\nroots 1\n";
+        assert!(matches!(
+            load(source, &mut core),
+            Err(LoadError::InvalidObject { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn load_invalid_number() {
+        for number in ["x", "NaN", "inf", "-inf"] {
+            let mut core = Instance::new();
+
+            let source = format!(
+                "fornjot-objects 1\nsurface line 0 0 0 {number} 0 0 0 0 1\n"
+            );
+            assert!(matches!(
+                load(&source, &mut core),
+                Err(LoadError::InvalidObject { line: 2, .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use fj_math::Scalar;
+
+use crate::{
+    geometry::{GlobalPath, SurfacePath},
+    objects::{AnyObject, Stored},
+    storage::{Handle, ObjectId},
+    Instance,
+};
+
+use super::HEADER;
+
+/// Write objects, and all objects they reference
+///
+/// The provided objects are the roots, which [`load`] returns in the same
+/// order. See the [module documentation] for details on the format.
+///
+/// [`load`]: super::load
+/// [module documentation]: super
+pub fn save(
+    roots: impl IntoIterator<Item = impl Into<AnyObject<Stored>>>,
+    core: &Instance,
+    writer: impl io::Write,
+) -> io::Result<()> {
+    let mut writer = io::BufWriter::new(writer);
+    writeln!(writer, "{HEADER}")?;
+
+    let mut saver = Saver {
+        core,
+        writer: &mut writer,
+        indices: BTreeMap::new(),
+    };
+
+    let mut indices = Vec::new();
+    for root in roots {
+        indices.push(saver.object(root.into())?);
+    }
+
+    write!(writer, "roots")?;
+    for index in indices {
+        write!(writer, " {index}")?;
+    }
+    writeln!(writer)?;
+
+    writer.flush()
+}
+
+/// Write all objects in all stores
+///
+/// All objects are roots, ordered by store, and then by when they were
+/// inserted.
+pub fn save_all(core: &Instance, writer: impl io::Write) -> io::Result<()> {
+    let objects = &core.services.objects;
+
+    let roots = objects
+        .curves
+        .iter()
+        .map(AnyObject::from)
+        .chain(objects.cycles.iter().map(AnyObject::from))
+        .chain(objects.faces.iter().map(AnyObject::from))
+        .chain(objects.half_edges.iter().map(AnyObject::from))
+        .chain(objects.regions.iter().map(AnyObject::from))
+        .chain(objects.shells.iter().map(AnyObject::from))
+        .chain(objects.sketches.iter().map(AnyObject::from))
+        .chain(objects.solids.iter().map(AnyObject::from))
+        .chain(objects.surfaces.iter().map(AnyObject::from))
+        .chain(objects.vertices.iter().map(AnyObject::from))
+        .collect::<Vec<_>>();
+
+    save(roots, core, writer)
+}
+
+struct Saver<'r, W> {
+    core: &'r Instance,
+    writer: &'r mut W,
+
+    /// The numbers of the objects that have been written already
+    indices: BTreeMap<ObjectId, usize>,
+}
+
+impl<W: io::Write> Saver<'_, W> {
+    /// Write an object, after the objects it references, unless written already
+    ///
+    /// Returns the number of the object.
+    fn object(&mut self, object: AnyObject<Stored>) -> io::Result<usize> {
+        if let Some(index) = self.indices.get(&object.id()) {
+            return Ok(*index);
+        }
+
+        match &object {
+            AnyObject::Curve(_) => {
+                write!(self.writer, "curve")?;
+            }
+            AnyObject::Cycle(cycle) => {
+                let half_edges = self.objects(cycle.half_edges())?;
+                write!(self.writer, "cycle{half_edges}")?;
+            }
+            AnyObject::Face(face) => {
+                let surface = self.object(face.surface().clone().into())?;
+                let region = self.object(face.region().clone().into())?;
+                write!(self.writer, "face {surface} {region}")?;
+            }
+            AnyObject::HalfEdge(half_edge) => {
+                let curve = self.object(half_edge.curve().clone().into())?;
+                let vertex =
+                    self.object(half_edge.start_vertex().clone().into())?;
+
+                write!(self.writer, "half-edge")?;
+                match half_edge.path() {
+                    SurfacePath::Circle(circle) => {
+                        write!(self.writer, " circle")?;
+                        self.numbers(circle.center().coords.components)?;
+                        self.numbers(circle.a().components)?;
+                        self.numbers(circle.b().components)?;
+                    }
+                    SurfacePath::Line(line) => {
+                        write!(self.writer, " line")?;
+                        self.numbers(line.origin().coords.components)?;
+                        self.numbers(line.direction().components)?;
+                    }
+                }
+                self.numbers(half_edge.boundary().inner.map(|point| point.t))?;
+                write!(self.writer, " {curve} {vertex}")?;
+            }
+            AnyObject::Region(region) => {
+                let exterior = self.object(region.exterior().clone().into())?;
+                let interiors = self.objects(region.interiors())?;
+
+                let color = match region.color() {
+                    Some(color) => {
+                        let [r, g, b, a] = color.0;
+                        format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+                    }
+                    None => "-".to_string(),
+                };
+
+                write!(self.writer, "region {color} {exterior}{interiors}")?;
+            }
+            AnyObject::Shell(shell) => {
+                let faces = self.objects(shell.faces())?;
+                write!(self.writer, "shell{faces}")?;
+            }
+            AnyObject::Sketch(sketch) => {
+                let regions = self.objects(sketch.regions())?;
+                write!(self.writer, "sketch{regions}")?;
+            }
+            AnyObject::Solid(solid) => {
+                let shells = self.objects(solid.shells())?;
+                write!(self.writer, "solid{shells}")?;
+            }
+            AnyObject::Surface(surface) => {
+                write!(self.writer, "surface")?;
+
+                let surfaces = &self.core.services.objects.surfaces;
+                let planes = [
+                    (surfaces.xy_plane(), "xy-plane"),
+                    (surfaces.xz_plane(), "xz-plane"),
+                    (surfaces.yz_plane(), "yz-plane"),
+                ];
+                let plane = planes
+                    .into_iter()
+                    .find(|(plane, _)| plane.id() == surface.id());
+
+                if let Some((_, name)) = plane {
+                    write!(self.writer, " {name}")?;
+                } else {
+                    let geometry = surface.geometry();
+                    match geometry.u {
+                        GlobalPath::Circle(circle) => {
+                            write!(self.writer, " circle")?;
+                            self.numbers(circle.center().coords.components)?;
+                            self.numbers(circle.a().components)?;
+                            self.numbers(circle.b().components)?;
+                        }
+                        GlobalPath::Line(line) => {
+                            write!(self.writer, " line")?;
+                            self.numbers(line.origin().coords.components)?;
+                            self.numbers(line.direction().components)?;
+                        }
+                    }
+                    self.numbers(geometry.v.components)?;
+                }
+            }
+            AnyObject::Vertex(_) => {
+                write!(self.writer, "vertex")?;
+            }
+        }
+        writeln!(self.writer)?;
+
+        let index = self.indices.len();
+        self.indices.insert(object.id(), index);
+
+        Ok(index)
+    }
+
+    /// Write multiple objects
+    ///
+    /// Returns their numbers, each preceded by a space.
+    fn objects<'a, T: 'a>(
+        &mut self,
+        objects: impl IntoIterator<Item = &'a Handle<T>>,
+    ) -> io::Result<String>
+    where
+        AnyObject<Stored>: From<Handle<T>>,
+    {
+        let mut indices = String::new();
+
+        for object in objects {
+            let index = self.object(object.clone().into())?;
+            indices.push_str(&format!(" {index}"));
+        }
+
+        Ok(indices)
+    }
+
+    fn numbers<const D: usize>(
+        &mut self,
+        numbers: [Scalar; D],
+    ) -> io::Result<()> {
+        for number in numbers {
+            // The `Display` implementation of `f64` writes the shortest
+            // representation that parses back into the same value.
+            write!(self.writer, " {}", number.into_f64())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    objects::{Face, Shell},
+    storage::Handle,
+};
+
+/// Determine the faces that are adjacent to a face
+pub trait AdjacentFaces {
+    /// Determine the faces that share an edge with the provided face
+    ///
+    /// Each adjacent face is returned once, in the order the faces are stored
+    /// in the object. The provided face is not included, unless it is adjacent
+    /// to itself.
+    ///
+    /// Returns an empty list, if the provided face is not part of the object
+    /// this method is called on.
+    fn adjacent_faces(&self, face: &Handle<Face>) -> Vec<Handle<Face>>;
+}
+
+impl AdjacentFaces for Shell {
+    fn adjacent_faces(&self, face: &Handle<Face>) -> Vec<Handle<Face>> {
+        if !self.faces().contains(face) {
+            return Vec::new();
+        }
+
+        // A sibling lies on the same curve, with the boundary reversed. Collect
+        // what the siblings of this face's half-edges look like, so the shell
+        // only needs to be traversed once.
+        let siblings = face
+            .region()
+            .all_cycles()
+            .flat_map(|cycle| cycle.half_edges())
+            .map(|half_edge| {
+                (half_edge.curve().id(), half_edge.boundary().reverse())
+            })
+            .collect::<BTreeSet<_>>();
+
+        self.faces()
+            .iter()
+            .filter(|other| {
+                other
+                    .region()
+                    .all_cycles()
+                    .flat_map(|cycle| cycle.half_edges())
+                    .any(|half_edge| {
+                        siblings.contains(&(
+                            half_edge.curve().id(),
+                            half_edge.boundary(),
+                        ))
+                    })
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{queries::tests::cuboid, Instance};
+
+    use super::AdjacentFaces;
+
+    #[test]
+    fn adjacent_faces_of_cuboid() {
+        let mut core = Instance::new();
+
+        let solid = cuboid(&mut core);
+        let shell = solid.shells().only();
+
+        for face in shell.faces() {
+            let adjacent = shell.adjacent_faces(face);
+
+            assert_eq!(adjacent.len(), 4);
+            assert!(!adjacent.contains(face));
+        }
+    }
+
+    #[test]
+    fn adjacent_faces_of_foreign_face() {
+        let mut core = Instance::new();
+
+        let a = cuboid(&mut core);
+        let b = cuboid(&mut core);
+
+        let face = b.shells().only().faces().first();
+        assert!(a.shells().only().adjacent_faces(face).is_empty());
+    }
+}
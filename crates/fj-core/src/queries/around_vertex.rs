@@ -0,0 +1,91 @@
+use crate::{
+    objects::{Face, HalfEdge, Shell, Vertex},
+    storage::Handle,
+};
+
+/// Determine the objects that surround a vertex
+pub trait AroundVertex {
+    /// Determine the half-edges that start at the provided vertex
+    ///
+    /// Every half-edge that ends at the vertex has a sibling that starts there,
+    /// so these half-edges cover all edges that meet at the vertex.
+    fn half_edges_around_vertex(
+        &self,
+        vertex: &Handle<Vertex>,
+    ) -> Vec<Handle<HalfEdge>>;
+
+    /// Determine the faces that touch the provided vertex
+    ///
+    /// Each face is returned once, in the order the faces are stored in the
+    /// object.
+    fn faces_around_vertex(&self, vertex: &Handle<Vertex>)
+        -> Vec<Handle<Face>>;
+}
+
+impl AroundVertex for Shell {
+    fn half_edges_around_vertex(
+        &self,
+        vertex: &Handle<Vertex>,
+    ) -> Vec<Handle<HalfEdge>> {
+        self.faces()
+            .iter()
+            .flat_map(|face| face.region().all_cycles())
+            .flat_map(|cycle| cycle.half_edges())
+            .filter(|half_edge| half_edge.start_vertex().id() == vertex.id())
+            .cloned()
+            .collect()
+    }
+
+    fn faces_around_vertex(
+        &self,
+        vertex: &Handle<Vertex>,
+    ) -> Vec<Handle<Face>> {
+        self.faces()
+            .iter()
+            .filter(|face| {
+                face.region()
+                    .all_cycles()
+                    .flat_map(|cycle| cycle.half_edges())
+                    .any(|half_edge| {
+                        half_edge.start_vertex().id() == vertex.id()
+                    })
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        queries::{tests::cuboid, Select},
+        Instance,
+    };
+
+    use super::AroundVertex;
+
+    #[test]
+    fn around_corner_of_cuboid() {
+        let mut core = Instance::new();
+
+        let solid = cuboid(&mut core);
+        let shell = solid.shells().only();
+
+        let bottom = shell.select_faces().with_normal([0., 0., -1.]).only();
+        let half_edge = bottom.region().exterior().half_edges().first();
+        let vertex = half_edge.start_vertex();
+
+        let half_edges = shell.half_edges_around_vertex(vertex);
+        assert_eq!(half_edges.len(), 3);
+        assert!(half_edges.contains(half_edge));
+        assert!(half_edges
+            .iter()
+            .all(|half_edge| half_edge.start_vertex().id() == vertex.id()));
+
+        let faces = shell.faces_around_vertex(vertex);
+        assert_eq!(faces.len(), 3);
+        assert!(faces.contains(&bottom));
+        assert!(!faces
+            .contains(&shell.select_faces().with_normal([0., 0., 1.]).only()));
+    }
+}
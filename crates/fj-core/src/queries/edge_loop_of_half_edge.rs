@@ -0,0 +1,101 @@
+use crate::{
+    objects::{Cycle, Face, HalfEdge, Region, Shell},
+    storage::Handle,
+};
+
+/// Determine the loop of edges that a half-edge is part of
+pub trait EdgeLoopOfHalfEdge {
+    /// Determine the loop of edges that the provided half-edge is part of
+    ///
+    /// Returns all half-edges of the cycle that contains the provided
+    /// half-edge, in order, starting with the provided half-edge.
+    ///
+    /// Returns `None`, if the provided half-edge is not part of the object this
+    /// method is called on.
+    fn edge_loop_of_half_edge(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+    ) -> Option<Vec<Handle<HalfEdge>>>;
+}
+
+impl EdgeLoopOfHalfEdge for Cycle {
+    fn edge_loop_of_half_edge(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+    ) -> Option<Vec<Handle<HalfEdge>>> {
+        let index = self.half_edges().index_of(half_edge)?;
+
+        let edge_loop = (index..index + self.half_edges().len())
+            .map(|i| self.half_edges().nth_circular(i).clone())
+            .collect();
+
+        Some(edge_loop)
+    }
+}
+
+impl EdgeLoopOfHalfEdge for Region {
+    fn edge_loop_of_half_edge(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+    ) -> Option<Vec<Handle<HalfEdge>>> {
+        self.all_cycles()
+            .find_map(|cycle| cycle.edge_loop_of_half_edge(half_edge))
+    }
+}
+
+impl EdgeLoopOfHalfEdge for Face {
+    fn edge_loop_of_half_edge(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+    ) -> Option<Vec<Handle<HalfEdge>>> {
+        self.region().edge_loop_of_half_edge(half_edge)
+    }
+}
+
+impl EdgeLoopOfHalfEdge for Shell {
+    fn edge_loop_of_half_edge(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+    ) -> Option<Vec<Handle<HalfEdge>>> {
+        self.faces()
+            .iter()
+            .find_map(|face| face.edge_loop_of_half_edge(half_edge))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        queries::{tests::cuboid, Select},
+        Instance,
+    };
+
+    use super::EdgeLoopOfHalfEdge;
+
+    #[test]
+    fn edge_loop_of_cuboid_face() {
+        let mut core = Instance::new();
+
+        let solid = cuboid(&mut core);
+        let shell = solid.shells().only();
+
+        let top = shell.select_faces().with_normal([0., 0., 1.]).only();
+        let cycle = top.region().exterior();
+
+        for (i, half_edge) in cycle.half_edges().iter().enumerate() {
+            let edge_loop = shell.edge_loop_of_half_edge(half_edge).unwrap();
+
+            assert_eq!(edge_loop.len(), 4);
+            assert_eq!(edge_loop.first(), Some(half_edge));
+            for (j, h) in edge_loop.iter().enumerate() {
+                assert_eq!(h, cycle.half_edges().nth_circular(i + j));
+            }
+
+            assert_eq!(top.edge_loop_of_half_edge(half_edge), Some(edge_loop));
+        }
+
+        let bottom = shell.select_faces().with_normal([0., 0., -1.]).only();
+        let half_edge = bottom.region().exterior().half_edges().first();
+        assert_eq!(top.edge_loop_of_half_edge(half_edge), None);
+    }
+}
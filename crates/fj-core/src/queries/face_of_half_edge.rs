@@ -0,0 +1,83 @@
+use crate::{
+    objects::{Face, HalfEdge, Shell, Solid},
+    storage::Handle,
+};
+
+/// Determine the face that a half-edge is part of
+pub trait FaceOfHalfEdge {
+    /// Determine the face that the provided half-edge is part of
+    ///
+    /// Returns `None`, if the provided half-edge is not part of the object this
+    /// method is called on.
+    fn face_of_half_edge(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+    ) -> Option<Handle<Face>>;
+}
+
+impl FaceOfHalfEdge for Shell {
+    fn face_of_half_edge(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+    ) -> Option<Handle<Face>> {
+        self.faces()
+            .iter()
+            .find(|face| {
+                face.region()
+                    .all_cycles()
+                    .any(|cycle| cycle.half_edges().contains(half_edge))
+            })
+            .cloned()
+    }
+}
+
+impl FaceOfHalfEdge for Solid {
+    fn face_of_half_edge(
+        &self,
+        half_edge: &Handle<HalfEdge>,
+    ) -> Option<Handle<Face>> {
+        self.shells()
+            .iter()
+            .find_map(|shell| shell.face_of_half_edge(half_edge))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{queries::tests::cuboid, Instance};
+
+    use super::FaceOfHalfEdge;
+
+    #[test]
+    fn face_of_half_edge_in_cuboid() {
+        let mut core = Instance::new();
+
+        let solid = cuboid(&mut core);
+        let shell = solid.shells().only();
+
+        for face in shell.faces() {
+            for half_edge in face.region().exterior().half_edges() {
+                assert_eq!(
+                    shell.face_of_half_edge(half_edge).as_ref(),
+                    Some(face)
+                );
+                assert_eq!(
+                    solid.face_of_half_edge(half_edge).as_ref(),
+                    Some(face)
+                );
+            }
+        }
+
+        let other = cuboid(&mut core);
+        let half_edge = other
+            .shells()
+            .only()
+            .faces()
+            .first()
+            .region()
+            .exterior()
+            .half_edges()
+            .first();
+        assert_eq!(solid.face_of_half_edge(half_edge), None);
+    }
+}
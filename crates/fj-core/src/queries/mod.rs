@@ -9,12 +9,52 @@
 //! This module provides traits express such non-trivial queries, and implements
 //! them for various objects that have the information to answer the query.
 
+mod adjacent_faces;
 mod all_half_edges_with_surface;
+mod around_vertex;
 mod bounding_vertices_of_half_edge;
+mod edge_loop_of_half_edge;
+mod face_of_half_edge;
+mod select;
+mod shell_of_face;
 mod sibling_of_half_edge;
 
 pub use self::{
+    adjacent_faces::AdjacentFaces,
     all_half_edges_with_surface::AllHalfEdgesWithSurface,
+    around_vertex::AroundVertex,
     bounding_vertices_of_half_edge::BoundingVerticesOfHalfEdge,
+    edge_loop_of_half_edge::EdgeLoopOfHalfEdge,
+    face_of_half_edge::FaceOfHalfEdge,
+    select::{FaceSelection, HalfEdgeSelection, Select},
+    shell_of_face::ShellOfFace,
     sibling_of_half_edge::SiblingOfHalfEdge,
 };
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::{Region, Sketch, Solid},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            sweep::SweepSketch,
+            update::UpdateSketch,
+        },
+        Instance,
+    };
+
+    /// Build a 1x2x3 cuboid, with one corner at the origin
+    pub fn cuboid(core: &mut Instance) -> Solid {
+        let bottom_surface = core.services.objects.surfaces.xy_plane();
+
+        Sketch::empty()
+            .add_regions(
+                [Region::polygon(
+                    [[0., 0.], [1., 0.], [1., 2.], [0., 2.]],
+                    core,
+                )],
+                core,
+            )
+            .sweep_sketch(bottom_surface, [0., 0., 3.], core)
+    }
+}
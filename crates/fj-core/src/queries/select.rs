@@ -0,0 +1,559 @@
+use fj_math::{Scalar, Vector};
+
+use crate::{
+    algorithms::mass_properties::MassProperties,
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{AnyObject, Face, HalfEdge, Handedness, Shell, Solid, Stored},
+    services::Provenance,
+    storage::Handle,
+};
+
+/// Select faces and half-edges of an object by their geometry
+///
+/// Selecting faces and edges by what they look like, instead of by their
+/// position within the object, keeps models working when their structure
+/// changes.
+pub trait Select {
+    /// Start a selection of the object's faces
+    fn select_faces(&self) -> FaceSelection;
+
+    /// Start a selection of the object's half-edges
+    fn select_half_edges(&self) -> HalfEdgeSelection {
+        let half_edges = self
+            .select_faces()
+            .faces
+            .into_iter()
+            .flat_map(|face| {
+                face.region()
+                    .all_cycles()
+                    .flat_map(|cycle| cycle.half_edges().iter().cloned())
+                    .map(|half_edge| (half_edge, face.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        HalfEdgeSelection { half_edges }
+    }
+}
+
+impl Select for Shell {
+    fn select_faces(&self) -> FaceSelection {
+        FaceSelection {
+            faces: self.faces().iter().cloned().collect(),
+        }
+    }
+}
+
+impl Select for Solid {
+    fn select_faces(&self) -> FaceSelection {
+        FaceSelection {
+            faces: self
+                .shells()
+                .iter()
+                .flat_map(|shell| shell.faces().iter().cloned())
+                .collect(),
+        }
+    }
+}
+
+/// A selection of faces
+///
+/// Created by [`Select::select_faces`]. Each method narrows down the selection.
+#[derive(Clone, Debug)]
+pub struct FaceSelection {
+    faces: Vec<Handle<Face>>,
+}
+
+impl FaceSelection {
+    /// Select only faces that match the provided predicate
+    #[must_use]
+    pub fn filter(mut self, mut predicate: impl FnMut(&Face) -> bool) -> Self {
+        self.faces.retain(|face| predicate(face));
+        self
+    }
+
+    /// Select only faces on planar surfaces
+    #[must_use]
+    pub fn planar(self) -> Self {
+        self.filter(|face| plane_normal(face).is_some())
+    }
+
+    /// Select only faces on curved surfaces
+    #[must_use]
+    pub fn curved(self) -> Self {
+        self.filter(|face| plane_normal(face).is_none())
+    }
+
+    /// Select only planar faces whose normal points in the provided direction
+    ///
+    /// The normal of a face points towards its front side, which is the
+    /// outside of a valid shell.
+    #[must_use]
+    pub fn with_normal(self, direction: impl Into<Vector<3>>) -> Self {
+        let direction = direction.into().normalize();
+
+        self.filter(|face| {
+            plane_normal(face)
+                .is_some_and(|normal| is_same_direction(normal, direction))
+        })
+    }
+
+    /// Select only the faces that are furthest along the provided direction
+    ///
+    /// The position of a face is measured at its centroid. All faces that are
+    /// equally far along are selected.
+    #[must_use]
+    pub fn furthest_along(mut self, direction: impl Into<Vector<3>>) -> Self {
+        let direction = direction.into();
+
+        let distances = self
+            .faces
+            .iter()
+            .map(|face| face.mass_properties().centroid.coords.dot(&direction))
+            .collect::<Vec<_>>();
+
+        let Some(max) = distances.iter().copied().max() else {
+            return self;
+        };
+
+        let mut distances = distances.into_iter();
+        self.faces.retain(|_| {
+            let distance = distances.next().expect("One distance per face");
+            max - distance < Scalar::from_f64(EPSILON)
+        });
+
+        self
+    }
+
+    /// Select only faces that were derived from the provided object
+    ///
+    /// This can be used to find the faces that an operation created from a
+    /// given object, like the side face that sweeping a half-edge produced. See
+    /// [`Provenance`] for more information.
+    #[must_use]
+    pub fn derived_from(
+        mut self,
+        original: impl Into<AnyObject<Stored>>,
+        provenance: &Provenance,
+    ) -> Self {
+        let derived = provenance.derived_from::<Face>(original);
+        self.faces.retain(|face| derived.contains(face));
+        self
+    }
+
+    /// Access all selected faces
+    pub fn all(self) -> Vec<Handle<Face>> {
+        self.faces
+    }
+
+    /// Access the first of the selected faces
+    ///
+    /// Returns `None`, if no faces are selected.
+    pub fn first(self) -> Option<Handle<Face>> {
+        self.faces.into_iter().next()
+    }
+
+    /// Access the only selected face
+    ///
+    /// # Panics
+    ///
+    /// Panics, if not exactly one face is selected.
+    pub fn only(self) -> Handle<Face> {
+        let [face] = <[_; 1]>::try_from(self.faces).unwrap_or_else(|faces| {
+            panic!("Expected exactly one face, got {}", faces.len())
+        });
+
+        face
+    }
+}
+
+/// A selection of half-edges
+///
+/// Created by [`Select::select_half_edges`]. Each method narrows down the
+/// selection.
+///
+/// Every edge of a valid shell consists of two half-edges, one in each of the
+/// faces that meet at the edge. Unless narrowed down to specific faces, a
+/// selection includes both.
+#[derive(Clone, Debug)]
+pub struct HalfEdgeSelection {
+    half_edges: Vec<(Handle<HalfEdge>, Handle<Face>)>,
+}
+
+impl HalfEdgeSelection {
+    /// Select only half-edges that match the provided predicate
+    ///
+    /// The predicate also receives the face that the half-edge is part of.
+    #[must_use]
+    pub fn filter(
+        mut self,
+        mut predicate: impl FnMut(&HalfEdge, &Face) -> bool,
+    ) -> Self {
+        self.half_edges
+            .retain(|(half_edge, face)| predicate(half_edge, face));
+        self
+    }
+
+    /// Select only the half-edges of the provided face
+    #[must_use]
+    pub fn in_face(mut self, face: &Handle<Face>) -> Self {
+        self.half_edges.retain(|(_, f)| f == face);
+        self
+    }
+
+    /// Select only half-edges that are straight lines
+    #[must_use]
+    pub fn straight(self) -> Self {
+        self.filter(|half_edge, face| {
+            matches!(shape(half_edge, face), EdgeShape::Line(_))
+        })
+    }
+
+    /// Select only half-edges that are circular arcs
+    #[must_use]
+    pub fn circular(self) -> Self {
+        self.filter(|half_edge, face| {
+            matches!(shape(half_edge, face), EdgeShape::Circle)
+        })
+    }
+
+    /// Select only straight half-edges that are parallel to a vector
+    ///
+    /// Half-edges pointing in the opposite direction are also selected.
+    #[must_use]
+    pub fn parallel_to(self, vector: impl Into<Vector<3>>) -> Self {
+        let vector = vector.into().normalize();
+
+        self.filter(|half_edge, face| match shape(half_edge, face) {
+            EdgeShape::Line(direction) => {
+                is_same_direction(direction, vector)
+                    || is_same_direction(direction, -vector)
+            }
+            EdgeShape::Circle | EdgeShape::Other => false,
+        })
+    }
+
+    /// Select only half-edges that were derived from the provided object
+    ///
+    /// See [`Provenance`] for more information.
+    #[must_use]
+    pub fn derived_from(
+        mut self,
+        original: impl Into<AnyObject<Stored>>,
+        provenance: &Provenance,
+    ) -> Self {
+        let derived = provenance.derived_from::<HalfEdge>(original);
+        self.half_edges
+            .retain(|(half_edge, _)| derived.contains(half_edge));
+        self
+    }
+
+    /// Access all selected half-edges
+    pub fn all(self) -> Vec<Handle<HalfEdge>> {
+        self.half_edges
+            .into_iter()
+            .map(|(half_edge, _)| half_edge)
+            .collect()
+    }
+
+    /// Access the first of the selected half-edges
+    ///
+    /// Returns `None`, if no half-edges are selected.
+    pub fn first(self) -> Option<Handle<HalfEdge>> {
+        self.all().into_iter().next()
+    }
+
+    /// Access the only selected half-edge
+    ///
+    /// # Panics
+    ///
+    /// Panics, if not exactly one half-edge is selected.
+    pub fn only(self) -> Handle<HalfEdge> {
+        let [half_edge] =
+            <[_; 1]>::try_from(self.all()).unwrap_or_else(|half_edges| {
+                panic!(
+                    "Expected exactly one half-edge, got {}",
+                    half_edges.len()
+                )
+            });
+
+        half_edge
+    }
+}
+
+/// The tolerance used when comparing directions and distances
+///
+/// Directions are compared using unit vectors, so this is a tolerance for
+/// their components.
+const EPSILON: f64 = 1e-9;
+
+fn is_same_direction(a: Vector<3>, b: Vector<3>) -> bool {
+    (a.normalize() - b.normalize()).magnitude() < Scalar::from_f64(EPSILON)
+}
+
+/// Compute the normal of a face, if it is planar
+///
+/// The normal points towards the front side of the face.
+fn plane_normal(face: &Face) -> Option<Vector<3>> {
+    let surface = face.surface().geometry();
+
+    let GlobalPath::Line(line) = surface.u else {
+        return None;
+    };
+
+    let normal = line.direction().cross(&surface.v).normalize();
+    let normal = match face.coord_handedness() {
+        Handedness::RightHanded => normal,
+        Handedness::LeftHanded => -normal,
+    };
+
+    Some(normal)
+}
+
+/// The shape of a half-edge in 3D
+enum EdgeShape {
+    Line(Vector<3>),
+    Circle,
+    Other,
+}
+
+fn shape(half_edge: &HalfEdge, face: &Face) -> EdgeShape {
+    let surface: SurfaceGeometry = face.surface().geometry();
+
+    match (surface.u, half_edge.path()) {
+        (GlobalPath::Line(_), SurfacePath::Line(line)) => EdgeShape::Line(
+            surface.vector_from_surface_coords(line.direction()),
+        ),
+        (GlobalPath::Line(_), SurfacePath::Circle(_)) => EdgeShape::Circle,
+        (GlobalPath::Circle(_), SurfacePath::Line(line)) => {
+            // On a curved surface, a line in surface coordinates is only
+            // straight or circular in 3D, if it runs along one of the axes.
+            let direction = line.direction();
+
+            if direction.u == Scalar::ZERO {
+                EdgeShape::Line(surface.v)
+            } else if direction.v == Scalar::ZERO {
+                EdgeShape::Circle
+            } else {
+                EdgeShape::Other
+            }
+        }
+        (GlobalPath::Circle(_), SurfacePath::Circle(_)) => EdgeShape::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_math::Point;
+
+    use crate::{
+        objects::{Region, Sketch, Solid},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            sweep::SweepSketch,
+            update::UpdateSketch,
+        },
+        queries::tests::cuboid,
+        Instance,
+    };
+
+    use super::Select;
+
+    #[test]
+    fn select_faces_of_cuboid() {
+        let mut core = Instance::new();
+
+        let solid = cuboid(&mut core);
+
+        assert_eq!(solid.select_faces().all().len(), 6);
+        assert_eq!(solid.select_faces().planar().all().len(), 6);
+        assert!(solid.select_faces().curved().all().is_empty());
+
+        let top = solid.select_faces().with_normal([0., 0., 1.]).only();
+        let bottom = solid.select_faces().with_normal([0., 0., -1.]).only();
+        assert_ne!(top, bottom);
+
+        // The length of the direction doesn't matter.
+        assert_eq!(solid.select_faces().with_normal([0., 0., 3.]).only(), top);
+        assert!(solid
+            .select_faces()
+            .with_normal([1., 1., 0.])
+            .all()
+            .is_empty());
+
+        assert_eq!(
+            solid.select_faces().furthest_along([0., 0., 1.]).only(),
+            top
+        );
+        assert_eq!(
+            solid.select_faces().furthest_along([0., 0., -1.]).only(),
+            bottom
+        );
+        assert_eq!(
+            solid.select_faces().furthest_along([1., 1., 1.]).only(),
+            top
+        );
+        assert_eq!(
+            solid.select_faces().furthest_along([1., 0., 0.]).only(),
+            solid.select_faces().with_normal([1., 0., 0.]).only(),
+        );
+
+        assert_eq!(solid.select_faces().first(), Some(bottom));
+        assert_eq!(
+            solid.select_faces().with_normal([1., 1., 0.]).first(),
+            None
+        );
+    }
+
+    #[test]
+    fn select_faces_of_cylinder() {
+        let mut core = Instance::new();
+
+        let solid = cylinder(&mut core);
+
+        assert_eq!(solid.select_faces().planar().all().len(), 2);
+        let side = solid.select_faces().curved().only();
+
+        // A curved face has no normal.
+        for direction in [[1., 0., 0.], [0., 1., 0.]] {
+            assert!(solid
+                .select_faces()
+                .with_normal(direction)
+                .all()
+                .is_empty());
+        }
+
+        // All faces are centered on the axis, so none of them is further out
+        // than the others.
+        let faces = solid.select_faces().furthest_along([1., 0., 0.]).all();
+        assert_eq!(faces.len(), 3);
+        assert!(faces.contains(&side));
+    }
+
+    #[test]
+    fn select_half_edges_of_cuboid() {
+        let mut core = Instance::new();
+
+        let solid = cuboid(&mut core);
+
+        // Every edge consists of two half-edges.
+        assert_eq!(solid.select_half_edges().all().len(), 24);
+        assert_eq!(solid.select_half_edges().straight().all().len(), 24);
+        assert!(solid.select_half_edges().circular().all().is_empty());
+
+        for direction in [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]] {
+            let half_edges =
+                solid.select_half_edges().parallel_to(direction).all();
+            assert_eq!(half_edges.len(), 8);
+
+            // Half-edges that point the other way are also parallel.
+            let [x, y, z] = direction;
+            assert_eq!(
+                solid.select_half_edges().parallel_to([-x, -y, -z]).all(),
+                half_edges,
+            );
+        }
+        assert!(solid
+            .select_half_edges()
+            .parallel_to([1., 1., 0.])
+            .all()
+            .is_empty());
+
+        let top = solid.select_faces().with_normal([0., 0., 1.]).only();
+        let half_edges = solid
+            .select_half_edges()
+            .in_face(&top)
+            .parallel_to([0., 1., 0.])
+            .all();
+        assert_eq!(half_edges.len(), 2);
+        assert!(half_edges.iter().all(|half_edge| top
+            .region()
+            .exterior()
+            .half_edges()
+            .contains(half_edge)));
+    }
+
+    #[test]
+    fn select_half_edges_of_cylinder() {
+        let mut core = Instance::new();
+
+        let solid = cylinder(&mut core);
+
+        // The top and bottom face are bounded by one circle each. The side face
+        // is bounded by those circles and the seam along its axis.
+        assert_eq!(solid.select_half_edges().circular().all().len(), 4);
+
+        let seam = solid.select_half_edges().straight().all();
+        assert_eq!(seam.len(), 2);
+        assert_eq!(
+            solid.select_half_edges().parallel_to([0., 0., 1.]).all(),
+            seam
+        );
+
+        let side = solid.select_faces().curved().only();
+        assert_eq!(
+            solid
+                .select_half_edges()
+                .in_face(&side)
+                .circular()
+                .all()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn select_only_half_edge() {
+        let mut core = Instance::new();
+
+        let solid = cylinder(&mut core);
+        let bottom = solid.select_faces().with_normal([0., 0., -1.]).only();
+
+        let half_edge =
+            solid.select_half_edges().in_face(&bottom).circular().only();
+        assert_eq!(bottom.region().exterior().half_edges().only(), &half_edge);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected exactly one face, got 0")]
+    fn select_only_face_of_none() {
+        let mut core = Instance::new();
+
+        cuboid(&mut core).select_faces().curved().only();
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected exactly one face, got 6")]
+    fn select_only_face_of_several() {
+        let mut core = Instance::new();
+
+        cuboid(&mut core).select_faces().planar().only();
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected exactly one half-edge, got 0")]
+    fn select_only_half_edge_of_none() {
+        let mut core = Instance::new();
+
+        cuboid(&mut core).select_half_edges().circular().only();
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected exactly one half-edge, got 2")]
+    fn select_only_half_edge_of_several() {
+        let mut core = Instance::new();
+
+        cylinder(&mut core).select_half_edges().straight().only();
+    }
+
+    fn cylinder(core: &mut Instance) -> Solid {
+        let bottom_surface = core.services.objects.surfaces.xy_plane();
+
+        Sketch::empty()
+            .add_regions(
+                [Region::circle(Point::from([0., 0.]), 1., core)],
+                core,
+            )
+            .sweep_sketch(bottom_surface, [0., 0., 2.], core)
+    }
+}
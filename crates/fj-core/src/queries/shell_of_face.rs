@@ -0,0 +1,45 @@
+use crate::{
+    objects::{Face, Shell, Solid},
+    storage::Handle,
+};
+
+/// Determine the shell that a face is part of
+pub trait ShellOfFace {
+    /// Determine the shell that the provided face is part of
+    ///
+    /// Returns `None`, if the provided face is not part of the object this
+    /// method is called on.
+    fn shell_of_face(&self, face: &Handle<Face>) -> Option<Handle<Shell>>;
+}
+
+impl ShellOfFace for Solid {
+    fn shell_of_face(&self, face: &Handle<Face>) -> Option<Handle<Shell>> {
+        self.shells()
+            .iter()
+            .find(|shell| shell.faces().contains(face))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{queries::tests::cuboid, Instance};
+
+    use super::ShellOfFace;
+
+    #[test]
+    fn shell_of_cuboid_face() {
+        let mut core = Instance::new();
+
+        let solid = cuboid(&mut core);
+        let shell = solid.shells().only();
+
+        for face in shell.faces() {
+            assert_eq!(solid.shell_of_face(face).as_ref(), Some(shell));
+        }
+
+        let other = cuboid(&mut core);
+        let face = other.shells().only().faces().first();
+        assert_eq!(solid.shell_of_face(face), None);
+    }
+}
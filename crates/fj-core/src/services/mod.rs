@@ -3,16 +3,18 @@
 //! See [`Service`].
 
 mod objects;
+mod provenance;
 mod service;
 mod validation;
 
 use crate::{
-    objects::{AboutToBeStored, AnyObject, Objects},
+    objects::{AboutToBeStored, AnyObject, Objects, Stored},
     validate::{ValidationConfig, ValidationErrors},
 };
 
 pub use self::{
     objects::{InsertObject, Operation},
+    provenance::{Provenance, ProvenanceCommand, ProvenanceEvent},
     service::{Service, State},
     validation::{Validation, ValidationCommand, ValidationEvent},
 };
@@ -29,6 +31,12 @@ pub struct Services {
     ///
     /// Validates objects that are inserted using the objects service.
     pub validation: Service<Validation>,
+
+    /// The provenance service
+    ///
+    /// Keeps track of which objects operations derived from which other
+    /// objects.
+    pub provenance: Service<Provenance>,
 }
 
 impl Services {
@@ -43,9 +51,12 @@ impl Services {
         let validation =
             Service::new(Validation::with_validation_config(config));
 
+        let provenance = Service::default();
+
         Self {
             objects,
             validation,
+            provenance,
         }
     }
 
@@ -63,6 +74,18 @@ impl Services {
         }
     }
 
+    /// Record that an object was derived from another
+    pub fn derive_object(
+        &mut self,
+        original: AnyObject<Stored>,
+        derived: AnyObject<Stored>,
+    ) {
+        self.provenance.process(
+            ProvenanceCommand::DeriveObject { original, derived },
+            &mut Vec::new(),
+        );
+    }
+
     /// Drop `Services`; return any unhandled validation error
     pub fn drop_and_validate(self) -> Result<(), ValidationErrors> {
         let errors = self.validation.into_state().into_errors();
@@ -0,0 +1,225 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::{
+    objects::{AnyObject, Stored},
+    storage::{Handle, ObjectId},
+};
+
+use super::State;
+
+/// Records which objects were derived from which other objects
+///
+/// Operations that create new objects based on existing ones, like sweeping a
+/// half-edge into a face, or replacing a face with an updated version of it,
+/// record that relationship here. This makes it possible to refer to objects
+/// created by an operation, based on the objects that were passed to it.
+#[derive(Default)]
+pub struct Provenance {
+    derived: BTreeMap<ObjectId, Vec<AnyObject<Stored>>>,
+}
+
+impl Provenance {
+    /// Access all objects of type `T` that were derived from `original`
+    ///
+    /// This includes objects that were derived indirectly, through any number
+    /// of intermediate objects. Sweeping a half-edge might first reverse it,
+    /// for example, and then sweep the reversed version into a face. That face
+    /// is considered to be derived from the original half-edge.
+    ///
+    /// Objects are returned in the order in which they were derived. Since
+    /// intermediate versions of an object are included, it is usually
+    /// necessary to filter the result, for example by checking which of the
+    /// returned faces are part of a given shell.
+    pub fn derived_from<T>(
+        &self,
+        original: impl Into<AnyObject<Stored>>,
+    ) -> Vec<Handle<T>>
+    where
+        Handle<T>: TryFrom<AnyObject<Stored>>,
+    {
+        let original = original.into();
+
+        let mut visited = BTreeSet::new();
+        let mut queue = VecDeque::from([original.id()]);
+        let mut derived = Vec::new();
+
+        while let Some(id) = queue.pop_front() {
+            for object in self.derived.get(&id).into_iter().flatten() {
+                if !visited.insert(object.id()) {
+                    continue;
+                }
+
+                queue.push_back(object.id());
+                derived.push(object.clone());
+            }
+        }
+
+        derived
+            .into_iter()
+            .filter_map(|object| Handle::<T>::try_from(object).ok())
+            .collect()
+    }
+}
+
+impl State for Provenance {
+    type Command = ProvenanceCommand;
+    type Event = ProvenanceEvent;
+
+    fn decide(&self, command: Self::Command, events: &mut Vec<Self::Event>) {
+        let ProvenanceCommand::DeriveObject { original, derived } = command;
+
+        if original.id() == derived.id() {
+            return;
+        }
+
+        let already_known = self
+            .derived
+            .get(&original.id())
+            .is_some_and(|objects| objects.contains(&derived));
+        if already_known {
+            return;
+        }
+
+        events.push(ProvenanceEvent::ObjectDerived { original, derived });
+    }
+
+    fn evolve(&mut self, event: &Self::Event) {
+        let ProvenanceEvent::ObjectDerived { original, derived } = event;
+
+        self.derived
+            .entry(original.id())
+            .or_default()
+            .push(derived.clone());
+    }
+}
+
+/// The command accepted by the provenance service
+pub enum ProvenanceCommand {
+    /// Record that an object was derived from another
+    DeriveObject {
+        /// The object that was passed to the operation
+        original: AnyObject<Stored>,
+
+        /// The object that the operation created based on `original`
+        derived: AnyObject<Stored>,
+    },
+}
+
+/// The event produced by the provenance service
+#[derive(Clone)]
+pub enum ProvenanceEvent {
+    /// An object was derived from another
+    ObjectDerived {
+        /// The object that was passed to the operation
+        original: AnyObject<Stored>,
+
+        /// The object that the operation created based on `original`
+        derived: AnyObject<Stored>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        objects::{HalfEdge, Region, Sketch, Solid},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            insert::Insert,
+            split::SplitFace,
+            sweep::SweepSketch,
+            update::UpdateSketch,
+        },
+        queries::Select,
+        storage::Handle,
+        Instance,
+    };
+
+    #[test]
+    fn side_face_derived_from_swept_half_edge() {
+        let mut core = Instance::new();
+
+        let (solid, half_edges) = sweep_square(&mut core);
+        let shell = solid.shells().only();
+
+        // The first half-edge of the square runs along the x-axis.
+        let face = shell
+            .select_faces()
+            .derived_from(half_edges[0].clone(), &core.services.provenance)
+            .only();
+        assert_eq!(
+            face,
+            shell.select_faces().with_normal([0., -1., 0.]).only()
+        );
+
+        // Edges that were derived from the same half-edge, like the top edge
+        // of that face, run along the x-axis too.
+        let derived_half_edges = shell
+            .select_half_edges()
+            .derived_from(half_edges[0].clone(), &core.services.provenance);
+        let num_derived = derived_half_edges.clone().all().len();
+        assert!(num_derived > 0);
+        assert_eq!(
+            derived_half_edges.parallel_to([1., 0., 0.]).all().len(),
+            num_derived
+        );
+
+        // Every half-edge produced exactly one side face.
+        for half_edge in half_edges {
+            let faces = shell
+                .select_faces()
+                .derived_from(half_edge, &core.services.provenance)
+                .all();
+            assert_eq!(faces.len(), 1);
+        }
+    }
+
+    #[test]
+    fn faces_derived_through_split_face() {
+        let mut core = Instance::new();
+
+        let (solid, half_edges) = sweep_square(&mut core);
+        let shell = solid.shells().only();
+
+        let face = shell
+            .select_faces()
+            .derived_from(half_edges[0].clone(), &core.services.provenance)
+            .only();
+        let cycle = face.region().exterior();
+        let line = [
+            (cycle.half_edges().nth(0).unwrap(), [0.5]),
+            (cycle.half_edges().nth(2).unwrap(), [0.5]),
+        ];
+        let (shell, [a, b]) = shell.split_face(&face, line, &mut core);
+        let shell = shell.insert(&mut core);
+
+        // The faces that the split created are derived from the original side
+        // face, and thus from the half-edge that it was swept from.
+        let mut faces = shell
+            .select_faces()
+            .derived_from(half_edges[0].clone(), &core.services.provenance)
+            .all();
+        faces.sort();
+        let mut expected = vec![a, b];
+        expected.sort();
+        assert_eq!(faces, expected);
+    }
+
+    /// Sweep a unit square, and return the solid and the half-edges of the
+    /// square
+    fn sweep_square(core: &mut Instance) -> (Solid, Vec<Handle<HalfEdge>>) {
+        let region =
+            Region::polygon([[0., 0.], [1., 0.], [1., 1.], [0., 1.]], core)
+                .insert(core);
+        let half_edges =
+            region.exterior().half_edges().iter().cloned().collect();
+
+        let surface = core.services.objects.surfaces.xy_plane();
+        let solid = Sketch::empty().add_regions([region], core).sweep_sketch(
+            surface,
+            [0., 0., 1.],
+            core,
+        );
+
+        (solid, half_edges)
+    }
+}
@@ -25,6 +25,14 @@ impl Validation {
         Self { errors, config }
     }
 
+    /// Access the validation configuration
+    ///
+    /// Code that constructs objects can use this, to validate them before
+    /// inserting them, with the same configuration that insertion uses.
+    pub fn config(&self) -> ValidationConfig {
+        self.config
+    }
+
     /// Drop this instance, returning the errors it contained
     pub fn into_errors(mut self) -> ValidationErrors {
         ValidationErrors(self.errors.drain().map(|(_, error)| error).collect())
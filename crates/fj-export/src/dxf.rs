@@ -0,0 +1,148 @@
+use std::io::{self, Write};
+
+use fj_math::Scalar;
+
+use crate::outline::{Arc, Outline, Segment};
+
+/// Write an outline as an ASCII DXF file
+///
+/// Only the `ENTITIES` section is written, which is enough for CAD and CAM
+/// software to read it. Each segment becomes a `LINE`, `ARC`, `CIRCLE`, or, if
+/// the outline has been distorted into one, an `ELLIPSE` entity.
+pub fn write_dxf(outline: &Outline, mut writer: impl Write) -> io::Result<()> {
+    let mut dxf = DxfWriter {
+        writer: &mut writer,
+    };
+
+    dxf.pair(0, "SECTION")?;
+    dxf.pair(2, "ENTITIES")?;
+
+    for segment in outline.cycles.iter().flatten() {
+        match segment {
+            Segment::Line { start, end } => {
+                dxf.entity("LINE")?;
+                dxf.point(10, start.u, start.v)?;
+                dxf.point(11, end.u, end.v)?;
+            }
+            Segment::Arc(arc) if arc.is_circle() => {
+                let radius = arc.major.magnitude();
+
+                if arc.is_full() {
+                    dxf.entity("CIRCLE")?;
+                    dxf.point(10, arc.center.u, arc.center.v)?;
+                    dxf.pair(40, radius)?;
+                } else {
+                    let [start, end] = ccw_range(arc).map(|t| {
+                        let angle = t + arc.rotation();
+                        angle.into_f64().to_degrees().rem_euclid(360.)
+                    });
+
+                    dxf.entity("ARC")?;
+                    dxf.point(10, arc.center.u, arc.center.v)?;
+                    dxf.pair(40, radius)?;
+                    dxf.pair(50, start)?;
+                    dxf.pair(51, end)?;
+                }
+            }
+            Segment::Arc(arc) => {
+                let [start, end] = if arc.is_full() {
+                    [Scalar::ZERO, Scalar::TAU]
+                } else {
+                    ccw_range(arc)
+                };
+
+                dxf.entity("ELLIPSE")?;
+                dxf.point(10, arc.center.u, arc.center.v)?;
+                dxf.point(11, arc.major.u, arc.major.v)?;
+                dxf.pair(210, 0.)?;
+                dxf.pair(220, 0.)?;
+                dxf.pair(230, 1.)?;
+                dxf.pair(40, arc.minor.magnitude() / arc.major.magnitude())?;
+                dxf.pair(41, start)?;
+                dxf.pair(42, end)?;
+            }
+        }
+    }
+
+    dxf.pair(0, "ENDSEC")?;
+    dxf.pair(0, "EOF")?;
+
+    Ok(())
+}
+
+/// Compute the parameter range of an arc, in counter-clockwise direction
+///
+/// DXF arcs always run counter-clockwise, so the direction of arcs that run
+/// clockwise is reversed.
+fn ccw_range(arc: &Arc) -> [Scalar; 2] {
+    if arc.is_ccw() {
+        [arc.start, arc.end]
+    } else {
+        [arc.end, arc.start]
+    }
+}
+
+struct DxfWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> DxfWriter<W> {
+    fn pair(&mut self, code: u32, value: impl ToString) -> io::Result<()> {
+        writeln!(self.writer, "{code}")?;
+        writeln!(self.writer, "{}", value.to_string())
+    }
+
+    fn entity(&mut self, kind: &str) -> io::Result<()> {
+        self.pair(0, kind)?;
+        self.pair(8, "0")
+    }
+
+    fn point(&mut self, code: u32, x: Scalar, y: Scalar) -> io::Result<()> {
+        self.pair(code, x)?;
+        self.pair(code + 10, y)?;
+        self.pair(code + 20, 0.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_core::{
+        objects::{Region, Sketch},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            update::UpdateSketch,
+        },
+        Instance,
+    };
+
+    use crate::outline::Outline;
+
+    use super::write_dxf;
+
+    #[test]
+    fn circle() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let sketch = Sketch::empty()
+            .add_regions([Region::circle([1., 2.], 3., &mut core)], &mut core);
+
+        let mut dxf = Vec::new();
+        write_dxf(&Outline::from_sketch(&sketch), &mut dxf)?;
+        let dxf = String::from_utf8(dxf)?;
+        let pairs = dxf.lines().collect::<Vec<_>>();
+
+        let entities = pairs
+            .chunks(2)
+            .filter(|pair| pair[0] == "0")
+            .map(|pair| pair[1])
+            .collect::<Vec<_>>();
+        assert_eq!(entities, ["SECTION", "CIRCLE", "ENDSEC", "EOF"]);
+
+        let circle = dxf.split("CIRCLE").nth(1).unwrap_or_default();
+        for (code, value) in [("10", "1"), ("20", "2"), ("40", "3")] {
+            assert!(circle.contains(&format!("\n{code}\n{value}\n")));
+        }
+
+        Ok(())
+    }
+}
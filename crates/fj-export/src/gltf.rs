@@ -0,0 +1,274 @@
+//! # glTF export
+//!
+//! See [`write_gltf`] and [`write_glb`].
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use fj_interop::{Color, Mesh};
+use fj_math::Point;
+
+/// Write a mesh as a glTF file, with the binary data embedded into the JSON
+pub fn write_gltf(
+    mesh: &Mesh<Point<3>>,
+    mut writer: impl io::Write,
+) -> io::Result<()> {
+    let (json, _) = gltf(mesh, |buffer| {
+        format!(
+            "\"uri\":\"data:application/octet-stream;base64,{}\",",
+            STANDARD.encode(buffer)
+        )
+    });
+
+    writer.write_all(json.as_bytes())
+}
+
+/// Write a mesh as a binary glTF file
+pub fn write_glb(
+    mesh: &Mesh<Point<3>>,
+    mut writer: impl io::Write,
+) -> io::Result<()> {
+    let (json, buffer) = gltf(mesh, |_| String::new());
+
+    // Both chunks must be aligned to 4 bytes. The JSON chunk is padded with
+    // spaces, so it remains valid JSON.
+    let mut json = json.into_bytes();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    let mut buffer = buffer;
+    buffer.resize(buffer.len().next_multiple_of(4), 0);
+
+    let mut chunks = vec![(0x4E4F534A_u32, json)];
+    if !buffer.is_empty() {
+        chunks.push((0x004E4942, buffer));
+    }
+
+    let length =
+        12 + chunks.iter().map(|(_, data)| 8 + data.len()).sum::<usize>();
+    let length = u32::try_from(length).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, "mesh too large for GLB")
+    })?;
+
+    writer.write_all(b"glTF")?;
+    writer.write_all(&2_u32.to_le_bytes())?;
+    writer.write_all(&length.to_le_bytes())?;
+
+    for (kind, data) in chunks {
+        writer.write_all(&(data.len() as u32).to_le_bytes())?;
+        writer.write_all(&kind.to_le_bytes())?;
+        writer.write_all(&data)?;
+    }
+
+    Ok(())
+}
+
+/// Create the JSON of a glTF file, and its binary buffer
+///
+/// `uri` creates the part of the buffer definition that tells where its data
+/// can be found, from the data.
+fn gltf(
+    mesh: &Mesh<Point<3>>,
+    uri: impl Fn(&[u8]) -> String,
+) -> (String, Vec<u8>) {
+    // Each color becomes a primitive with its own material. That's supported
+    // everywhere, while support for vertex colors is spotty.
+    let mut triangles_by_color = BTreeMap::new();
+    for triangle in mesh.triangles() {
+        triangles_by_color
+            .entry(triangle.color)
+            .or_insert_with(Vec::new)
+            .push(triangle.inner.points());
+    }
+
+    let mut buffer = Vec::new();
+    let mut buffer_views = Vec::new();
+    let mut accessors = Vec::new();
+    let mut materials = Vec::new();
+    let mut primitives = Vec::new();
+
+    for (color, triangles) in triangles_by_color {
+        let mut positions = Vec::new();
+        let mut indices = Vec::new();
+        let mut indices_by_point = HashMap::new();
+
+        for point in triangles.into_iter().flatten() {
+            let index = *indices_by_point.entry(point).or_insert_with(|| {
+                positions.push(point.coords.components.map(|s| s.into_f32()));
+                positions.len() as u32 - 1
+            });
+            indices.push(index);
+        }
+
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for position in &positions {
+            for i in 0..3 {
+                min[i] = min[i].min(position[i]);
+                max[i] = max[i].max(position[i]);
+            }
+        }
+
+        let offset = buffer.len();
+        buffer.extend(positions.iter().flatten().flat_map(|c| c.to_le_bytes()));
+        buffer_views.push(format!(
+            "{{\"buffer\":0,\"byteOffset\":{offset},\"byteLength\":{},\
+            \"target\":34962}}",
+            buffer.len() - offset,
+        ));
+        accessors.push(format!(
+            "{{\"bufferView\":{},\"componentType\":5126,\"count\":{},\
+            \"type\":\"VEC3\",\"min\":{},\"max\":{}}}",
+            buffer_views.len() - 1,
+            positions.len(),
+            array(min),
+            array(max),
+        ));
+
+        let offset = buffer.len();
+        buffer.extend(indices.iter().flat_map(|i| i.to_le_bytes()));
+        buffer_views.push(format!(
+            "{{\"buffer\":0,\"byteOffset\":{offset},\"byteLength\":{},\
+            \"target\":34963}}",
+            buffer.len() - offset,
+        ));
+        accessors.push(format!(
+            "{{\"bufferView\":{},\"componentType\":5125,\"count\":{},\
+            \"type\":\"SCALAR\"}}",
+            buffer_views.len() - 1,
+            indices.len(),
+        ));
+
+        materials.push(material(color));
+        primitives.push(format!(
+            "{{\"attributes\":{{\"POSITION\":{}}},\"indices\":{},\
+            \"material\":{}}}",
+            accessors.len() - 2,
+            accessors.len() - 1,
+            materials.len() - 1,
+        ));
+    }
+
+    // glTF uses meters, and the y-axis points up. Fornjot doesn't define a
+    // unit, but millimeters are assumed by all formats that have units, and
+    // the z-axis points up. Rotating by a quarter turn around the x-axis,
+    // and scaling the node, fixes that, without touching the vertex data.
+    let mut node = String::from(
+        "{\"rotation\":[-0.70710677,0,0,0.70710677],\
+        \"scale\":[0.001,0.001,0.001]",
+    );
+    let mut json = String::from(
+        "{\"asset\":{\"version\":\"2.0\",\"generator\":\"Fornjot\"},\
+        \"scene\":0,\"scenes\":[{\"nodes\":[0]}],",
+    );
+
+    if !primitives.is_empty() {
+        node.push_str(",\"mesh\":0");
+        json.push_str(&format!(
+            "\"meshes\":[{{\"primitives\":[{}]}}],\"materials\":[{}],\
+            \"accessors\":[{}],\"bufferViews\":[{}],\
+            \"buffers\":[{{{}\"byteLength\":{}}}],",
+            primitives.join(","),
+            materials.join(","),
+            accessors.join(","),
+            buffer_views.join(","),
+            uri(&buffer),
+            buffer.len(),
+        ));
+    }
+
+    node.push('}');
+    json.push_str(&format!("\"nodes\":[{node}]}}"));
+
+    (json, buffer)
+}
+
+fn material(Color(rgba): Color) -> String {
+    // Colors are sRGB, but glTF expects linear values. Alpha is linear in
+    // both cases.
+    let [r, g, b, a] = rgba.map(|channel| f32::from(channel) / 255.);
+    let [r, g, b] = [r, g, b].map(|channel| {
+        if channel <= 0.04045 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    });
+
+    let alpha_mode = if a < 1. {
+        ",\"alphaMode\":\"BLEND\""
+    } else {
+        ""
+    };
+
+    format!(
+        "{{\"pbrMetallicRoughness\":{{\"baseColorFactor\":{},\
+        \"metallicFactor\":0,\"roughnessFactor\":1}}{alpha_mode}}}",
+        array([r, g, b, a]),
+    )
+}
+
+fn array<const D: usize>(values: [f32; D]) -> String {
+    let values = values.map(|value| value.to_string());
+    format!("[{}]", values.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::two_colored_triangles;
+
+    use super::{write_glb, write_gltf};
+
+    #[test]
+    fn glb_layout() -> anyhow::Result<()> {
+        let mut glb = Vec::new();
+        write_glb(&two_colored_triangles(), &mut glb)?;
+
+        let u32_at = |offset: usize| {
+            let bytes = glb[offset..offset + 4].try_into().unwrap();
+            u32::from_le_bytes(bytes) as usize
+        };
+
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(u32_at(4), 2);
+        assert_eq!(u32_at(8), glb.len());
+
+        let json_length = u32_at(12);
+        assert_eq!(&glb[16..20], b"JSON");
+        assert_eq!(json_length % 4, 0);
+
+        let json = std::str::from_utf8(&glb[20..20 + json_length])?;
+        assert!(json.starts_with('{'));
+        assert!(json.trim_end().ends_with('}'));
+        assert_eq!(json.matches("\"material\":").count(), 2);
+
+        let bin = 20 + json_length;
+        let bin_length = u32_at(bin);
+        assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+        assert_eq!(bin_length % 4, 0);
+        assert_eq!(bin + 8 + bin_length, glb.len());
+
+        // Each triangle has its own primitive, with 3 positions of 12 bytes,
+        // and 3 indices of 4 bytes.
+        assert_eq!(bin_length, 2 * (3 * 12 + 3 * 4));
+        assert!(json.contains(&format!("\"byteLength\":{bin_length}}}")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn gltf_embeds_buffer() -> anyhow::Result<()> {
+        let mut gltf = Vec::new();
+        write_gltf(&two_colored_triangles(), &mut gltf)?;
+        let gltf = String::from_utf8(gltf)?;
+
+        assert!(
+            gltf.contains("\"uri\":\"data:application/octet-stream;base64,")
+        );
+        assert_eq!(gltf.matches("\"baseColorFactor\"").count(), 2);
+        assert_eq!(gltf.matches("\"alphaMode\":\"BLEND\"").count(), 1);
+
+        Ok(())
+    }
+}
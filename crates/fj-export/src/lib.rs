@@ -8,30 +8,74 @@
 //!
 //! [Fornjot]: https://www.fornjot.app/
 
-use std::{fs::File, path::Path};
+mod dxf;
+mod gltf;
+mod outline;
+mod step;
+mod svg;
+mod threemf;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
 
 use thiserror::Error;
 
-use fj_interop::Mesh;
-use fj_math::{Point, Triangle};
+use fj_core::objects::{Face, Sketch, Solid};
+use fj_interop::{Color, Mesh};
+use fj_math::{Point, Scalar, Triangle, Vector};
+
+use self::outline::Outline;
 
 /// Export the provided mesh to the file at the given path.
 ///
 /// This function will create a file if it does not exist, and will truncate it if it does.
 ///
-/// Currently 3MF & STL file types are supported. The case insensitive file extension of
-/// the provided path is used to switch between supported types.
+/// Currently 3MF, STL, OBJ, glTF & GLB file types are supported. The case insensitive file
+/// extension of the provided path is used to switch between supported types.
+///
+/// 3MF, glTF, and GLB files include the colors of the triangles, as materials. OBJ files refer
+/// to materials in an MTL file, which is written next to them, with the same name.
+///
+/// OBJ files include vertex normals. Triangles that meet at a shallow angle, like those that
+/// approximate a curved surface, are shaded smoothly. Sharper edges stay sharp.
 pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
+    export_with_options(mesh, path, &ExportOptions::default())
+}
+
+/// Export the provided mesh to the file at the given path, using the provided options.
+///
+/// Works like [`export`], but allows for configuring how the file is written.
+pub fn export_with_options(
+    mesh: &Mesh<Point<3>>,
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<(), Error> {
     match path.extension() {
         Some(extension) if extension.to_ascii_uppercase() == "3MF" => {
             export_3mf(mesh, path)
         }
         Some(extension) if extension.to_ascii_uppercase() == "STL" => {
-            export_stl(mesh, path)
+            export_stl(mesh, path, options.stl_format)
         }
         Some(extension) if extension.to_ascii_uppercase() == "OBJ" => {
             export_obj(mesh, path)
         }
+        Some(extension) if extension.to_ascii_uppercase() == "GLTF" => {
+            let mut file = BufWriter::new(File::create(path)?);
+            gltf::write_gltf(mesh, &mut file)?;
+            file.flush()?;
+            Ok(())
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "GLB" => {
+            let mut file = BufWriter::new(File::create(path)?);
+            gltf::write_glb(mesh, &mut file)?;
+            file.flush()?;
+            Ok(())
+        }
         Some(extension) => Err(Error::InvalidExtension(
             extension.to_string_lossy().into_owned(),
         )),
@@ -39,39 +83,126 @@ pub fn export(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
     }
 }
 
-fn export_3mf(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    let vertices = mesh
-        .vertices()
-        .map(|point| threemf::model::Vertex {
-            x: point.x.into_f64(),
-            y: point.y.into_f64(),
-            z: point.z.into_f64(),
-        })
-        .collect();
+/// Options for [`export_with_options`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExportOptions {
+    /// The variant of the format used for STL files
+    pub stl_format: StlFormat,
+}
 
-    let indices: Vec<_> = mesh.indices().collect();
-    let triangles = indices
-        .chunks(3)
-        .map(|triangle| threemf::model::Triangle {
-            v1: triangle[0] as usize,
-            v2: triangle[1] as usize,
-            v3: triangle[2] as usize,
-        })
-        .collect();
+/// A variant of the STL format
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StlFormat {
+    /// Binary STL, without colors
+    #[default]
+    Binary,
 
-    let mesh = threemf::Mesh {
-        vertices: threemf::model::Vertices { vertex: vertices },
-        triangles: threemf::model::Triangles {
-            triangle: triangles,
-        },
-    };
+    /// Binary STL, with the color of each triangle in its attribute bytes
+    ///
+    /// Uses the convention of VisCAM and SolidView: Red, green, and blue are
+    /// stored with 5 bits each, starting with blue in the least significant
+    /// bits. The most significant bit is set, to mark the color as valid.
+    /// Colors lose precision that way, and their alpha value is dropped.
+    BinaryWithColors,
+
+    /// ASCII STL, which can't include colors
+    Ascii,
+}
+
+/// Export the provided sketch to the file at the given path.
+///
+/// This function will create a file if it does not exist, and will truncate it if it does.
+///
+/// Currently DXF & SVG file types are supported. The case insensitive file extension of
+/// the provided path is used to switch between supported types.
+///
+/// Unlike the mesh formats that [`export`] writes, these are vector formats. Lines, arcs, and
+/// circles are written as such, instead of being approximated.
+pub fn export_sketch(sketch: &Sketch, path: &Path) -> Result<(), Error> {
+    export_outline(&Outline::from_sketch(sketch), path)
+}
+
+/// Export the provided planar face to the file at the given path.
+///
+/// Works like [`export_sketch`]. The face is written as seen from its front side, in a
+/// coordinate system whose origin and x-axis are those of the face's surface.
+///
+/// Returns [`Error::NonPlanarFace`], if the face's surface is not a plane.
+pub fn export_face(face: &Face, path: &Path) -> Result<(), Error> {
+    let outline = Outline::from_face(face).ok_or(Error::NonPlanarFace)?;
+    export_outline(&outline, path)
+}
+
+/// Export the provided solid to the file at the given path.
+///
+/// This function will create a file if it does not exist, and will truncate it if it does.
+///
+/// Currently STEP files are supported, using the file extensions `.step` or `.stp`. They are
+/// written according to the AP214 schema, which CAD software that reads AP242 can also read.
+///
+/// The solid is written as a boundary representation, with exact planes, cylinders, lines, and
+/// circles. Geometry that can't be represented like that results in
+/// [`Error::UnsupportedGeometry`].
+pub fn export_solid(solid: &Solid, path: &Path) -> Result<(), Error> {
+    match path.extension() {
+        Some(extension)
+            if extension.to_ascii_uppercase() == "STEP"
+                || extension.to_ascii_uppercase() == "STP" =>
+        {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
 
-    threemf::write(path, mesh)?;
+            let mut file = BufWriter::new(File::create(path)?);
+            step::write_step(solid, &name, &mut file)?;
+            file.flush()?;
+            Ok(())
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
 
+fn export_outline(outline: &Outline, path: &Path) -> Result<(), Error> {
+    match path.extension() {
+        Some(extension) if extension.to_ascii_uppercase() == "DXF" => {
+            let mut file = BufWriter::new(File::create(path)?);
+            dxf::write_dxf(outline, &mut file)?;
+            file.flush()?;
+            Ok(())
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "SVG" => {
+            let mut file = BufWriter::new(File::create(path)?);
+            svg::write_svg(outline, &mut file)?;
+            file.flush()?;
+            Ok(())
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
+
+fn export_3mf(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+    threemf::write_3mf(mesh, &mut file)?;
+    file.flush()?;
     Ok(())
 }
 
-fn export_stl(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
+fn export_stl(
+    mesh: &Mesh<Point<3>>,
+    path: &Path,
+    format: StlFormat,
+) -> Result<(), Error> {
+    if format == StlFormat::Ascii {
+        return export_ascii_stl(mesh, path);
+    }
+
     let points = mesh
         .triangles()
         .map(|triangle| triangle.inner.points())
@@ -87,14 +218,24 @@ fn export_stl(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
         .map(|triangle: Triangle<3>| triangle.normal())
         .map(|vector| vector.components.map(|s| s.into_f32()));
 
+    let attributes = mesh.triangles().map(|triangle| match format {
+        StlFormat::BinaryWithColors => {
+            let [r, g, b, _] =
+                triangle.color.0.map(|channel| u16::from(channel >> 3));
+            0x8000 | r << 10 | g << 5 | b
+        }
+        StlFormat::Binary | StlFormat::Ascii => 0,
+    });
+
     let triangles = vertices
         .zip(normals)
-        .map(|([v1, v2, v3], normal)| stl::Triangle {
+        .zip(attributes)
+        .map(|(([v1, v2, v3], normal), attr_byte_count)| stl::Triangle {
             normal,
             v1,
             v2,
             v3,
-            attr_byte_count: 0,
+            attr_byte_count,
         })
         .collect::<Vec<_>>();
 
@@ -116,53 +257,182 @@ fn export_stl(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+fn export_ascii_stl(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().replace(char::is_whitespace, "_"))
+        .unwrap_or_default();
+
+    let mut file = BufWriter::new(File::create(path)?);
+
+    writeln!(file, "solid {name}")?;
+    for triangle in mesh.triangles() {
+        let [nx, ny, nz] =
+            triangle.inner.normal().components.map(|s| s.into_f32());
+        writeln!(file, "  facet normal {nx:e} {ny:e} {nz:e}")?;
+        writeln!(file, "    outer loop")?;
+        for point in triangle.inner.points() {
+            let [x, y, z] = point.coords.components.map(|s| s.into_f32());
+            writeln!(file, "      vertex {x:e} {y:e} {z:e}")?;
+        }
+        writeln!(file, "    endloop")?;
+        writeln!(file, "  endfacet")?;
+    }
+    writeln!(file, "endsolid {name}")?;
+
+    file.flush()?;
+
+    Ok(())
+}
+
 fn export_obj(mesh: &Mesh<Point<3>>, path: &Path) -> Result<(), Error> {
-    let mut f = File::create(path)?;
-
-    for (cnt, t) in mesh.triangles().enumerate() {
-        // write each point of the triangle
-        for v in t.inner.points() {
-            wavefront_rs::obj::writer::Writer { auto_newline: true }
-                .write(
-                    &mut f,
-                    &wavefront_rs::obj::entity::Entity::Vertex {
-                        x: v.x.into_f64(),
-                        y: v.y.into_f64(),
-                        z: v.z.into_f64(),
-                        w: None,
-                    },
-                )
-                .or(Err(Error::OBJ))?;
+    use wavefront_rs::obj::{self, entity::FaceVertex};
+
+    // The colors of the triangles are written as materials, into a separate
+    // file next to the OBJ file.
+    let mtl_path = path.with_extension("mtl");
+    let mtl_name = mtl_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let material_name = |Color([r, g, b, a]): Color| {
+        format!("color_{r:02x}{g:02x}{b:02x}{a:02x}")
+    };
+
+    let vertex_normals = vertex_normals(mesh);
+
+    let mut triangles_by_color = BTreeMap::new();
+    let indices = mesh.indices().collect::<Vec<_>>();
+    for ((indices, triangle), normals) in
+        indices.chunks(3).zip(mesh.triangles()).zip(&vertex_normals)
+    {
+        triangles_by_color
+            .entry(triangle.color)
+            .or_insert_with(Vec::new)
+            .push((indices, normals));
+    }
+
+    // The MTL file is written directly. `wavefront_rs` writes the keywords
+    // for colors in lowercase, which not all readers accept.
+    let mut f = BufWriter::new(File::create(&mtl_path)?);
+    for &color in triangles_by_color.keys() {
+        let [r, g, b, a] = color.0.map(|channel| f64::from(channel) / 255.);
+
+        writeln!(f, "newmtl {}", material_name(color))?;
+        writeln!(f, "Kd {r} {g} {b}")?;
+        writeln!(f, "d {a}")?;
+        writeln!(f, "illum 1")?;
+    }
+    f.flush()?;
+
+    let mut f = BufWriter::new(File::create(path)?);
+    let mut write_obj = |entity| {
+        obj::writer::Writer { auto_newline: true }
+            .write(&mut f, &entity)
+            .or(Err(Error::OBJ))
+    };
+
+    write_obj(obj::entity::Entity::MtlLib { name: mtl_name })?;
+
+    for v in mesh.vertices() {
+        write_obj(obj::entity::Entity::Vertex {
+            x: v.x.into_f64(),
+            y: v.y.into_f64(),
+            z: v.z.into_f64(),
+            w: None,
+        })?;
+    }
+
+    // Vertices share a normal, if the triangles that meet there are smoothed
+    // into each other. Normals are only written once.
+    let mut normals = HashMap::new();
+
+    for (color, triangles) in triangles_by_color {
+        write_obj(obj::entity::Entity::UseMtl {
+            name: material_name(color),
+        })?;
+
+        for (indices, vertex_normals) in triangles {
+            let mut vertices = Vec::new();
+
+            for (&index, &normal) in indices.iter().zip(vertex_normals) {
+                let next_index = normals.len() + 1;
+                let normal_index = *normals.entry(normal).or_insert(next_index);
+                if normal_index == next_index {
+                    let [x, y, z] = normal.components.map(|s| s.into_f64());
+                    write_obj(obj::entity::Entity::VertexNormal { x, y, z })?;
+                }
+
+                vertices.push(FaceVertex {
+                    vertex: i64::from(index) + 1,
+                    texture: None,
+                    normal: Some(normal_index as i64),
+                });
+            }
+
+            write_obj(obj::entity::Entity::Face { vertices })?;
         }
+    }
+    f.flush()?;
+
+    Ok(())
+}
 
-        // write the triangle
-        wavefront_rs::obj::writer::Writer { auto_newline: true }
-            .write(
-                &mut f,
-                &wavefront_rs::obj::entity::Entity::Face {
-                    vertices: vec![
-                        wavefront_rs::obj::entity::FaceVertex {
-                            vertex: (cnt * 3 + 1) as i64,
-                            texture: None,
-                            normal: None,
-                        },
-                        wavefront_rs::obj::entity::FaceVertex {
-                            vertex: (cnt * 3 + 2) as i64,
-                            texture: None,
-                            normal: None,
-                        },
-                        wavefront_rs::obj::entity::FaceVertex {
-                            vertex: (cnt * 3 + 3) as i64,
-                            texture: None,
-                            normal: None,
-                        },
-                    ],
-                },
+/// The largest angle between triangles, at which OBJ export smoothes them
+const CREASE_ANGLE: f64 = 30.;
+
+/// Compute the normal at each vertex of each triangle of a mesh
+///
+/// The mesh doesn't know which triangles approximate the same curved surface.
+/// Triangles that meet at an angle of less than [`CREASE_ANGLE`] are assumed
+/// to do so. The normal of a vertex is the average of the normals of those
+/// triangles that share the vertex, weighted by their area. Where triangles
+/// meet at a sharper angle, the edge between them stays sharp.
+fn vertex_normals(mesh: &Mesh<Point<3>>) -> Vec<[Vector<3>; 3]> {
+    let indices = mesh.indices().collect::<Vec<_>>();
+
+    // The cross product of two edges of a triangle has a length of twice its
+    // area, which is what weighs the average.
+    let triangles = indices
+        .chunks(3)
+        .zip(mesh.triangles())
+        .map(|(indices, triangle)| {
+            let [a, b, c] = triangle.inner.points();
+            (
+                (b - a).cross(&(c - a)),
+                [indices[0], indices[1], indices[2]],
             )
-            .or(Err(Error::OBJ))?;
+        })
+        .collect::<Vec<_>>();
+
+    let mut triangles_by_vertex = HashMap::new();
+    for (i, (_, vertices)) in triangles.iter().enumerate() {
+        for vertex in vertices {
+            triangles_by_vertex
+                .entry(*vertex)
+                .or_insert_with(Vec::new)
+                .push(i);
+        }
     }
 
-    Ok(())
+    let min_cos = Scalar::from_f64(CREASE_ANGLE.to_radians().cos());
+
+    triangles
+        .iter()
+        .map(|(normal, vertices)| {
+            let direction = normal.normalize();
+
+            vertices.map(|vertex| {
+                triangles_by_vertex[&vertex]
+                    .iter()
+                    .map(|&i| triangles[i].0)
+                    .filter(|other| other.normalize().dot(&direction) > min_cos)
+                    .fold(Vector::from([0., 0., 0.]), |sum, other| sum + other)
+                    .normalize()
+            })
+        })
+        .collect()
 }
 
 /// An error that can occur while exporting
@@ -184,11 +454,181 @@ pub enum Error {
     #[error("maximum triangle count exceeded")]
     InvalidTriangleCount,
 
-    /// Threemf error whilst exporting to 3MF file
-    #[error("threemf error whilst exporting to 3MF file")]
-    ThreeMF(#[from] threemf::Error),
+    /// Zip error whilst exporting to 3MF file
+    #[error("zip error whilst exporting to 3MF file")]
+    Zip(#[from] zip::result::ZipError),
 
     /// OBJ exporter error whilst exporting to OBJ file
     #[error("obj error whilst exporting to OBJ file")]
     OBJ,
+
+    /// Face to export is not planar
+    #[error("face to export is not planar")]
+    NonPlanarFace,
+
+    /// Geometry that can't be represented in the target file format
+    #[error("unsupported geometry whilst exporting: {0}")]
+    UnsupportedGeometry(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, fs};
+
+    use fj_interop::{Color, Mesh};
+    use fj_math::Point;
+
+    use super::{export_obj, export_stl, StlFormat};
+
+    #[test]
+    fn obj_indices_and_materials() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("square.obj");
+        export_obj(&two_colored_triangles(), &path)?;
+
+        let obj = fs::read_to_string(&path)?;
+        let mtl = fs::read_to_string(dir.path().join("square.mtl"))?;
+
+        let lines = |keyword: &str| {
+            obj.lines()
+                .filter_map(|line| line.strip_prefix(&format!("{keyword} ")))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(lines("mtllib"), ["square.mtl"]);
+        assert_eq!(lines("v").len(), 4);
+
+        // Both triangles are in the same plane, so they share their normal.
+        assert_eq!(lines("vn").len(), 1);
+
+        let faces = lines("f");
+        assert_eq!(faces.len(), 2);
+        for face in faces {
+            let vertices = face.split_whitespace().collect::<Vec<_>>();
+            assert_eq!(vertices.len(), 3);
+
+            for vertex in vertices {
+                let (v, vn) = vertex.split_once("//").unwrap();
+                assert!((1..=4).contains(&v.parse::<usize>()?), "{vertex}");
+                assert_eq!(vn, "1");
+            }
+        }
+
+        let materials = mtl
+            .lines()
+            .filter_map(|line| line.strip_prefix("newmtl "))
+            .map(str::to_string)
+            .collect::<BTreeSet<_>>();
+        let used = lines("usemtl").into_iter().collect::<BTreeSet<_>>();
+        assert_eq!(materials.len(), 2);
+        assert_eq!(used, materials);
+
+        Ok(())
+    }
+
+    #[test]
+    fn obj_normals() -> anyhow::Result<()> {
+        // Two triangles that share an edge along the x-axis, and are folded
+        // along it by the provided angle.
+        let fold = |angle: f64| {
+            let (sin, cos) = angle.to_radians().sin_cos();
+            let mut mesh = Mesh::new();
+            mesh.push_triangle(
+                [[0., 0., 0.], [1., 0., 0.], [0., -1., 0.]],
+                Color::default(),
+            );
+            mesh.push_triangle(
+                [[0., 0., 0.], [0., cos, sin], [1., 0., 0.]],
+                Color::default(),
+            );
+            mesh
+        };
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("fold.obj");
+        let num_normals = |mesh| -> anyhow::Result<usize> {
+            export_obj(&mesh, &path)?;
+            let obj = fs::read_to_string(&path)?;
+            Ok(obj.lines().filter(|line| line.starts_with("vn ")).count())
+        };
+
+        // At a shallow angle, the vertices on the shared edge get an averaged
+        // normal. The other two vertices keep the normals of their triangles.
+        assert_eq!(num_normals(fold(10.))?, 3);
+
+        // At a sharp angle, the edge stays sharp.
+        assert_eq!(num_normals(fold(90.))?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ascii_stl() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("two triangles.stl");
+        export_stl(&two_colored_triangles(), &path, StlFormat::Ascii)?;
+
+        let stl = fs::read_to_string(&path)?;
+        let lines = stl.lines().map(str::trim).collect::<Vec<_>>();
+
+        assert_eq!(lines.first(), Some(&"solid two_triangles"));
+        assert_eq!(lines.last(), Some(&"endsolid two_triangles"));
+
+        let count = |prefix: &str| {
+            lines.iter().filter(|line| line.starts_with(prefix)).count()
+        };
+        assert_eq!(count("facet normal 0e0 0e0 1e0"), 2);
+        assert_eq!(count("outer loop"), 2);
+        assert_eq!(count("vertex "), 6);
+        assert_eq!(count("endloop"), 2);
+        assert_eq!(count("endfacet"), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_stl_colors() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("square.stl");
+
+        let attributes = |format| -> anyhow::Result<Vec<u16>> {
+            export_stl(&two_colored_triangles(), &path, format)?;
+            let stl = fs::read(&path)?;
+
+            let num_triangles =
+                u32::from_le_bytes(stl[80..84].try_into()?) as usize;
+            assert_eq!(stl.len(), 84 + num_triangles * 50);
+
+            // Each triangle consists of its normal and its vertices, 12 values
+            // of 4 bytes, followed by its 2 attribute bytes.
+            stl[84..]
+                .chunks(50)
+                .map(|triangle| {
+                    Ok(u16::from_le_bytes(triangle[48..50].try_into()?))
+                })
+                .collect()
+        };
+
+        assert_eq!(attributes(StlFormat::Binary)?, [0, 0]);
+
+        // VisCAM colors have 5 bits per channel, red in the most significant
+        // ones, and a set bit to mark them as valid.
+        let red = 0x8000 | 0b11111 << 10;
+        let blue = 0x8000 | 0b11111;
+        assert_eq!(attributes(StlFormat::BinaryWithColors)?, [red, blue]);
+
+        Ok(())
+    }
+
+    /// A square made of two triangles, each with its own color
+    pub fn two_colored_triangles() -> Mesh<Point<3>> {
+        let [a, b, c, d] =
+            [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]];
+
+        let mut mesh = Mesh::new();
+        mesh.push_triangle([a, b, c], Color([255, 0, 0, 255]));
+        mesh.push_triangle([a, c, d], Color([0, 0, 255, 128]));
+        mesh
+    }
 }
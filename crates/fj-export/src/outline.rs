@@ -0,0 +1,273 @@
+//! # 2D outlines of sketches and faces
+//!
+//! See [`Outline`].
+
+use fj_core::{
+    geometry::{GlobalPath, SurfacePath},
+    objects::{Cycle, Face, Handedness, Sketch},
+};
+use fj_math::{Aabb, Point, Scalar, Vector};
+
+/// The closed cycles of a sketch or planar face, in 2D
+///
+/// Unlike the triangle meshes that other formats are exported from, an outline
+/// keeps lines and arcs exact.
+pub struct Outline {
+    pub cycles: Vec<Vec<Segment>>,
+}
+
+impl Outline {
+    /// Create the outline of a sketch, in the coordinates of the sketch
+    pub fn from_sketch(sketch: &Sketch) -> Self {
+        let cycles = sketch
+            .regions()
+            .iter()
+            .flat_map(|region| region.all_cycles())
+            .map(|cycle| segments(cycle, |point| point, |vector| vector))
+            .collect();
+
+        Self { cycles }
+    }
+
+    /// Create the outline of a planar face, as seen from its front side
+    ///
+    /// The outline is defined in a coordinate system, whose origin is the
+    /// origin of the face's surface, and whose x-axis points along the
+    /// surface's u-axis. Unlike the surface's own coordinate system, it is
+    /// orthonormal, so circles stay circles.
+    ///
+    /// Returns `None`, if the face is not planar.
+    pub fn from_face(face: &Face) -> Option<Self> {
+        let surface = face.surface().geometry();
+        let GlobalPath::Line(u) = surface.u else {
+            return None;
+        };
+
+        let x = u.direction().normalize();
+        let y = {
+            let y = u.direction().cross(&surface.v).cross(&u.direction());
+            let y = y.normalize();
+
+            // The front side of the face is the side from which its exterior
+            // cycle appears counter-clockwise. Looking at it from there, the
+            // surface's coordinate system might be left-handed.
+            match face.coord_handedness() {
+                Handedness::RightHanded => y,
+                Handedness::LeftHanded => -y,
+            }
+        };
+
+        let to_outline = |vector: Vector<2>| {
+            let vector = surface.vector_from_surface_coords(vector);
+            Vector::from([vector.dot(&x), vector.dot(&y)])
+        };
+
+        let cycles = face
+            .region()
+            .all_cycles()
+            .map(|cycle| {
+                segments(
+                    cycle,
+                    |point| Point::origin() + to_outline(point.coords),
+                    to_outline,
+                )
+            })
+            .collect();
+
+        Some(Self { cycles })
+    }
+
+    /// Compute the bounding box of the outline
+    ///
+    /// Returns `None`, if the outline is empty.
+    pub fn aabb(&self) -> Option<Aabb<2>> {
+        let points = self
+            .cycles
+            .iter()
+            .flatten()
+            .flat_map(|segment| match segment {
+                Segment::Line { start, end } => vec![*start, *end],
+                Segment::Arc(arc) => {
+                    let mut points = arc.extremes();
+                    points.extend([arc.start_point(), arc.end_point()]);
+                    points
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if points.is_empty() {
+            return None;
+        }
+
+        Some(Aabb::<2>::from_points(points))
+    }
+}
+
+/// A segment of a cycle in an [`Outline`]
+pub enum Segment {
+    /// A straight line
+    Line { start: Point<2>, end: Point<2> },
+
+    /// An elliptical or circular arc
+    Arc(Arc),
+}
+
+/// An arc of an ellipse, which might be a circle
+///
+/// The points on the ellipse are `center + major * cos(t) + minor * sin(t)`.
+/// The arc runs from the parameter `t = start` to `t = end`, counter-clockwise
+/// if `end` is larger than `start`.
+pub struct Arc {
+    pub center: Point<2>,
+
+    /// The semi-major axis, which is where `t = 0`
+    pub major: Vector<2>,
+
+    /// The semi-minor axis, a quarter turn counter-clockwise from `major`
+    pub minor: Vector<2>,
+
+    pub start: Scalar,
+    pub end: Scalar,
+}
+
+impl Arc {
+    /// Create an arc from conjugate semi-diameters of an ellipse
+    ///
+    /// The points on the ellipse are `center + a * cos(t) + b * sin(t)`, which
+    /// is the form in which circles in surface coordinates end up, once they
+    /// are converted into outline coordinates.
+    fn from_conjugate_diameters(
+        center: Point<2>,
+        a: Vector<2>,
+        b: Vector<2>,
+        [start, end]: [Scalar; 2],
+    ) -> Self {
+        // The axes of the ellipse are at the parameter where the distance from
+        // the center is at an extremum.
+        let angle = (a.dot(&b) * 2.).atan2(a.dot(&a) - b.dot(&b)) / 2.;
+        let (sin, cos) = angle.sin_cos();
+
+        let mut major = a * cos + b * sin;
+        let mut minor = b * cos - a * sin;
+        let mut offset = angle;
+
+        if minor.magnitude() > major.magnitude() {
+            (major, minor) = (minor, -major);
+            offset += Scalar::PI / 2.;
+        }
+
+        let mut sign = Scalar::ONE;
+        if major.cross2d(&minor) < Scalar::ZERO {
+            minor = -minor;
+            sign = -sign;
+        }
+
+        let [mut start, mut end] = [start, end].map(|t| (t - offset) * sign);
+
+        let turns = (start / Scalar::TAU).floor();
+        start -= turns * Scalar::TAU;
+        end -= turns * Scalar::TAU;
+
+        Self {
+            center,
+            major,
+            minor,
+            start,
+            end,
+        }
+    }
+
+    /// Compute the point on the ellipse at the provided parameter
+    pub fn point(&self, t: Scalar) -> Point<2> {
+        let (sin, cos) = t.sin_cos();
+        self.center + self.major * cos + self.minor * sin
+    }
+
+    pub fn start_point(&self) -> Point<2> {
+        self.point(self.start)
+    }
+
+    pub fn end_point(&self) -> Point<2> {
+        self.point(self.end)
+    }
+
+    /// Determine whether the arc runs counter-clockwise
+    pub fn is_ccw(&self) -> bool {
+        self.end > self.start
+    }
+
+    /// Determine whether the arc is a full ellipse
+    pub fn is_full(&self) -> bool {
+        (self.end - self.start).abs() >= Scalar::TAU - Scalar::from_f64(1e-9)
+    }
+
+    /// Determine whether the ellipse is a circle
+    pub fn is_circle(&self) -> bool {
+        let [major, minor] = [self.major, self.minor].map(|v| v.magnitude());
+        major - minor <= major * 1e-9
+    }
+
+    /// Compute the angle of the major axis, relative to the x-axis
+    pub fn rotation(&self) -> Scalar {
+        self.major.v.atan2(self.major.u)
+    }
+
+    /// The points of the arc that are furthest out along the x and y axes
+    fn extremes(&self) -> Vec<Point<2>> {
+        let [low, high] = if self.is_ccw() {
+            [self.start, self.end]
+        } else {
+            [self.end, self.start]
+        };
+
+        [0, 1]
+            .into_iter()
+            .flat_map(|i| {
+                // The derivative of the coordinate is zero here, and half a
+                // turn further.
+                let [a, b] =
+                    [self.major, self.minor].map(|axis| axis.components[i]);
+                let t = b.atan2(a);
+                [t, t + Scalar::PI]
+            })
+            .filter_map(|t| {
+                // Find the first occurrence of the parameter, that is not
+                // before the arc starts.
+                let turns = ((low - t) / Scalar::TAU).ceil();
+                let t = t + turns * Scalar::TAU;
+                (t <= high).then(|| self.point(t))
+            })
+            .collect()
+    }
+}
+
+fn segments(
+    cycle: &Cycle,
+    point: impl Fn(Point<2>) -> Point<2>,
+    vector: impl Fn(Vector<2>) -> Vector<2>,
+) -> Vec<Segment> {
+    cycle
+        .half_edges()
+        .iter()
+        .map(|half_edge| {
+            let [start, end] = half_edge.boundary().inner;
+
+            match half_edge.path() {
+                SurfacePath::Line(_) => {
+                    let [start, end] = [start, end].map(|t| {
+                        point(half_edge.path().point_from_path_coords(t))
+                    });
+                    Segment::Line { start, end }
+                }
+                SurfacePath::Circle(circle) => {
+                    Segment::Arc(Arc::from_conjugate_diameters(
+                        point(circle.center()),
+                        vector(circle.a()),
+                        vector(circle.b()),
+                        [start.t, end.t],
+                    ))
+                }
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,679 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Write},
+};
+
+use fj_core::{
+    geometry::{CurveBoundary, GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{Face, HalfEdge, Handedness, Solid},
+    storage::{Handle, ObjectId},
+};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::Error;
+
+/// Write a solid as an ISO 10303-21 (STEP) file, using the AP214 schema
+///
+/// The solid is written as a boundary representation. Faces keep their planes
+/// and cylinders, and edges keep their lines and circles. Each shell becomes a
+/// `MANIFOLD_SOLID_BREP`. Model units are written as millimeters.
+pub fn write_step(
+    solid: &Solid,
+    name: &str,
+    mut writer: impl Write,
+) -> Result<(), Error> {
+    let mut step = StepWriter::default();
+
+    let mut items = Vec::new();
+    for shell in solid.shells() {
+        let mut faces = Vec::new();
+        for face in shell.faces() {
+            faces.push(step.face(face)?);
+        }
+
+        let faces = list(faces);
+        let shell = step.add(format!("CLOSED_SHELL('',{faces})"));
+        items.push(step.add(format!("MANIFOLD_SOLID_BREP('',{shell})")));
+    }
+
+    let origin =
+        step.placement(Point::origin(), Vector::unit_z(), Vector::unit_x());
+    items.push(origin);
+
+    let context = step.context();
+    let representation = step.add(format!(
+        "ADVANCED_BREP_SHAPE_REPRESENTATION({},{},{context})",
+        string(name),
+        list(items),
+    ));
+    step.product(name, representation);
+
+    step.write(name, &mut writer)?;
+
+    Ok(())
+}
+
+/// Collects the entities of a STEP file
+#[derive(Default)]
+struct StepWriter {
+    entities: Vec<String>,
+
+    /// The `VERTEX_POINT` of each vertex
+    vertices: BTreeMap<ObjectId, Id>,
+
+    /// The `EDGE_CURVE` of each pair of sibling half-edges
+    ///
+    /// Siblings share a curve and their bounding vertices, which identifies
+    /// them. The edge curve is stored with its start vertex, from which the
+    /// orientation of the half-edges that refer to it can be determined.
+    edges: BTreeMap<(ObjectId, [ObjectId; 2]), (Id, ObjectId)>,
+}
+
+impl StepWriter {
+    fn add(&mut self, entity: String) -> Id {
+        self.entities.push(entity);
+        Id(self.entities.len())
+    }
+
+    fn point(&mut self, point: Point<3>) -> Id {
+        let [x, y, z] = point.coords.components.map(real);
+        self.add(format!("CARTESIAN_POINT('',({x},{y},{z}))"))
+    }
+
+    fn direction(&mut self, direction: Vector<3>) -> Id {
+        let [x, y, z] = direction.normalize().components.map(real);
+        self.add(format!("DIRECTION('',({x},{y},{z}))"))
+    }
+
+    fn placement(
+        &mut self,
+        origin: Point<3>,
+        axis: Vector<3>,
+        ref_direction: Vector<3>,
+    ) -> Id {
+        let origin = self.point(origin);
+        let axis = self.direction(axis);
+        let ref_direction = self.direction(ref_direction);
+        self.add(format!(
+            "AXIS2_PLACEMENT_3D('',{origin},{axis},{ref_direction})"
+        ))
+    }
+
+    fn circle(&mut self, center: Point<3>, a: Vector<3>, b: Vector<3>) -> Id {
+        let placement = self.placement(center, a.cross(&b), a);
+        let radius = real(a.magnitude());
+        self.add(format!("CIRCLE('',{placement},{radius})"))
+    }
+
+    fn face(&mut self, face: &Face) -> Result<Id, Error> {
+        let surface = face.surface().geometry();
+
+        // The front side of a face is where its exterior cycle appears
+        // counter-clockwise. Depending on the side, the surface's coordinate
+        // system can appear left-handed.
+        let is_right_handed =
+            face.coord_handedness() == Handedness::RightHanded;
+
+        let (surface_id, same_sense) = match surface.u {
+            GlobalPath::Line(u) => {
+                let normal = u.direction().cross(&surface.v);
+                let normal = if is_right_handed { normal } else { -normal };
+                let placement =
+                    self.placement(u.origin(), normal, u.direction());
+                (self.add(format!("PLANE('',{placement})")), true)
+            }
+            GlobalPath::Circle(u) => {
+                let axis = u.a().cross(&u.b());
+
+                // The surface normal points along the cross product of the
+                // surface's u and v directions. At `u = 0`, that's `b` and `v`.
+                let normal_points_out =
+                    u.b().cross(&surface.v).dot(&u.a()) > Scalar::ZERO;
+
+                if is_parallel(axis, surface.v) {
+                    let placement = self.placement(u.center(), axis, u.a());
+                    let radius = real(u.radius());
+                    let surface = self.add(format!(
+                        "CYLINDRICAL_SURFACE('',{placement},{radius})"
+                    ));
+
+                    // Cylindrical surfaces always face outward.
+                    (surface, normal_points_out == is_right_handed)
+                } else {
+                    let circle = self.circle(u.center(), u.a(), u.b());
+                    let direction = self.direction(surface.v);
+                    let magnitude = real(surface.v.magnitude());
+                    let vector =
+                        self.add(format!("VECTOR('',{direction},{magnitude})"));
+                    let surface = self.add(format!(
+                        "SURFACE_OF_LINEAR_EXTRUSION('',{circle},{vector})"
+                    ));
+
+                    (surface, is_right_handed)
+                }
+            }
+        };
+
+        let mut bounds = Vec::new();
+        for cycle in face.region().all_cycles() {
+            let mut oriented_edges = Vec::new();
+            for (half_edge, next) in cycle.half_edges().pairs() {
+                let (edge, orientation) =
+                    self.edge(half_edge, next, &surface)?;
+                oriented_edges.push(self.add(format!(
+                    "ORIENTED_EDGE('',*,*,{edge},{})",
+                    logical(orientation)
+                )));
+            }
+
+            let edge_loop =
+                self.add(format!("EDGE_LOOP('',{})", list(oriented_edges)));
+
+            let kind = if cycle == face.region().exterior() {
+                "FACE_OUTER_BOUND"
+            } else {
+                "FACE_BOUND"
+            };
+            bounds.push(self.add(format!("{kind}('',{edge_loop},.T.)")));
+        }
+
+        Ok(self.add(format!(
+            "ADVANCED_FACE('',{},{surface_id},{})",
+            list(bounds),
+            logical(same_sense),
+        )))
+    }
+
+    /// Access the edge curve of a half-edge, creating it if necessary
+    ///
+    /// Also returns whether the half-edge has the same orientation as the edge
+    /// curve. Edge curves are created with the orientation of the first of the
+    /// sibling half-edges.
+    fn edge(
+        &mut self,
+        half_edge: &Handle<HalfEdge>,
+        next: &Handle<HalfEdge>,
+        surface: &SurfaceGeometry,
+    ) -> Result<(Id, bool), Error> {
+        let start = half_edge.start_vertex().id();
+        let end = next.start_vertex().id();
+
+        let mut vertices = [start, end];
+        vertices.sort();
+        let key = (half_edge.curve().id(), vertices);
+
+        if let Some(&(edge, edge_start)) = self.edges.get(&key) {
+            // Siblings are reversed relative to each other. If the edge starts
+            // and ends at the same vertex, the vertices can't tell them apart,
+            // but the first sibling has created the edge curve.
+            return Ok((edge, start == edge_start && start != end));
+        }
+
+        let geometry =
+            EdgeGeometry::new(half_edge.path(), half_edge.boundary(), surface)?;
+
+        let start_point = self.vertex(start, geometry.start());
+        let end_point = self.vertex(end, geometry.end());
+
+        let curve = match geometry {
+            EdgeGeometry::Line { start, end } => {
+                let point = self.point(start);
+                let direction = self.direction(end - start);
+                let magnitude = real((end - start).magnitude());
+                let vector =
+                    self.add(format!("VECTOR('',{direction},{magnitude})"));
+                self.add(format!("LINE('',{point},{vector})"))
+            }
+            EdgeGeometry::Arc { center, a, b, .. } => self.circle(center, a, b),
+        };
+
+        let edge = self.add(format!(
+            "EDGE_CURVE('',{start_point},{end_point},{curve},.T.)"
+        ));
+        self.edges.insert(key, (edge, start));
+
+        Ok((edge, true))
+    }
+
+    fn vertex(&mut self, vertex: ObjectId, position: Point<3>) -> Id {
+        if let Some(&id) = self.vertices.get(&vertex) {
+            return id;
+        }
+
+        let point = self.point(position);
+        let id = self.add(format!("VERTEX_POINT('',{point})"));
+        self.vertices.insert(vertex, id);
+
+        id
+    }
+
+    fn context(&mut self) -> Id {
+        let length = self.add(
+            "(LENGTH_UNIT()NAMED_UNIT(*)SI_UNIT(.MILLI.,.METRE.))".to_string(),
+        );
+        let angle = self.add(
+            "(NAMED_UNIT(*)PLANE_ANGLE_UNIT()SI_UNIT($,.RADIAN.))".to_string(),
+        );
+        let solid_angle = self.add(
+            "(NAMED_UNIT(*)SI_UNIT($,.STERADIAN.)SOLID_ANGLE_UNIT())"
+                .to_string(),
+        );
+        let uncertainty = self.add(format!(
+            "UNCERTAINTY_MEASURE_WITH_UNIT(LENGTH_MEASURE(1.E-07),{length},\
+            'distance_accuracy_value','confusion accuracy')"
+        ));
+
+        self.add(format!(
+            "(GEOMETRIC_REPRESENTATION_CONTEXT(3)\
+            GLOBAL_UNCERTAINTY_ASSIGNED_CONTEXT(({uncertainty}))\
+            GLOBAL_UNIT_ASSIGNED_CONTEXT(({length},{angle},{solid_angle}))\
+            REPRESENTATION_CONTEXT('',''))"
+        ))
+    }
+
+    /// Add the product structure, that makes the shape a part
+    fn product(&mut self, name: &str, representation: Id) {
+        let name = string(name);
+
+        let application = self.add(
+            "APPLICATION_CONTEXT('core data for automotive mechanical design \
+            processes')"
+                .to_string(),
+        );
+        self.add(format!(
+            "APPLICATION_PROTOCOL_DEFINITION('international standard',\
+            'automotive_design',2000,{application})"
+        ));
+        let product_context =
+            self.add(format!("PRODUCT_CONTEXT('',{application},'mechanical')"));
+        let product =
+            self.add(format!("PRODUCT({name},{name},'',({product_context}))"));
+        self.add(format!(
+            "PRODUCT_RELATED_PRODUCT_CATEGORY('part',$,({product}))"
+        ));
+        let formation =
+            self.add(format!("PRODUCT_DEFINITION_FORMATION('','',{product})"));
+        let definition_context = self.add(format!(
+            "PRODUCT_DEFINITION_CONTEXT('part definition',{application},\
+            'design')"
+        ));
+        let definition = self.add(format!(
+            "PRODUCT_DEFINITION('design','',{formation},{definition_context})"
+        ));
+        let shape =
+            self.add(format!("PRODUCT_DEFINITION_SHAPE('','',{definition})"));
+        self.add(format!(
+            "SHAPE_DEFINITION_REPRESENTATION({shape},{representation})"
+        ));
+    }
+
+    fn write(&self, name: &str, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "ISO-10303-21;")?;
+        writeln!(writer, "HEADER;")?;
+        writeln!(writer, "FILE_DESCRIPTION((''),'2;1');")?;
+        writeln!(
+            writer,
+            "FILE_NAME({},'',(''),(''),'Fornjot','Fornjot','');",
+            string(name)
+        )?;
+        writeln!(
+            writer,
+            "FILE_SCHEMA(('AUTOMOTIVE_DESIGN {{ 1 0 10303 214 1 1 1 1 }}'));"
+        )?;
+        writeln!(writer, "ENDSEC;")?;
+        writeln!(writer, "DATA;")?;
+        for (i, entity) in self.entities.iter().enumerate() {
+            writeln!(writer, "{}={entity};", Id(i + 1))?;
+        }
+        writeln!(writer, "ENDSEC;")?;
+        writeln!(writer, "END-ISO-10303-21;")?;
+
+        Ok(())
+    }
+}
+
+/// The geometry of an edge, in model coordinates
+enum EdgeGeometry {
+    Line {
+        start: Point<3>,
+        end: Point<3>,
+    },
+    /// An arc, that runs from `start` to `end` in the direction from `a` to `b`
+    Arc {
+        center: Point<3>,
+        a: Vector<3>,
+        b: Vector<3>,
+        start: Scalar,
+        end: Scalar,
+    },
+}
+
+impl EdgeGeometry {
+    fn new(
+        path: SurfacePath,
+        boundary: CurveBoundary<Point<1>>,
+        surface: &SurfaceGeometry,
+    ) -> Result<Self, Error> {
+        let [start, end] = boundary.inner;
+
+        match (surface.u, path) {
+            (GlobalPath::Line(_), SurfacePath::Line(_)) => {
+                let [start, end] = [start, end].map(|point| {
+                    surface.point_from_surface_coords(
+                        path.point_from_path_coords(point),
+                    )
+                });
+                Ok(Self::Line { start, end })
+            }
+            (GlobalPath::Line(_), SurfacePath::Circle(circle)) => {
+                let center = surface.point_from_surface_coords(circle.center());
+                let [a, b] = [circle.a(), circle.b()]
+                    .map(|vector| surface.vector_from_surface_coords(vector));
+
+                let is_circle = (a.magnitude() - b.magnitude()).abs()
+                    < Scalar::from_f64(1e-9) * a.magnitude()
+                    && a.dot(&b).abs() < Scalar::from_f64(1e-9) * a.dot(&a);
+                if !is_circle {
+                    return Err(Error::UnsupportedGeometry(
+                        "elliptical edge".to_string(),
+                    ));
+                }
+
+                Ok(Self::arc(center, a, b, [start.t, end.t]))
+            }
+            (GlobalPath::Circle(u), SurfacePath::Line(line)) => {
+                let direction = line.direction();
+                let [start, end] = [start, end]
+                    .map(|point| line.point_from_line_coords(point));
+
+                if is_zero(direction.v, direction.magnitude()) {
+                    // The edge runs around the cylinder.
+                    let center = u.center() + surface.v * start.v;
+                    Ok(Self::arc(center, u.a(), u.b(), [start.u, end.u]))
+                } else if is_zero(direction.u, direction.magnitude()) {
+                    let [start, end] = [start, end]
+                        .map(|point| surface.point_from_surface_coords(point));
+                    Ok(Self::Line { start, end })
+                } else {
+                    Err(Error::UnsupportedGeometry("helical edge".to_string()))
+                }
+            }
+            (GlobalPath::Circle(_), SurfacePath::Circle(_)) => {
+                Err(Error::UnsupportedGeometry(
+                    "circle on curved surface".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Create an arc, reversing its circle, if it runs in negative direction
+    fn arc(
+        center: Point<3>,
+        a: Vector<3>,
+        b: Vector<3>,
+        [start, end]: [Scalar; 2],
+    ) -> Self {
+        let (b, [start, end]) = if end < start {
+            (-b, [-start, -end])
+        } else {
+            (b, [start, end])
+        };
+
+        Self::Arc {
+            center,
+            a,
+            b,
+            start,
+            end,
+        }
+    }
+
+    fn start(&self) -> Point<3> {
+        match self {
+            Self::Line { start, .. } => *start,
+            Self::Arc {
+                center,
+                a,
+                b,
+                start,
+                ..
+            } => arc_point(*center, *a, *b, *start),
+        }
+    }
+
+    fn end(&self) -> Point<3> {
+        match self {
+            Self::Line { end, .. } => *end,
+            Self::Arc {
+                center, a, b, end, ..
+            } => arc_point(*center, *a, *b, *end),
+        }
+    }
+}
+
+fn arc_point(
+    center: Point<3>,
+    a: Vector<3>,
+    b: Vector<3>,
+    t: Scalar,
+) -> Point<3> {
+    let (sin, cos) = t.sin_cos();
+    center + a * cos + b * sin
+}
+
+fn is_zero(value: Scalar, magnitude: Scalar) -> bool {
+    value.abs() <= magnitude * Scalar::from_f64(1e-12)
+}
+
+fn is_parallel(a: Vector<3>, b: Vector<3>) -> bool {
+    is_zero(a.cross(&b).magnitude(), a.magnitude() * b.magnitude())
+}
+
+/// A reference to an entity
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+struct Id(usize);
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+fn list(ids: impl IntoIterator<Item = Id>) -> String {
+    let ids = ids.into_iter().map(|id| id.to_string()).collect::<Vec<_>>();
+    format!("({})", ids.join(","))
+}
+
+/// Format a real number, which always contains a decimal point in STEP
+fn real(value: Scalar) -> String {
+    let value = value.into_f64() + 0.;
+    let value = value.to_string();
+
+    if value.contains('.') {
+        value
+    } else {
+        format!("{value}.")
+    }
+}
+
+fn logical(value: bool) -> &'static str {
+    if value {
+        ".T."
+    } else {
+        ".F."
+    }
+}
+
+fn string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_core::{
+        algorithms::mass_properties::MassProperties,
+        objects::{Cycle, Region, Sketch, Solid, Surface},
+        operations::{
+            build::{BuildCycle, BuildRegion, BuildSketch, BuildSurface},
+            insert::Insert,
+            sweep::SweepSketch,
+            update::UpdateSketch,
+        },
+        validate::Validate,
+        Instance,
+    };
+    use fj_import::import_step;
+    use fj_math::Scalar;
+
+    use super::write_step;
+
+    #[test]
+    fn cuboid_round_trip() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let cuboid = sweep(
+            Region::polygon(
+                [[0., 0.], [1., 0.], [1., 2.], [0., 2.]],
+                &mut core,
+            ),
+            3.,
+            &mut core,
+        );
+        let step = step(&cuboid)?;
+
+        assert_eq!(entities(&step, "CLOSED_SHELL").len(), 1);
+        assert_eq!(entities(&step, "PLANE").len(), 6);
+        assert_eq!(entities(&step, "EDGE_CURVE").len(), 12);
+        assert_eq!(entities(&step, "VERTEX_POINT").len(), 8);
+
+        // The faces of the cuboid are written with the orientation of their
+        // planes, which have their normals pointing outward.
+        let faces = entities(&step, "ADVANCED_FACE");
+        assert_eq!(faces.len(), 6);
+        assert!(faces.iter().all(|face| face.ends_with(",.T.)")));
+
+        // Each edge curve is used by two faces, in opposite directions.
+        let oriented_edges = entities(&step, "ORIENTED_EDGE");
+        assert_eq!(oriented_edges.len(), 24);
+        for orientation in [".T.", ".F."] {
+            assert_eq!(
+                oriented_edges
+                    .iter()
+                    .filter(|edge| edge.ends_with(&format!(",{orientation})")))
+                    .count(),
+                12
+            );
+        }
+
+        let imported = round_trip(&cuboid, &mut core)?;
+
+        assert_eq!(num_faces(&imported), 6);
+        assert_volume(&imported, 6.);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cylinder_round_trip() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let cylinder =
+            sweep(Region::circle([0., 0.], 1., &mut core), 2., &mut core);
+        let imported = round_trip(&cylinder, &mut core)?;
+
+        assert_eq!(num_faces(&imported), num_faces(&cylinder));
+        assert_volume(&imported, 2. * PI);
+
+        Ok(())
+    }
+
+    #[test]
+    fn arc_on_tilted_plane_round_trip() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        // A plane that isn't parallel to any of the coordinate planes, with
+        // orthonormal axes, so circles in surface coordinates stay circles.
+        let (surface, _) = Surface::plane_from_points([
+            [0., 0., 0.],
+            [2. / 3., 2. / 3., 1. / 3.],
+            [2. / 3., -1. / 3., -2. / 3.],
+        ]);
+        let surface = surface.insert(&mut core);
+
+        // Half of a disk, with a radius of 1.
+        let exterior = Cycle::builder([1., 0.])
+            .arc_to([-1., 0.], 1.)
+            .close()
+            .build(&mut core)
+            .insert(&mut core);
+        let region = Region::new(exterior, [], None).insert(&mut core);
+
+        // Sweep along the normal of the plane.
+        let normal = [-1. / 3., 2. / 3., -2. / 3.].map(|c| c * 2.);
+        let solid = Sketch::empty()
+            .add_regions([region], &mut core)
+            .sweep_sketch(surface, normal, &mut core);
+
+        let step = step(&solid)?;
+
+        // Both arcs are circles on the tilted planes, and the surface between
+        // them is a cylinder.
+        assert_eq!(entities(&step, "CIRCLE").len(), 2);
+        assert_eq!(entities(&step, "CYLINDRICAL_SURFACE").len(), 1);
+        assert!(entities(&step, "SURFACE_OF_LINEAR_EXTRUSION").is_empty());
+
+        let imported = round_trip(&solid, &mut core)?;
+
+        assert_eq!(num_faces(&imported), num_faces(&solid));
+        assert_volume(&imported, PI);
+
+        Ok(())
+    }
+
+    fn sweep(region: Region, height: f64, core: &mut Instance) -> Solid {
+        let surface = core.services.objects.surfaces.xy_plane();
+        Sketch::empty().add_regions([region], core).sweep_sketch(
+            surface,
+            [0., 0., height],
+            core,
+        )
+    }
+
+    fn step(solid: &Solid) -> anyhow::Result<String> {
+        let mut step = Vec::new();
+        write_step(solid, "test", &mut step)?;
+        Ok(String::from_utf8(step)?)
+    }
+
+    /// Find the entities of the provided type, without their ids
+    fn entities<'r>(step: &'r str, kind: &str) -> Vec<&'r str> {
+        step.lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(_, entity)| entity.trim_end_matches(';'))
+            .filter(|entity| entity.starts_with(&format!("{kind}(")))
+            .collect()
+    }
+
+    /// Write the solid as STEP, then import it again
+    fn round_trip(solid: &Solid, core: &mut Instance) -> anyhow::Result<Solid> {
+        let imported = import_step(&step(solid)?, core)?;
+        imported.validate_and_return_first_error()?;
+
+        Ok(imported)
+    }
+
+    fn num_faces(solid: &Solid) -> usize {
+        solid.shells().iter().map(|shell| shell.faces().len()).sum()
+    }
+
+    fn assert_volume(solid: &Solid, expected: f64) {
+        let volume = solid.mass_properties().volume;
+        assert!(
+            (volume - Scalar::from(expected)).abs() < Scalar::from(1e-6),
+            "Unexpected volume: {volume} (expected {expected})"
+        );
+    }
+}
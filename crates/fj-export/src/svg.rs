@@ -0,0 +1,152 @@
+use std::io::{self, Write};
+
+use fj_math::{Point, Scalar};
+
+use crate::outline::{Arc, Outline, Segment};
+
+/// Write an outline as an SVG file
+///
+/// Each cycle becomes a path, made up of straight lines and elliptical arcs,
+/// unless it is a full circle, which becomes a circle.
+/// The model's y-axis points up, so the outline is flipped to match SVG's
+/// y-axis, which points down. Model units are written as millimeters.
+pub fn write_svg(outline: &Outline, mut writer: impl Write) -> io::Result<()> {
+    let [min, max] = outline
+        .aabb()
+        .map(|aabb| [aabb.min, aabb.max])
+        .unwrap_or_default();
+    let size = max - min;
+
+    // Convert model coordinates into SVG coordinates.
+    let point = |point: Point<2>| {
+        let x = point.u - min.u;
+        let y = max.v - point.v;
+        format!("{x},{y}")
+    };
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}mm" height="{h}mm" viewBox="0 0 {w} {h}">"#,
+        w = size.u,
+        h = size.v,
+    )?;
+
+    for cycle in &outline.cycles {
+        let Some(first) = cycle.first() else {
+            continue;
+        };
+
+        if let [Segment::Arc(arc)] = cycle.as_slice() {
+            if arc.is_full() && arc.is_circle() {
+                let [cx, cy] = [arc.center.u - min.u, max.v - arc.center.v];
+                let r = arc.major.magnitude();
+
+                writeln!(
+                    writer,
+                    r#"  <circle cx="{cx}" cy="{cy}" r="{r}" fill="none" stroke="black" stroke-width="0.1"/>"#,
+                )?;
+                continue;
+            }
+        }
+
+        let start = match first {
+            Segment::Line { start, .. } => *start,
+            Segment::Arc(arc) => arc.start_point(),
+        };
+        let mut path = format!("M {}", point(start));
+
+        for segment in cycle {
+            match segment {
+                Segment::Line { end, .. } => {
+                    path.push_str(&format!(" L {}", point(*end)));
+                }
+                Segment::Arc(arc) => {
+                    // An arc that ends where it starts is ambiguous in SVG, so
+                    // full ellipses are split in half.
+                    let ends = if arc.is_full() {
+                        vec![(arc.start + arc.end) / 2., arc.end]
+                    } else {
+                        vec![arc.end]
+                    };
+
+                    let mut start = arc.start;
+                    for end in ends {
+                        path.push_str(&format!(
+                            " A {}",
+                            arc_command(arc, start, end, point(arc.point(end)))
+                        ));
+                        start = end;
+                    }
+                }
+            }
+        }
+
+        path.push_str(" Z");
+
+        writeln!(
+            writer,
+            r#"  <path d="{path}" fill="none" stroke="black" stroke-width="0.1"/>"#,
+        )?;
+    }
+
+    writeln!(writer, "</svg>")?;
+
+    Ok(())
+}
+
+/// Create the parameters of an SVG arc command
+fn arc_command(arc: &Arc, start: Scalar, end: Scalar, to: String) -> String {
+    let rx = arc.major.magnitude();
+    let ry = arc.minor.magnitude();
+
+    // SVG's y-axis points down, which inverts the direction of angles.
+    // Adding zero avoids writing negative zero.
+    let rotation = -arc.rotation().into_f64().to_degrees() + 0.;
+    let large_arc = u8::from((end - start).abs() > Scalar::PI);
+    let sweep = u8::from(!arc.is_ccw());
+
+    format!("{rx} {ry} {rotation} {large_arc} {sweep} {to}")
+}
+
+#[cfg(test)]
+mod tests {
+    use fj_core::{
+        objects::{Region, Sketch},
+        operations::{
+            build::{BuildRegion, BuildSketch},
+            update::UpdateSketch,
+        },
+        Instance,
+    };
+
+    use crate::outline::Outline;
+
+    use super::write_svg;
+
+    #[test]
+    fn circle() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let sketch = Sketch::empty().add_regions(
+            [
+                Region::circle([0., 0.], 2., &mut core),
+                Region::polygon(
+                    [[3., -1.], [5., -1.], [5., 1.], [3., 1.]],
+                    &mut core,
+                ),
+            ],
+            &mut core,
+        );
+
+        let mut svg = Vec::new();
+        write_svg(&Outline::from_sketch(&sketch), &mut svg)?;
+        let svg = String::from_utf8(svg)?;
+
+        assert!(svg.contains(r#"<circle cx="2" cy="2" r="2""#), "{svg}");
+        assert_eq!(svg.matches("<path").count(), 1);
+        assert!(!svg.contains(" A "));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+//! # 3MF export
+//!
+//! See [`write_3mf`].
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{Seek, Write},
+};
+
+use fj_interop::{Color, Mesh};
+use fj_math::Point;
+use zip::{write::FileOptions, ZipWriter};
+
+use crate::Error;
+
+/// Write a mesh as a 3MF package
+///
+/// Each color of the mesh becomes a base material, which the triangles of that
+/// color refer to. Slicers for multi-material printers can assign those to
+/// different filaments.
+pub fn write_3mf(
+    mesh: &Mesh<Point<3>>,
+    writer: impl Write + Seek,
+) -> Result<(), Error> {
+    let mut archive = ZipWriter::new(writer);
+
+    archive.start_file("[Content_Types].xml", FileOptions::default())?;
+    archive.write_all(CONTENT_TYPES.as_bytes())?;
+
+    archive.start_file("_rels/.rels", FileOptions::default())?;
+    archive.write_all(RELATIONSHIPS.as_bytes())?;
+
+    archive.start_file("3D/3dmodel.model", FileOptions::default())?;
+    archive.write_all(model(mesh).as_bytes())?;
+
+    archive.finish()?;
+
+    Ok(())
+}
+
+fn model(mesh: &Mesh<Point<3>>) -> String {
+    let mut materials = BTreeMap::new();
+    for triangle in mesh.triangles() {
+        let next_index = materials.len();
+        materials.entry(triangle.color).or_insert(next_index);
+    }
+
+    // Writing into a `String` can't fail, so all results are ignored below.
+    let mut xml = String::new();
+
+    xml.push_str(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <model unit=\"millimeter\" xml:lang=\"en-US\" \
+        xmlns=\"http://schemas.microsoft.com/3dmanufacturing/core/2015/02\">\n\
+        <resources>\n",
+    );
+
+    let mut materials_by_index = materials.iter().collect::<Vec<_>>();
+    materials_by_index.sort_by_key(|(_, index)| **index);
+
+    if !materials.is_empty() {
+        xml.push_str("<basematerials id=\"1\">\n");
+        for (Color([r, g, b, a]), index) in materials_by_index {
+            let _ = writeln!(
+                xml,
+                "<base name=\"Color {index}\" \
+                displaycolor=\"#{r:02X}{g:02X}{b:02X}{a:02X}\" />"
+            );
+        }
+        xml.push_str("</basematerials>\n");
+    }
+
+    xml.push_str("<object id=\"2\" type=\"model\"");
+    if !materials.is_empty() {
+        xml.push_str(" pid=\"1\" pindex=\"0\"");
+    }
+    xml.push_str(">\n<mesh>\n<vertices>\n");
+
+    for point in mesh.vertices() {
+        let [x, y, z] = point.coords.components.map(|s| s.into_f64());
+        let _ = writeln!(xml, "<vertex x=\"{x}\" y=\"{y}\" z=\"{z}\" />");
+    }
+
+    xml.push_str("</vertices>\n<triangles>\n");
+
+    let indices = mesh.indices().collect::<Vec<_>>();
+    for (vertices, triangle) in indices.chunks(3).zip(mesh.triangles()) {
+        let [v1, v2, v3] = [vertices[0], vertices[1], vertices[2]];
+        let material = materials[&triangle.color];
+        let _ = writeln!(
+            xml,
+            "<triangle v1=\"{v1}\" v2=\"{v2}\" v3=\"{v3}\" pid=\"1\" \
+            p1=\"{material}\" />"
+        );
+    }
+
+    xml.push_str(
+        "</triangles>\n</mesh>\n</object>\n</resources>\n\
+        <build>\n<item objectid=\"2\" />\n</build>\n</model>\n",
+    );
+
+    xml
+}
+
+const CONTENT_TYPES: &str = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">
+<Default Extension=\"rels\" \
+ContentType=\"application/vnd.openxmlformats-package.relationships+xml\" />
+<Default Extension=\"model\" \
+ContentType=\"application/vnd.ms-package.3dmanufacturing-3dmodel+xml\" />
+</Types>
+";
+
+const RELATIONSHIPS: &str = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<Relationships \
+xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">
+<Relationship Target=\"/3D/3dmodel.model\" Id=\"rel0\" \
+Type=\"http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel\" />
+</Relationships>
+";
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use zip::ZipArchive;
+
+    use crate::tests::two_colored_triangles;
+
+    use super::write_3mf;
+
+    #[test]
+    fn base_materials() -> anyhow::Result<()> {
+        let mut package = Cursor::new(Vec::new());
+        write_3mf(&two_colored_triangles(), &mut package)?;
+
+        let mut archive = ZipArchive::new(package)?;
+        let mut model = String::new();
+        archive
+            .by_name("3D/3dmodel.model")?
+            .read_to_string(&mut model)?;
+
+        assert_eq!(model.matches("<base ").count(), 2);
+        assert!(model.contains("displaycolor=\"#FF0000FF\""));
+        assert!(model.contains("displaycolor=\"#0000FF80\""));
+
+        assert_eq!(model.matches("<vertex ").count(), 4);
+        let triangles = model
+            .lines()
+            .filter(|line| line.starts_with("<triangle "))
+            .collect::<Vec<_>>();
+        assert_eq!(triangles.len(), 2);
+
+        // Each triangle refers to the base material of its own color.
+        for (triangle, material) in triangles.into_iter().zip([0, 1]) {
+            assert!(triangle.contains("pid=\"1\""));
+            assert!(triangle.contains(&format!("p1=\"{material}\"")));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use fj_core::{
+    objects::{Cycle, Sketch},
+    operations::build::{BuildCycle, BuildSketch},
+    Instance,
+};
+use fj_math::{Point, Scalar};
+
+/// A closed outline made up of lines and arcs
+///
+/// Importers collect the outlines they find in a file as contours, which are
+/// then built into a [`Sketch`] using [`build_sketch`].
+#[derive(Clone, Debug)]
+pub struct Contour {
+    start: Point<2>,
+    segments: Vec<Segment>,
+}
+
+impl Contour {
+    /// Start a contour at the provided point
+    pub fn new(start: Point<2>) -> Self {
+        Self {
+            start,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Access the point where the contour starts
+    pub fn start(&self) -> Point<2> {
+        self.start
+    }
+
+    /// Access the point where the last segment ends
+    pub fn current(&self) -> Point<2> {
+        self.segments
+            .last()
+            .map(|segment| segment.end)
+            .unwrap_or(self.start)
+    }
+
+    /// Add a line from the current point to `end`
+    ///
+    /// Lines that are shorter than the tolerance are skipped.
+    pub fn line_to(&mut self, end: Point<2>, tolerance: Scalar) {
+        if self.current().distance_to(&end) < tolerance {
+            return;
+        }
+
+        self.segments.push(Segment { through: None, end });
+    }
+
+    /// Add an arc from the current point through `through` to `end`
+    ///
+    /// If the three points are too close to a line for the arc to be
+    /// distinguishable from one, a line is added instead. If the arc ends where
+    /// it starts, it is skipped. Full circles need to be added as two arcs.
+    pub fn arc_through(
+        &mut self,
+        through: Point<2>,
+        end: Point<2>,
+        tolerance: Scalar,
+    ) {
+        let start = self.current();
+
+        let chord = end - start;
+        if chord.magnitude() < tolerance {
+            // Full circles can't be represented by a single arc. Callers must
+            // split them.
+            return;
+        }
+
+        let is_straight = (through - start).cross2d(&chord).abs()
+            / chord.magnitude()
+            < tolerance;
+        if is_straight {
+            self.line_to(end, tolerance);
+            return;
+        }
+
+        self.segments.push(Segment {
+            through: Some(through),
+            end,
+        });
+    }
+
+    /// Close the contour, connecting the current point to the start
+    ///
+    /// If the current point is close enough to the start already, the last
+    /// segment is adjusted to end exactly at the start.
+    pub fn close(&mut self, tolerance: Scalar) {
+        let start = self.start;
+
+        match self.segments.last_mut() {
+            Some(last) if last.end.distance_to(&start) < tolerance => {
+                last.end = start;
+            }
+            Some(_) => self.segments.push(Segment {
+                through: None,
+                end: start,
+            }),
+            None => {}
+        }
+    }
+
+    /// Build a cycle from the contour
+    ///
+    /// Returns `None`, if the contour doesn't enclose an area.
+    pub fn build(
+        mut self,
+        tolerance: Scalar,
+        core: &mut Instance,
+    ) -> Option<Cycle> {
+        self.close(tolerance);
+
+        let is_degenerate = match self.segments.as_slice() {
+            [] => true,
+            [segment] => segment.through.is_none(),
+            [a, b] => a.through.is_none() && b.through.is_none(),
+            _ => false,
+        };
+        if is_degenerate {
+            return None;
+        }
+
+        let mut builder = Cycle::builder(self.start);
+        for Segment { through, end } in self.segments {
+            builder = match through {
+                Some(through) => builder.arc_through(through, end),
+                None => builder.line_to(end),
+            };
+        }
+
+        let cycle = builder.build(core);
+        if cycle.signed_area().abs() < tolerance * tolerance {
+            return None;
+        }
+
+        Some(cycle)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    /// A point on the segment, if it is an arc
+    through: Option<Point<2>>,
+
+    end: Point<2>,
+}
+
+/// Build a sketch from contours
+///
+/// Contours can be nested within each other, to create holes. Contours that
+/// don't enclose an area are ignored.
+pub fn build_sketch(
+    contours: impl IntoIterator<Item = Contour>,
+    tolerance: Scalar,
+    core: &mut Instance,
+) -> Sketch {
+    let cycles = contours
+        .into_iter()
+        .filter_map(|contour| contour.build(tolerance, core))
+        .collect::<Vec<_>>();
+
+    Sketch::from_cycles(cycles, core)
+}
@@ -0,0 +1,497 @@
+//! # DXF import
+//!
+//! See [`import_dxf`].
+
+use fj_core::{algorithms::approx::Tolerance, objects::Sketch, Instance};
+use fj_math::{Point, Scalar, Vector};
+
+use crate::{
+    contour::{build_sketch, Contour},
+    Error,
+};
+
+/// Import the 2D entities of an ASCII DXF file into a [`Sketch`]
+///
+/// Supports `LINE`, `ARC`, `CIRCLE`, and `LWPOLYLINE` entities, including the
+/// arcs that polylines define using bulges. Other entities, like blocks and
+/// their references, are ignored. Only the x and y coordinates are used, and
+/// lines and arcs keep their exact shape.
+///
+/// Circles and closed polylines are closed contours already. All other
+/// entities are chained into closed contours, by connecting entities whose end
+/// points are closer to each other than `tolerance`. If that isn't possible,
+/// an error is returned. Contours that are nested within others become holes.
+///
+/// Coordinates are taken as they are, regardless of the drawing's units.
+pub fn import_dxf(
+    dxf: &str,
+    tolerance: impl Into<Tolerance>,
+    core: &mut Instance,
+) -> Result<Sketch, Error> {
+    let tolerance = tolerance.into().inner();
+
+    let mut contours = Vec::new();
+    let mut loose = Vec::new();
+
+    for entity in entities(dxf)? {
+        // Entities other than lines are defined in a coordinate system that
+        // depends on their extrusion direction. For planar drawings, that
+        // direction points along the z-axis, and the coordinate system is
+        // mirrored if it points down.
+        let is_mirrored = entity.get(230).unwrap_or(1.) < 0.;
+        let point = |[x, y]: [f64; 2]| {
+            let x = if is_mirrored { -x } else { x };
+            Point::from([x, y])
+        };
+
+        match entity.kind.as_str() {
+            "LINE" => {
+                let start = Point::from([entity.value(10)?, entity.value(20)?]);
+                let end = Point::from([entity.value(11)?, entity.value(21)?]);
+                loose.push(Piece::Line { start, end });
+            }
+            "ARC" => {
+                let center = [entity.value(10)?, entity.value(20)?];
+                let radius = entity.value(40)?;
+                let start_angle = entity.value(50)?;
+                let mut end_angle = entity.value(51)?;
+                if end_angle <= start_angle {
+                    end_angle += 360.;
+                }
+
+                let [start, through, end] =
+                    [start_angle, (start_angle + end_angle) / 2., end_angle]
+                        .map(|angle| {
+                            let (sin, cos) = angle.to_radians().sin_cos();
+                            point([
+                                center[0] + radius * cos,
+                                center[1] + radius * sin,
+                            ])
+                        });
+
+                loose.push(Piece::Arc {
+                    start,
+                    through,
+                    end,
+                });
+            }
+            "CIRCLE" => {
+                let [x, y] = [entity.value(10)?, entity.value(20)?];
+                let radius = entity.value(40)?;
+
+                let [right, top, left, bottom] = [
+                    [x + radius, y],
+                    [x, y + radius],
+                    [x - radius, y],
+                    [x, y - radius],
+                ]
+                .map(point);
+
+                let mut contour = Contour::new(right);
+                contour.arc_through(top, left, tolerance);
+                contour.arc_through(bottom, right, tolerance);
+                contours.push(contour);
+            }
+            "LWPOLYLINE" => {
+                let is_closed = entity.get(70).unwrap_or(0.) as i64 & 1 == 1;
+
+                // Each vertex has a bulge, which defines the segment from that
+                // vertex to the next.
+                let mut vertices: Vec<([f64; 2], f64)> = Vec::new();
+                for (code, value) in &entity.codes {
+                    let value = || parse_number(*code, value);
+                    match code {
+                        10 => vertices.push(([value()?, 0.], 0.)),
+                        20 | 42 => {
+                            let Some((position, bulge)) = vertices.last_mut()
+                            else {
+                                return Err(Error::Dxf(format!(
+                                    "group code {code} before first vertex \
+                                    of `LWPOLYLINE`"
+                                )));
+                            };
+                            if *code == 20 {
+                                position[1] = value()?;
+                            } else {
+                                *bulge = value()?;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                let num_segments = if is_closed {
+                    vertices.len()
+                } else {
+                    vertices.len().saturating_sub(1)
+                };
+                let pieces = (0..num_segments).map(|i| {
+                    let (start, bulge) = vertices[i];
+                    let (end, _) = vertices[(i + 1) % vertices.len()];
+                    Piece::from_bulge(point(start), point(end), bulge)
+                        .mirrored_if(is_mirrored)
+                });
+
+                if is_closed {
+                    let mut pieces = pieces.peekable();
+                    if let Some(first) = pieces.peek() {
+                        let mut contour = Contour::new(first.start());
+                        for piece in pieces {
+                            piece.add_to(&mut contour, tolerance);
+                        }
+                        contours.push(contour);
+                    }
+                } else {
+                    loose.extend(pieces);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    contours.extend(chain(loose, tolerance)?);
+
+    Ok(build_sketch(contours, tolerance, core))
+}
+
+/// A line or arc, that can be chained with others into a contour
+#[derive(Clone, Copy)]
+enum Piece {
+    Line {
+        start: Point<2>,
+        end: Point<2>,
+    },
+    Arc {
+        start: Point<2>,
+        through: Point<2>,
+        end: Point<2>,
+    },
+}
+
+impl Piece {
+    /// Create a polyline segment from its end points and bulge
+    ///
+    /// The bulge is the tangent of a quarter of the arc's angle. It's positive,
+    /// if the arc is counter-clockwise, and zero for straight lines.
+    fn from_bulge(start: Point<2>, end: Point<2>, bulge: f64) -> Self {
+        if bulge == 0. {
+            return Self::Line { start, end };
+        }
+
+        // The distance between the middle of the chord and the middle of the
+        // arc is the bulge times half the chord. Counter-clockwise arcs are to
+        // the right of the chord.
+        let chord = end - start;
+        let right = Vector::from([chord.v, -chord.u]);
+        let through = start + chord / 2. + right * Scalar::from_f64(bulge / 2.);
+
+        Self::Arc {
+            start,
+            through,
+            end,
+        }
+    }
+
+    /// Mirror the piece, if the polyline's points have been mirrored
+    ///
+    /// Polyline points are converted into the sketch's coordinate system as
+    /// they are parsed, but the bulges are not. This flips the side of the
+    /// chord that arcs are on.
+    fn mirrored_if(self, is_mirrored: bool) -> Self {
+        match self {
+            Self::Arc {
+                start,
+                through,
+                end,
+            } if is_mirrored => {
+                let middle = start + (end - start) / 2.;
+                Self::Arc {
+                    start,
+                    through: middle - (through - middle),
+                    end,
+                }
+            }
+            piece => piece,
+        }
+    }
+
+    fn start(&self) -> Point<2> {
+        match self {
+            Self::Line { start, .. } | Self::Arc { start, .. } => *start,
+        }
+    }
+
+    fn end(&self) -> Point<2> {
+        match self {
+            Self::Line { end, .. } | Self::Arc { end, .. } => *end,
+        }
+    }
+
+    fn reverse(self) -> Self {
+        match self {
+            Self::Line { start, end } => Self::Line {
+                start: end,
+                end: start,
+            },
+            Self::Arc {
+                start,
+                through,
+                end,
+            } => Self::Arc {
+                start: end,
+                through,
+                end: start,
+            },
+        }
+    }
+
+    fn add_to(self, contour: &mut Contour, tolerance: Scalar) {
+        match self {
+            Self::Line { end, .. } => contour.line_to(end, tolerance),
+            Self::Arc { through, end, .. } => {
+                contour.arc_through(through, end, tolerance)
+            }
+        }
+    }
+}
+
+/// Chain pieces into closed contours
+fn chain(
+    mut pieces: Vec<Piece>,
+    tolerance: Scalar,
+) -> Result<Vec<Contour>, Error> {
+    let mut contours = Vec::new();
+
+    while let Some(first) = pieces.pop() {
+        let mut contour = Contour::new(first.start());
+        first.add_to(&mut contour, tolerance);
+
+        let mut current = first.end();
+        while current.distance_to(&contour.start()) >= tolerance {
+            let next = pieces.iter().position(|piece| {
+                piece.start().distance_to(&current) < tolerance
+                    || piece.end().distance_to(&current) < tolerance
+            });
+            let Some(next) = next else {
+                return Err(Error::OpenContour(current));
+            };
+
+            let mut piece = pieces.swap_remove(next);
+            if piece.start().distance_to(&current) >= tolerance {
+                piece = piece.reverse();
+            }
+
+            piece.add_to(&mut contour, tolerance);
+            current = piece.end();
+        }
+
+        contours.push(contour);
+    }
+
+    Ok(contours)
+}
+
+/// An entity in the `ENTITIES` section of a DXF file
+struct Entity {
+    kind: String,
+    codes: Vec<(i32, String)>,
+}
+
+impl Entity {
+    /// Access the first value with the provided group code, as a number
+    fn get(&self, code: i32) -> Option<f64> {
+        self.codes
+            .iter()
+            .find(|(c, _)| *c == code)
+            .and_then(|(_, value)| value.parse().ok())
+    }
+
+    /// Access a required value, as a number
+    fn value(&self, code: i32) -> Result<f64, Error> {
+        let (_, value) =
+            self.codes.iter().find(|(c, _)| *c == code).ok_or_else(|| {
+                Error::Dxf(format!(
+                    "`{}` is missing group code {code}",
+                    self.kind
+                ))
+            })?;
+
+        parse_number(code, value)
+    }
+}
+
+fn parse_number(code: i32, value: &str) -> Result<f64, Error> {
+    value.parse().map_err(|_| {
+        Error::Dxf(format!("invalid value `{value}` for group code {code}"))
+    })
+}
+
+/// Read the entities from the `ENTITIES` section of an ASCII DXF file
+fn entities(dxf: &str) -> Result<Vec<Entity>, Error> {
+    let mut lines = dxf.lines().map(str::trim);
+    let mut pairs = Vec::new();
+
+    while let Some(code) = lines.next() {
+        if code.is_empty() && pairs.is_empty() {
+            continue;
+        }
+
+        let code = code
+            .parse::<i32>()
+            .map_err(|_| Error::Dxf(format!("invalid group code `{code}`")))?;
+        let value = lines.next().ok_or_else(|| {
+            Error::Dxf(format!("missing value for group code {code}"))
+        })?;
+
+        pairs.push((code, value.to_string()));
+    }
+
+    let mut entities = Vec::new();
+    let mut section = None;
+    let mut pairs = pairs.into_iter().peekable();
+
+    while let Some((code, value)) = pairs.next() {
+        if code != 0 {
+            continue;
+        }
+
+        match value.as_str() {
+            "SECTION" => {
+                section = pairs.next().map(|(_, name)| name);
+            }
+            "ENDSEC" => {
+                section = None;
+            }
+            kind if section.as_deref() == Some("ENTITIES") => {
+                let mut codes = Vec::new();
+                while let Some((code, value)) =
+                    pairs.next_if(|(code, _)| *code != 0)
+                {
+                    codes.push((code, value));
+                }
+
+                entities.push(Entity {
+                    kind: kind.to_string(),
+                    codes,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(entities)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_core::Instance;
+
+    use crate::{
+        tests::{assert_area, num_arcs, validate},
+        Error,
+    };
+
+    use super::import_dxf;
+
+    #[test]
+    fn entities_of_all_kinds() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let dxf = dxf(&[
+            // Loose lines and an arc, that have to be chained into a square
+            // with a half circle on top. One of the lines is reversed.
+            ("LINE", &[(10, 0.), (20, 0.), (11, 10.), (21, 0.)]),
+            ("LINE", &[(10, 10.), (20, 10.), (11, 10.), (21, 0.)]),
+            (
+                "ARC",
+                &[(10, 5.), (20, 10.), (40, 5.), (50, 0.), (51, 180.)],
+            ),
+            ("LINE", &[(10, 0.), (20, 10.), (11, 0.), (21, 0.)]),
+            // A hole in the middle of the square.
+            ("CIRCLE", &[(10, 5.), (20, 5.), (40, 2.)]),
+            // A closed polyline, with a half circle bulging out on top.
+            (
+                "LWPOLYLINE",
+                &[
+                    (70, 1.),
+                    (10, 20.),
+                    (20, 0.),
+                    (10, 24.),
+                    (20, 0.),
+                    (10, 24.),
+                    (20, 4.),
+                    (42, 1.),
+                    (10, 20.),
+                    (20, 4.),
+                ],
+            ),
+        ]);
+        let sketch = import_dxf(&dxf, 0.001, &mut core)?;
+
+        let mut regions = sketch.regions().iter().collect::<Vec<_>>();
+        regions.sort_by_key(|region| region.interiors().len());
+        let [polyline, square] = regions.as_slice() else {
+            panic!("Expected two regions, got {}", regions.len());
+        };
+
+        assert_eq!(square.interiors().len(), 1);
+        assert_eq!(num_arcs(square), 3);
+        assert_area(square, 100. + 12.5 * PI - 4. * PI);
+
+        assert!(polyline.interiors().is_empty());
+        assert_eq!(num_arcs(polyline), 1);
+        assert_area(polyline, 16. + 2. * PI);
+
+        validate(&sketch, &mut core)
+    }
+
+    #[test]
+    fn open_contour() {
+        let mut core = Instance::new();
+
+        let dxf = dxf(&[
+            ("LINE", &[(10, 0.), (20, 0.), (11, 1.), (21, 0.)]),
+            ("LINE", &[(10, 1.), (20, 0.), (11, 1.), (21, 1.)]),
+        ]);
+        let result = import_dxf(&dxf, 0.001, &mut core);
+        assert!(matches!(result, Err(Error::OpenContour(_))));
+    }
+
+    #[test]
+    fn missing_group_code() {
+        let mut core = Instance::new();
+
+        let dxf = dxf(&[("LINE", &[(10, 0.), (20, 0.), (11, 1.)])]);
+        let result = import_dxf(&dxf, 0.001, &mut core);
+        assert!(matches!(result, Err(Error::Dxf(_))));
+    }
+
+    #[test]
+    fn malformed_file() {
+        let mut core = Instance::new();
+
+        for dxf in [
+            "  0\nSECTION\n  2\nENTITIES\nzero\nLINE\n",
+            "  0\nSECTION\n  2\nENTITIES\n  0\nLINE\n 10\n",
+            "  0\nSECTION\n  2\nENTITIES\n  0\nCIRCLE\n 10\n0\n 20\n0\n 40\nr\n",
+        ] {
+            let result = import_dxf(dxf, 0.001, &mut core);
+            assert!(matches!(result, Err(Error::Dxf(_))), "{dxf}");
+        }
+    }
+
+    /// Create an ASCII DXF file, with the provided entities
+    fn dxf(entities: &[(&str, &[(i32, f64)])]) -> String {
+        let mut dxf = String::from("  0\nSECTION\n  2\nENTITIES\n");
+        for (kind, codes) in entities {
+            dxf.push_str(&format!("  0\n{kind}\n"));
+            for (code, value) in *codes {
+                dxf.push_str(&format!("{code:>3}\n{value}\n"));
+            }
+        }
+        dxf.push_str("  0\nENDSEC\n  0\nEOF\n");
+        dxf
+    }
+}
@@ -0,0 +1,201 @@
+//! # Fornjot Importer
+//!
+//! [Fornjot] is an early-stage b-rep CAD kernel written in Rust. The kernel is
+//! split into multiple libraries that can be used semi-independently, and this
+//! is one of those.
+//!
+//! This library imports 2D shapes from external file formats into Fornjot
+//! sketches, and 3D models into Fornjot solids.
+//!
+//! [Fornjot]: https://www.fornjot.app/
+
+mod contour;
+
+pub mod dxf;
+pub mod step;
+pub mod svg;
+
+pub use self::{dxf::import_dxf, step::import_step, svg::import_svg};
+
+use std::{fs, path::Path};
+
+use fj_core::{
+    algorithms::approx::Tolerance,
+    objects::{Sketch, Solid},
+    validate::ValidationError,
+    Instance,
+};
+use fj_math::Point;
+use thiserror::Error;
+
+/// Import the file at the given path into a [`Sketch`]
+///
+/// Currently, SVG and DXF files are supported. The case insensitive file extension of
+/// the provided path is used to switch between supported types.
+///
+/// Curves that can't be represented exactly are approximated, deviating from
+/// the original by no more than `tolerance`.
+pub fn import(
+    path: &Path,
+    tolerance: impl Into<Tolerance>,
+    core: &mut Instance,
+) -> Result<Sketch, Error> {
+    match path.extension() {
+        Some(extension) if extension.to_ascii_uppercase() == "SVG" => {
+            import_svg(&fs::read_to_string(path)?, tolerance, core)
+        }
+        Some(extension) if extension.to_ascii_uppercase() == "DXF" => {
+            // DXF files written by older software aren't necessarily UTF-8.
+            // All data that is relevant here is ASCII though.
+            let dxf = fs::read(path)?;
+            import_dxf(&String::from_utf8_lossy(&dxf), tolerance, core)
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
+
+/// Import the file at the given path into a [`Solid`]
+///
+/// Currently, STEP files are supported. The case insensitive file extension of
+/// the provided path is used to switch between supported types.
+///
+/// See [`import_step`] for details on how the file is imported.
+pub fn import_solid(path: &Path, core: &mut Instance) -> Result<Solid, Error> {
+    match path.extension() {
+        Some(extension)
+            if ["STEP", "STP"].contains(
+                &extension.to_ascii_uppercase().to_string_lossy().as_ref(),
+            ) =>
+        {
+            import_step(&fs::read_to_string(path)?, core)
+        }
+        Some(extension) => Err(Error::InvalidExtension(
+            extension.to_string_lossy().into_owned(),
+        )),
+        None => Err(Error::NoExtension),
+    }
+}
+
+/// An error that can occur while importing
+#[derive(Debug, Error)]
+pub enum Error {
+    /// No extension specified
+    #[error("no extension specified")]
+    NoExtension,
+
+    /// Unrecognized extension found
+    #[error("unrecognized extension found `{0:?}`")]
+    InvalidExtension(String),
+
+    /// I/O error whilst importing from file
+    #[error("I/O error whilst importing from file")]
+    Io(#[from] std::io::Error),
+
+    /// XML error whilst importing from SVG file
+    #[error("XML error whilst importing from SVG file")]
+    Xml(#[from] roxmltree::Error),
+
+    /// Invalid attribute value whilst importing from SVG file
+    #[error("invalid attribute value whilst importing from SVG file")]
+    Svg(#[from] svgtypes::Error),
+
+    /// Invalid data whilst importing from DXF file
+    #[error("invalid data whilst importing from DXF file: {0}")]
+    Dxf(String),
+
+    /// Entities that don't form a closed contour
+    #[error("entities don't form a closed contour; gap at {0:?}")]
+    OpenContour(Point<2>),
+
+    /// Invalid data whilst importing from STEP file
+    #[error("invalid data whilst importing from STEP file: {0}")]
+    Step(String),
+
+    /// STEP file contains geometry that is not supported
+    #[error("STEP file contains unsupported geometry: {0}")]
+    UnsupportedStep(String),
+
+    /// Imported object is not valid
+    #[error("imported object is not valid")]
+    Validation(#[source] Box<ValidationError>),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use fj_core::{
+        geometry::SurfacePath,
+        objects::{Region, Sketch},
+        operations::sweep::SweepSketch,
+        validate::Validate,
+        Instance,
+    };
+    use fj_math::Scalar;
+
+    use crate::{import, import_solid, Error};
+
+    #[test]
+    fn missing_extension() {
+        let mut core = Instance::new();
+
+        let result = import(Path::new("sketch"), 0.001, &mut core);
+        assert!(matches!(result, Err(Error::NoExtension)));
+
+        let result = import_solid(Path::new("model"), &mut core);
+        assert!(matches!(result, Err(Error::NoExtension)));
+    }
+
+    #[test]
+    fn unsupported_extension() {
+        let mut core = Instance::new();
+
+        let result = import(Path::new("sketch.png"), 0.001, &mut core);
+        assert!(matches!(result, Err(Error::InvalidExtension(_))));
+
+        let result = import_solid(Path::new("model.stl"), &mut core);
+        assert!(matches!(result, Err(Error::InvalidExtension(_))));
+    }
+
+    /// Count the arcs in all cycles of the region
+    pub fn num_arcs(region: &Region) -> usize {
+        region
+            .all_cycles()
+            .flat_map(|cycle| cycle.half_edges())
+            .filter(|half_edge| {
+                matches!(half_edge.path(), SurfacePath::Circle(_))
+            })
+            .count()
+    }
+
+    /// Check the area of the region, minus the area of its holes
+    pub fn assert_area(region: &Region, expected: f64) {
+        let area = region
+            .all_cycles()
+            .map(|cycle| cycle.signed_area().abs())
+            .reduce(|exterior, interior| exterior - interior)
+            .unwrap_or_default();
+
+        assert!(
+            (area - Scalar::from(expected)).abs() < Scalar::from(1e-6),
+            "Unexpected area: {area} (expected {expected})"
+        );
+    }
+
+    /// Validate the sketch, and the solid it sweeps into
+    pub fn validate(
+        sketch: &Sketch,
+        core: &mut Instance,
+    ) -> anyhow::Result<()> {
+        sketch.validate_and_return_first_error()?;
+
+        let surface = core.services.objects.surfaces.xy_plane();
+        let solid = sketch.sweep_sketch(surface, [0., 0., 1.], core);
+        solid.validate_and_return_first_error()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1106 @@
+//! # STEP import
+//!
+//! See [`import_step`].
+
+mod parser;
+
+use std::collections::BTreeMap;
+
+use fj_core::{
+    geometry::{GlobalPath, SurfaceGeometry, SurfacePath},
+    objects::{
+        Curve, Cycle, Face, HalfEdge, Region, Shell, Solid, Surface, Vertex,
+    },
+    operations::insert::Insert,
+    storage::Handle,
+    validate::{Validate, ValidationConfig},
+    Instance,
+};
+use fj_math::{Circle, Line, Point, Scalar, Vector};
+
+use crate::Error;
+
+use self::parser::{Instances, Parameter, Record};
+
+/// Import the solids in a STEP file
+///
+/// Supports boundary representations (`MANIFOLD_SOLID_BREP`) with planar and
+/// cylindrical faces, which are bounded by lines and circles. Any other
+/// geometry results in [`Error::UnsupportedStep`]. Each solid in the file
+/// becomes a shell of the returned solid. Where parts are placed within an
+/// assembly is not taken into account.
+///
+/// Coordinates are converted into millimeters.
+///
+/// All objects are validated, using the validation configuration of `core`,
+/// before they are inserted. An invalid object results in
+/// [`Error::Validation`], and is not inserted. STEP files are often written
+/// with less precision than the default configuration expects. Such files can
+/// be imported, using an [`Instance`] with a more tolerant configuration.
+pub fn import_step(step: &str, core: &mut Instance) -> Result<Solid, Error> {
+    let instances = parser::parse(step)?;
+
+    let mut importer = Importer {
+        instances: &instances,
+        scale: 1.,
+        config: core.services.validation.config(),
+        vertices: BTreeMap::new(),
+        edges: BTreeMap::new(),
+    };
+    importer.scale = importer.length_unit()?;
+
+    let mut shells = Vec::new();
+    for instance in instances.values() {
+        for record in &instance.records {
+            match record.name.as_str() {
+                "MANIFOLD_SOLID_BREP" => {
+                    let shell = record.reference(1)?;
+                    shells.push(importer.shell(shell, core)?);
+                }
+                "BREP_WITH_VOIDS" => {
+                    return Err(Error::UnsupportedStep(record.name.clone()));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if shells.is_empty() {
+        return Err(Error::Step("file contains no solids".to_string()));
+    }
+
+    let solid = Solid::new(shells);
+    validate(&solid, &importer.config)?;
+
+    Ok(solid)
+}
+
+struct Importer<'r> {
+    instances: &'r Instances,
+
+    /// The length of the file's length unit, in millimeters
+    scale: f64,
+
+    config: ValidationConfig,
+
+    /// The vertices that have been created, by the id of their `VERTEX_POINT`
+    vertices: BTreeMap<u64, (Handle<Vertex>, Point<3>)>,
+
+    /// The edges that have been created, by the id of their `EDGE_CURVE`
+    edges: BTreeMap<u64, Edge>,
+}
+
+impl<'r> Importer<'r> {
+    /// Access the record of a simple entity instance
+    fn record(&self, id: u64) -> Result<&'r Record, Error> {
+        self.instances
+            .get(&id)
+            .and_then(|instance| instance.records.first())
+            .ok_or_else(|| Error::Step(format!("missing instance #{id}")))
+    }
+
+    /// Access the record of an instance, making sure it has the expected type
+    fn expect(&self, id: u64, names: &[&str]) -> Result<&'r Record, Error> {
+        let record = self.record(id)?;
+
+        if !names.contains(&record.name.as_str()) {
+            return Err(Error::UnsupportedStep(record.name.clone()));
+        }
+
+        Ok(record)
+    }
+
+    /// Determine the length of the file's length unit, in millimeters
+    fn length_unit(&self) -> Result<f64, Error> {
+        let units = self.instances.values().find_map(|instance| {
+            instance.record("GLOBAL_UNIT_ASSIGNED_CONTEXT")
+        });
+        let Some(units) = units else {
+            return Ok(1.);
+        };
+
+        for unit in units.list(0)? {
+            let Parameter::Reference(unit) = unit else {
+                continue;
+            };
+            let Some(instance) = self.instances.get(unit) else {
+                continue;
+            };
+
+            if instance.record("LENGTH_UNIT").is_some() {
+                return self.length(*unit);
+            }
+        }
+
+        Ok(1.)
+    }
+
+    /// Determine the length of a length unit, in millimeters
+    fn length(&self, unit: u64) -> Result<f64, Error> {
+        let instance = self
+            .instances
+            .get(&unit)
+            .ok_or_else(|| Error::Step(format!("missing instance #{unit}")))?;
+
+        if let Some(si_unit) = instance.record("SI_UNIT") {
+            let prefix = match &si_unit.parameters[..] {
+                [Parameter::Enumeration(prefix), ..] => prefix.as_str(),
+                _ => "",
+            };
+
+            let length = match prefix {
+                "" => 1000.,
+                "KILO" => 1_000_000.,
+                "DECI" => 100.,
+                "CENTI" => 10.,
+                "MILLI" => 1.,
+                "MICRO" => 0.001,
+                "NANO" => 0.000_001,
+                prefix => {
+                    return Err(Error::UnsupportedStep(format!(
+                        "unit prefix `{prefix}`"
+                    )));
+                }
+            };
+
+            return Ok(length);
+        }
+
+        if let Some(conversion) = instance.record("CONVERSION_BASED_UNIT") {
+            let measure = self.record(conversion.reference(1)?)?;
+            let value = match measure.parameters.first() {
+                Some(Parameter::Typed(Record { parameters, .. })) => {
+                    match parameters.first() {
+                        Some(Parameter::Number(value)) => *value,
+                        _ => return Err(Error::Step("invalid measure".into())),
+                    }
+                }
+                Some(Parameter::Number(value)) => *value,
+                _ => return Err(Error::Step("invalid measure".into())),
+            };
+
+            return Ok(value * self.length(measure.reference(1)?)?);
+        }
+
+        Err(Error::UnsupportedStep("length unit".to_string()))
+    }
+
+    fn point(&self, id: u64) -> Result<Point<3>, Error> {
+        let record = self.expect(id, &["CARTESIAN_POINT"])?;
+        let [x, y, z] = record.numbers(1)?;
+        Ok(Point::from([x, y, z].map(|coord| coord * self.scale)))
+    }
+
+    fn direction(&self, id: u64) -> Result<Vector<3>, Error> {
+        let record = self.expect(id, &["DIRECTION"])?;
+        let [x, y, z] = record.numbers(1)?;
+
+        let direction = Vector::from([x, y, z]);
+        if direction.magnitude() == Scalar::ZERO {
+            return Err(Error::Step(format!(
+                "direction #{id} has zero length"
+            )));
+        }
+
+        Ok(direction.normalize())
+    }
+
+    /// Read the radius of a circle or cylinder, which must be positive
+    fn radius(&self, record: &Record, id: u64) -> Result<Scalar, Error> {
+        let radius = record.number(2)? * self.scale;
+        if radius <= 0. {
+            return Err(Error::Step(format!(
+                "radius of #{id} is not positive"
+            )));
+        }
+
+        Ok(Scalar::from_f64(radius))
+    }
+
+    /// Read an `AXIS2_PLACEMENT_3D`
+    ///
+    /// Returns its location, and its orthonormal z and x axes.
+    fn placement(&self, id: u64) -> Result<[Vector<3>; 3], Error> {
+        let record = self.expect(id, &["AXIS2_PLACEMENT_3D"])?;
+
+        let location = self.point(record.reference(1)?)?;
+        let axis = match record.parameters.get(2) {
+            Some(Parameter::Reference(id)) => self.direction(*id)?,
+            _ => Vector::unit_z(),
+        };
+        let ref_direction = match record.parameters.get(3) {
+            Some(Parameter::Reference(id)) => self.direction(*id)?,
+            _ => Vector::unit_x(),
+        };
+
+        // The reference direction is only required to not be parallel to the
+        // axis. What's actually used is its projection into the plane that is
+        // perpendicular to the axis.
+        let x = ref_direction - axis * ref_direction.dot(&axis);
+        if x.magnitude() < Scalar::from_f64(1e-9) {
+            return Err(Error::Step(format!(
+                "axis and reference direction of #{id} are parallel"
+            )));
+        }
+
+        Ok([location.coords, axis, x.normalize()])
+    }
+
+    fn shell(
+        &mut self,
+        id: u64,
+        core: &mut Instance,
+    ) -> Result<Handle<Shell>, Error> {
+        let record = self.expect(id, &["CLOSED_SHELL"])?;
+
+        let mut faces = Vec::new();
+        for face in record.references(1)? {
+            faces.push(self.face(face, core)?);
+        }
+
+        insert(Shell::new(faces), &self.config, core)
+    }
+
+    fn face(
+        &mut self,
+        id: u64,
+        core: &mut Instance,
+    ) -> Result<Handle<Face>, Error> {
+        let record = self.expect(id, &["ADVANCED_FACE", "FACE_SURFACE"])?;
+
+        // The edge loops are oriented such, that the face is on their left,
+        // when looking at the face from the front. That's the same in Fornjot,
+        // meaning the orientation of the face itself doesn't need to be taken
+        // into account.
+        let surface = self.surface(record.reference(2)?)?;
+
+        let mut exterior = None;
+        let mut interiors = Vec::new();
+
+        for bound in record.references(1)? {
+            let bound =
+                self.expect(bound, &["FACE_OUTER_BOUND", "FACE_BOUND"])?;
+            let cycle = self.cycle(
+                bound.reference(1)?,
+                bound.logical(2)?,
+                &surface,
+                core,
+            )?;
+
+            if bound.name == "FACE_OUTER_BOUND" && exterior.is_none() {
+                exterior = Some(cycle);
+            } else {
+                interiors.push(cycle);
+            }
+        }
+
+        // Specifying which bound is the outer one is optional. If it's
+        // missing, the outer bound must be the one that encloses the others.
+        let exterior = match exterior {
+            Some(exterior) => exterior,
+            None => {
+                let outer = interiors
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, cycle)| cycle.signed_area().abs())
+                    .map(|(i, _)| i)
+                    .ok_or_else(|| {
+                        Error::Step(format!("face #{id} has no bounds"))
+                    })?;
+                interiors.remove(outer)
+            }
+        };
+
+        let region =
+            insert(Region::new(exterior, interiors, None), &self.config, core)?;
+        let surface =
+            insert(Surface::new(surface.geometry()), &self.config, core)?;
+
+        insert(Face::new(surface, region), &self.config, core)
+    }
+
+    fn surface(&self, id: u64) -> Result<FaceSurface, Error> {
+        let record = self.expect(id, &["PLANE", "CYLINDRICAL_SURFACE"])?;
+        let [origin, axis, x] = self.placement(record.reference(1)?)?;
+        let origin = Point { coords: origin };
+
+        match record.name.as_str() {
+            "PLANE" => {
+                let y = axis.cross(&x);
+                Ok(FaceSurface::Plane { origin, x, y })
+            }
+            _ => {
+                let radius = self.radius(record, id)?;
+                let circle =
+                    exact_circle(origin, axis, x, radius).ok_or_else(|| {
+                        Error::UnsupportedStep(format!(
+                            "cylinder #{id}, that can't be represented exactly"
+                        ))
+                    })?;
+                Ok(FaceSurface::Cylinder { circle, axis })
+            }
+        }
+    }
+
+    fn cycle(
+        &mut self,
+        id: u64,
+        orientation: bool,
+        surface: &FaceSurface,
+        core: &mut Instance,
+    ) -> Result<Handle<Cycle>, Error> {
+        let record = self.expect(id, &["EDGE_LOOP"])?;
+
+        let mut oriented_edges = Vec::new();
+        for oriented_edge in record.references(1)? {
+            let oriented_edge =
+                self.expect(oriented_edge, &["ORIENTED_EDGE"])?;
+            oriented_edges
+                .push((oriented_edge.reference(3)?, oriented_edge.logical(4)?));
+        }
+
+        if !orientation {
+            oriented_edges.reverse();
+            for (_, orientation) in &mut oriented_edges {
+                *orientation = !*orientation;
+            }
+        }
+
+        let mut half_edges = Vec::new();
+        let mut previous_end = None;
+
+        for (edge, orientation) in oriented_edges {
+            let edge = self.edge(edge, core)?;
+
+            let [start, end] = if orientation { [0, 1] } else { [1, 0] };
+            let path = surface.path(&edge, start, previous_end)?;
+            let boundary = [start, end].map(|i| Point::from([edge.coords[i]]));
+
+            previous_end = Some(path.point_from_path_coords(boundary[1]));
+
+            let half_edge = HalfEdge::new(
+                path,
+                boundary,
+                edge.curve.clone(),
+                edge.vertices[start].clone(),
+            );
+            half_edges.push(insert(half_edge, &self.config, core)?);
+        }
+
+        insert(Cycle::new(half_edges), &self.config, core)
+    }
+
+    /// Access the edge for an `EDGE_CURVE`, creating it if necessary
+    fn edge(&mut self, id: u64, core: &mut Instance) -> Result<Edge, Error> {
+        if let Some(edge) = self.edges.get(&id) {
+            return Ok(edge.clone());
+        }
+
+        let record = self.expect(id, &["EDGE_CURVE"])?;
+        let vertex_ids = [record.reference(1)?, record.reference(2)?];
+        let [(a, a_position), (b, b_position)] = [
+            self.vertex(vertex_ids[0], core)?,
+            self.vertex(vertex_ids[1], core)?,
+        ];
+        let same_sense = record.logical(4)?;
+
+        let geometry = self.curve(record.reference(3)?)?;
+
+        let coords = match geometry {
+            EdgeGeometry::Line => {
+                if vertex_ids[0] == vertex_ids[1] {
+                    return Err(Error::Step(format!(
+                        "straight edge #{id} starts and ends at same vertex"
+                    )));
+                }
+
+                [Scalar::ZERO, Scalar::ONE]
+            }
+            EdgeGeometry::Circle(circle) => {
+                // The edge runs from its first to its second vertex, along the
+                // direction of the circle, unless it has the opposite sense.
+                let start = circle.point_to_circle_coords(a_position).t;
+                let mut end = circle.point_to_circle_coords(b_position).t;
+
+                if vertex_ids[0] == vertex_ids[1] {
+                    end = start;
+                }
+                if same_sense {
+                    while end <= start {
+                        end += Scalar::TAU;
+                    }
+                } else {
+                    while end >= start {
+                        end -= Scalar::TAU;
+                    }
+                }
+
+                [start, end]
+            }
+        };
+
+        let edge = Edge {
+            curve: insert(Curve::new(), &self.config, core)?,
+            vertices: [a, b],
+            positions: [a_position, b_position],
+            geometry,
+            coords,
+        };
+        self.edges.insert(id, edge.clone());
+
+        Ok(edge)
+    }
+
+    fn vertex(
+        &mut self,
+        id: u64,
+        core: &mut Instance,
+    ) -> Result<(Handle<Vertex>, Point<3>), Error> {
+        if let Some(vertex) = self.vertices.get(&id) {
+            return Ok(vertex.clone());
+        }
+
+        let record = self.expect(id, &["VERTEX_POINT"])?;
+        let position = self.point(record.reference(1)?)?;
+
+        let vertex = (insert(Vertex::new(), &self.config, core)?, position);
+        self.vertices.insert(id, vertex.clone());
+
+        Ok(vertex)
+    }
+
+    fn curve(&self, id: u64) -> Result<EdgeGeometry, Error> {
+        let record = self.record(id)?;
+
+        match record.name.as_str() {
+            "LINE" => Ok(EdgeGeometry::Line),
+            "CIRCLE" => {
+                let [center, axis, x] = self.placement(record.reference(1)?)?;
+                let radius = self.radius(record, id)?;
+
+                let circle =
+                    exact_circle(Point { coords: center }, axis, x, radius)
+                        .ok_or_else(|| {
+                            Error::UnsupportedStep(format!(
+                            "circle #{id}, that can't be represented exactly"
+                        ))
+                        })?;
+                Ok(EdgeGeometry::Circle(circle))
+            }
+            // Curves that are the intersection of two surfaces reference their
+            // 3D geometry first.
+            "SURFACE_CURVE" | "SEAM_CURVE" => self.curve(record.reference(1)?),
+            name => Err(Error::UnsupportedStep(name.to_string())),
+        }
+    }
+}
+
+/// An edge, shared by the two half-edges that refer to it
+#[derive(Clone)]
+struct Edge {
+    curve: Handle<Curve>,
+
+    /// The vertices where the edge starts and ends
+    vertices: [Handle<Vertex>; 2],
+
+    /// The positions of the vertices
+    positions: [Point<3>; 2],
+
+    geometry: EdgeGeometry,
+
+    /// The curve coordinates of the vertices
+    coords: [Scalar; 2],
+}
+
+#[derive(Clone, Copy)]
+enum EdgeGeometry {
+    /// A line, whose curve coordinates are `0` at the start and `1` at the end
+    Line,
+
+    /// A circle, whose curve coordinates are angles
+    Circle(Circle<3>),
+}
+
+/// The surface of a face
+enum FaceSurface {
+    Plane {
+        origin: Point<3>,
+        x: Vector<3>,
+        y: Vector<3>,
+    },
+    Cylinder {
+        circle: Circle<3>,
+        axis: Vector<3>,
+    },
+}
+
+impl FaceSurface {
+    fn geometry(&self) -> SurfaceGeometry {
+        match self {
+            Self::Plane { origin, x, y } => SurfaceGeometry {
+                u: GlobalPath::Line(Line::from_origin_and_direction(
+                    *origin, *x,
+                )),
+                v: *y,
+            },
+            Self::Cylinder { circle, axis } => SurfaceGeometry {
+                u: GlobalPath::Circle(*circle),
+                v: *axis,
+            },
+        }
+    }
+
+    /// Create the path of an edge within the surface
+    ///
+    /// The path uses the curve coordinates of the edge. On cylinders, the path
+    /// could be at any multiple of a full turn around. It's placed so that it
+    /// connects to `previous_end`, if provided. `start` is the index of the
+    /// edge's vertex, where the half-edge that uses the path starts.
+    fn path(
+        &self,
+        edge: &Edge,
+        start: usize,
+        previous_end: Option<Point<2>>,
+    ) -> Result<SurfacePath, Error> {
+        match (self, edge.geometry) {
+            (Self::Plane { origin, x, y }, geometry) => {
+                let project = |vector: Vector<3>| {
+                    Vector::from([vector.dot(x), vector.dot(y)])
+                };
+
+                match geometry {
+                    EdgeGeometry::Line => {
+                        let [a, b] = [0, 1].map(|i| {
+                            let point = Point::origin()
+                                + project(edge.positions[i] - *origin);
+                            (Point::from([edge.coords[i]]), point)
+                        });
+                        Ok(SurfacePath::Line(
+                            Line::from_points_with_line_coords([a, b]),
+                        ))
+                    }
+                    EdgeGeometry::Circle(circle) => {
+                        let normal = x.cross(y);
+                        if !is_parallel(circle.a().cross(&circle.b()), normal) {
+                            return Err(Error::UnsupportedStep(
+                                "circle that isn't parallel to its plane"
+                                    .to_string(),
+                            ));
+                        }
+
+                        // Constructing `b` from the components of `a` makes
+                        // them exactly equal in length, and perpendicular.
+                        let a = project(circle.a());
+                        let b = Vector::from([-a.v, a.u]);
+                        let b = if circle.a().cross(&circle.b()).dot(&normal)
+                            > Scalar::ZERO
+                        {
+                            b
+                        } else {
+                            -b
+                        };
+
+                        let center = Point::origin()
+                            + project(circle.center() - *origin);
+                        Ok(SurfacePath::Circle(Circle::new(center, a, b)))
+                    }
+                }
+            }
+            (Self::Cylinder { circle, axis }, geometry) => {
+                let height =
+                    |point: Point<3>| (point - circle.center()).dot(axis);
+
+                let points = match geometry {
+                    EdgeGeometry::Line => {
+                        let [a, b] = edge.positions;
+                        if !is_parallel(b - a, *axis) {
+                            return Err(Error::UnsupportedStep(
+                                "line on cylinder that isn't parallel to its \
+                                axis"
+                                    .to_string(),
+                            ));
+                        }
+
+                        let u = circle.point_to_circle_coords(a).t;
+                        edge.positions.map(|point| [u, height(point)])
+                    }
+                    EdgeGeometry::Circle(edge_circle) => {
+                        let edge_axis = edge_circle.a().cross(&edge_circle.b());
+                        if !is_parallel(edge_axis, *axis) {
+                            return Err(Error::UnsupportedStep(
+                                "circle on cylinder that isn't perpendicular \
+                                to its axis"
+                                    .to_string(),
+                            ));
+                        }
+
+                        // The angle on the cylinder where the circle's curve
+                        // coordinates are zero, and the direction in which
+                        // they increase.
+                        let offset = circle
+                            .point_to_circle_coords(
+                                edge_circle.point_from_circle_coords([0.]),
+                            )
+                            .t;
+                        let sign = edge_axis.dot(axis).sign().to_scalar();
+
+                        let v = height(edge_circle.center());
+                        edge.coords.map(|t| [offset + t * sign, v])
+                    }
+                };
+
+                let path = |turns: Scalar| {
+                    let [a, b] = [0, 1].map(|i| {
+                        let [u, v] = points[i];
+                        let point = Point::from([u + turns * Scalar::TAU, v]);
+                        (Point::from([edge.coords[i]]), point)
+                    });
+                    SurfacePath::Line(Line::from_points_with_line_coords([
+                        a, b,
+                    ]))
+                };
+
+                let Some(previous_end) = previous_end else {
+                    return Ok(path(Scalar::ZERO));
+                };
+
+                let [u, _] = points[start];
+                let turns = ((previous_end.u - u) / Scalar::TAU).round();
+
+                Ok(path(turns))
+            }
+        }
+    }
+}
+
+/// Create a circle that [`Circle::new`] accepts
+///
+/// [`Circle::new`] requires the two vectors that define the circle to be of
+/// exactly the same length, and to be perpendicular within machine precision.
+/// Computing them from an arbitrary axis doesn't reliably achieve that. Since
+/// the angle where the circle's coordinates start doesn't matter, different
+/// ones are tried, and the last bits of the vectors are adjusted.
+fn exact_circle(
+    center: Point<3>,
+    axis: Vector<3>,
+    x: Vector<3>,
+    radius: Scalar,
+) -> Option<Circle<3>> {
+    let y = axis.cross(&x);
+
+    for attempt in 0..16 {
+        let (sin, cos) = (Scalar::from_f64(0.1) * attempt as f64).sin_cos();
+        let a = (x * cos + y * sin) * radius;
+        let b = (y * cos - x * sin) * radius;
+
+        for steps in 0..8 {
+            for component in 0..3 {
+                for direction in [1, -1] {
+                    let mut b = b;
+                    b.components[component] = Scalar::from_f64(step(
+                        b.components[component].into_f64(),
+                        steps * direction,
+                    ));
+
+                    if a.magnitude() == b.magnitude()
+                        && a.dot(&b).into_f64() < f64::EPSILON
+                    {
+                        return Some(Circle::new(center, a, b));
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Move a number by the provided number of representable values
+fn step(value: f64, steps: i64) -> f64 {
+    let mut value = value;
+
+    for _ in 0..steps.abs() {
+        let increase = (steps > 0) == (value >= 0.);
+        let bits = value.to_bits();
+        value = if value == 0. {
+            f64::from_bits(1).copysign(steps as f64)
+        } else if increase {
+            f64::from_bits(bits + 1)
+        } else {
+            f64::from_bits(bits - 1)
+        };
+    }
+
+    value
+}
+
+fn is_parallel(a: Vector<3>, b: Vector<3>) -> bool {
+    a.cross(&b).magnitude() <= a.magnitude() * b.magnitude() * 1e-9
+}
+
+/// Validate an object and insert it, if it is valid
+fn insert<T>(
+    object: T,
+    config: &ValidationConfig,
+    core: &mut Instance,
+) -> Result<Handle<T>, Error>
+where
+    T: Insert<Inserted = Handle<T>> + Validate,
+{
+    validate(&object, config)?;
+    Ok(object.insert(core))
+}
+
+fn validate(
+    object: &impl Validate,
+    config: &ValidationConfig,
+) -> Result<(), Error> {
+    let mut errors = Vec::new();
+    object.validate_with_config(config, &mut errors);
+
+    match errors.into_iter().next() {
+        Some(err) => Err(Error::Validation(Box::new(err))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_core::{
+        algorithms::mass_properties::MassProperties, objects::Solid, Instance,
+    };
+    use fj_math::Scalar;
+
+    use crate::Error;
+
+    use super::import_step;
+
+    /// A box of 10 x 20 x 30 millimeters
+    const BOX: &str = "
+        #1=CARTESIAN_POINT('',(0.,0.,0.));
+        #2=CARTESIAN_POINT('',(10.,0.,0.));
+        #3=CARTESIAN_POINT('',(10.,20.,0.));
+        #4=CARTESIAN_POINT('',(0.,20.,0.));
+        #5=CARTESIAN_POINT('',(0.,0.,30.));
+        #6=CARTESIAN_POINT('',(10.,0.,30.));
+        #7=CARTESIAN_POINT('',(10.,20.,30.));
+        #8=CARTESIAN_POINT('',(0.,20.,30.));
+        #9=VERTEX_POINT('',#1);
+        #10=VERTEX_POINT('',#2);
+        #11=VERTEX_POINT('',#3);
+        #12=VERTEX_POINT('',#4);
+        #13=VERTEX_POINT('',#5);
+        #14=VERTEX_POINT('',#6);
+        #15=VERTEX_POINT('',#7);
+        #16=VERTEX_POINT('',#8);
+        #17=DIRECTION('',(1.,0.,0.));
+        #18=DIRECTION('',(0.,1.,0.));
+        #19=DIRECTION('',(0.,0.,1.));
+        #20=DIRECTION('',(-1.,0.,0.));
+        #21=DIRECTION('',(0.,-1.,0.));
+        #22=DIRECTION('',(0.,0.,-1.));
+        #23=VECTOR('',#17,10.);
+        #24=VECTOR('',#18,20.);
+        #25=VECTOR('',#19,30.);
+        #26=LINE('',#1,#23);
+        #27=LINE('',#2,#24);
+        #28=LINE('',#4,#23);
+        #29=LINE('',#1,#24);
+        #30=LINE('',#5,#23);
+        #31=LINE('',#6,#24);
+        #32=LINE('',#8,#23);
+        #33=LINE('',#5,#24);
+        #34=LINE('',#1,#25);
+        #35=LINE('',#2,#25);
+        #36=LINE('',#3,#25);
+        #37=LINE('',#4,#25);
+        #38=EDGE_CURVE('',#9,#10,#26,.T.);
+        #39=EDGE_CURVE('',#10,#11,#27,.T.);
+        #40=EDGE_CURVE('',#12,#11,#28,.T.);
+        #41=EDGE_CURVE('',#9,#12,#29,.T.);
+        #42=EDGE_CURVE('',#13,#14,#30,.T.);
+        #43=EDGE_CURVE('',#14,#15,#31,.T.);
+        #44=EDGE_CURVE('',#16,#15,#32,.T.);
+        #45=EDGE_CURVE('',#13,#16,#33,.T.);
+        #46=EDGE_CURVE('',#9,#13,#34,.T.);
+        #47=EDGE_CURVE('',#10,#14,#35,.T.);
+        #48=EDGE_CURVE('',#11,#15,#36,.T.);
+        #49=EDGE_CURVE('',#12,#16,#37,.T.);
+        #50=AXIS2_PLACEMENT_3D('',#1,#22,#17);
+        #51=PLANE('',#50);
+        #52=AXIS2_PLACEMENT_3D('',#5,#19,#17);
+        #53=PLANE('',#52);
+        #54=AXIS2_PLACEMENT_3D('',#1,#21,#17);
+        #55=PLANE('',#54);
+        #56=AXIS2_PLACEMENT_3D('',#3,#18,#17);
+        #57=PLANE('',#56);
+        #58=AXIS2_PLACEMENT_3D('',#1,#20,#18);
+        #59=PLANE('',#58);
+        #60=AXIS2_PLACEMENT_3D('',#2,#17,#18);
+        #61=PLANE('',#60);
+        #62=ORIENTED_EDGE('',*,*,#41,.T.);
+        #63=ORIENTED_EDGE('',*,*,#40,.T.);
+        #64=ORIENTED_EDGE('',*,*,#39,.F.);
+        #65=ORIENTED_EDGE('',*,*,#38,.F.);
+        #66=EDGE_LOOP('',(#62,#63,#64,#65));
+        #67=FACE_OUTER_BOUND('',#66,.T.);
+        #68=ADVANCED_FACE('',(#67),#51,.T.);
+        #69=ORIENTED_EDGE('',*,*,#42,.T.);
+        #70=ORIENTED_EDGE('',*,*,#43,.T.);
+        #71=ORIENTED_EDGE('',*,*,#44,.F.);
+        #72=ORIENTED_EDGE('',*,*,#45,.F.);
+        #73=EDGE_LOOP('',(#69,#70,#71,#72));
+        #74=FACE_OUTER_BOUND('',#73,.T.);
+        #75=ADVANCED_FACE('',(#74),#53,.T.);
+        #76=ORIENTED_EDGE('',*,*,#38,.T.);
+        #77=ORIENTED_EDGE('',*,*,#47,.T.);
+        #78=ORIENTED_EDGE('',*,*,#42,.F.);
+        #79=ORIENTED_EDGE('',*,*,#46,.F.);
+        #80=EDGE_LOOP('',(#76,#77,#78,#79));
+        #81=FACE_OUTER_BOUND('',#80,.T.);
+        #82=ADVANCED_FACE('',(#81),#55,.T.);
+        #83=ORIENTED_EDGE('',*,*,#40,.F.);
+        #84=ORIENTED_EDGE('',*,*,#49,.T.);
+        #85=ORIENTED_EDGE('',*,*,#44,.T.);
+        #86=ORIENTED_EDGE('',*,*,#48,.F.);
+        #87=EDGE_LOOP('',(#83,#84,#85,#86));
+        #88=FACE_OUTER_BOUND('',#87,.T.);
+        #89=ADVANCED_FACE('',(#88),#57,.T.);
+        #90=ORIENTED_EDGE('',*,*,#46,.T.);
+        #91=ORIENTED_EDGE('',*,*,#45,.T.);
+        #92=ORIENTED_EDGE('',*,*,#49,.F.);
+        #93=ORIENTED_EDGE('',*,*,#41,.F.);
+        #94=EDGE_LOOP('',(#90,#91,#92,#93));
+        #95=FACE_OUTER_BOUND('',#94,.T.);
+        #96=ADVANCED_FACE('',(#95),#59,.T.);
+        #97=ORIENTED_EDGE('',*,*,#39,.T.);
+        #98=ORIENTED_EDGE('',*,*,#48,.T.);
+        #99=ORIENTED_EDGE('',*,*,#43,.F.);
+        #100=ORIENTED_EDGE('',*,*,#47,.F.);
+        #101=EDGE_LOOP('',(#97,#98,#99,#100));
+        #102=FACE_OUTER_BOUND('',#101,.T.);
+        #103=ADVANCED_FACE('',(#102),#61,.T.);
+        #104=CLOSED_SHELL('',(#68,#75,#82,#89,#96,#103));
+        #105=MANIFOLD_SOLID_BREP('box',#104);
+        #106=(LENGTH_UNIT()NAMED_UNIT(*)SI_UNIT(.MILLI.,.METRE.));
+        #107=(GEOMETRIC_REPRESENTATION_CONTEXT(3)
+            GLOBAL_UNIT_ASSIGNED_CONTEXT((#106))
+            REPRESENTATION_CONTEXT('',''));
+        #108=ADVANCED_BREP_SHAPE_REPRESENTATION('box',(#105),#107);
+    ";
+
+    /// A cylinder with a radius and height of one inch, and a through hole
+    /// with a radius of half an inch
+    const TUBE: &str = "
+        #1=CARTESIAN_POINT('',(0.,0.,0.));
+        #2=CARTESIAN_POINT('',(0.,0.,1.));
+        #3=CARTESIAN_POINT('',(1.,0.,0.));
+        #4=CARTESIAN_POINT('',(1.,0.,1.));
+        #5=CARTESIAN_POINT('',(0.5,0.,0.));
+        #6=CARTESIAN_POINT('',(0.5,0.,1.));
+        #7=DIRECTION('',(0.,0.,1.));
+        #8=DIRECTION('',(0.,0.,-1.));
+        #9=DIRECTION('',(1.,0.,0.));
+        #10=VERTEX_POINT('',#3);
+        #11=VERTEX_POINT('',#4);
+        #12=VERTEX_POINT('',#5);
+        #13=VERTEX_POINT('',#6);
+        #14=AXIS2_PLACEMENT_3D('',#1,#8,#9);
+        #15=AXIS2_PLACEMENT_3D('',#2,#8,#9);
+        #16=AXIS2_PLACEMENT_3D('',#1,#7,#9);
+        #17=AXIS2_PLACEMENT_3D('',#2,#7,#9);
+        #18=CIRCLE('',#14,1.);
+        #19=CIRCLE('',#16,0.5);
+        #20=CIRCLE('',#15,1.);
+        #21=CIRCLE('',#17,0.5);
+        #22=EDGE_CURVE('',#10,#10,#18,.T.);
+        #23=EDGE_CURVE('',#12,#12,#19,.T.);
+        #24=EDGE_CURVE('',#11,#11,#20,.T.);
+        #25=EDGE_CURVE('',#13,#13,#21,.T.);
+        #26=VECTOR('',#7,1.);
+        #27=LINE('',#3,#26);
+        #28=EDGE_CURVE('',#10,#11,#27,.T.);
+        #29=LINE('',#5,#26);
+        #30=EDGE_CURVE('',#12,#13,#29,.T.);
+        #31=PLANE('',#14);
+        #32=ORIENTED_EDGE('',*,*,#22,.T.);
+        #33=EDGE_LOOP('',(#32));
+        #34=FACE_OUTER_BOUND('',#33,.T.);
+        #35=ORIENTED_EDGE('',*,*,#23,.T.);
+        #36=EDGE_LOOP('',(#35));
+        #37=FACE_BOUND('',#36,.T.);
+        #38=ADVANCED_FACE('',(#34,#37),#31,.T.);
+        #39=CYLINDRICAL_SURFACE('',#16,1.);
+        #40=ORIENTED_EDGE('',*,*,#22,.F.);
+        #41=ORIENTED_EDGE('',*,*,#28,.T.);
+        #42=ORIENTED_EDGE('',*,*,#24,.T.);
+        #43=ORIENTED_EDGE('',*,*,#28,.F.);
+        #44=EDGE_LOOP('',(#40,#41,#42,#43));
+        #45=FACE_OUTER_BOUND('',#44,.T.);
+        #46=ADVANCED_FACE('',(#45),#39,.T.);
+        #47=CYLINDRICAL_SURFACE('',#16,0.5);
+        #48=ORIENTED_EDGE('',*,*,#23,.F.);
+        #49=ORIENTED_EDGE('',*,*,#30,.T.);
+        #50=ORIENTED_EDGE('',*,*,#25,.T.);
+        #51=ORIENTED_EDGE('',*,*,#30,.F.);
+        #52=EDGE_LOOP('',(#48,#49,#50,#51));
+        #53=FACE_OUTER_BOUND('',#52,.T.);
+        #54=ADVANCED_FACE('',(#53),#47,.F.);
+        #55=PLANE('',#17);
+        #56=ORIENTED_EDGE('',*,*,#24,.F.);
+        #57=EDGE_LOOP('',(#56));
+        #58=FACE_OUTER_BOUND('',#57,.T.);
+        #59=ORIENTED_EDGE('',*,*,#25,.F.);
+        #60=EDGE_LOOP('',(#59));
+        #61=FACE_BOUND('',#60,.T.);
+        #62=ADVANCED_FACE('',(#58,#61),#55,.T.);
+        #63=CLOSED_SHELL('',(#38,#46,#54,#62));
+        #64=MANIFOLD_SOLID_BREP('tube',#63);
+        #65=(LENGTH_UNIT()NAMED_UNIT(*)SI_UNIT(.MILLI.,.METRE.));
+        #66=LENGTH_MEASURE_WITH_UNIT(LENGTH_MEASURE(25.4),#65);
+        #67=DIMENSIONAL_EXPONENTS(1.,0.,0.,0.,0.,0.,0.);
+        #68=(CONVERSION_BASED_UNIT('INCH',#66)LENGTH_UNIT()NAMED_UNIT(#67));
+        #69=(GEOMETRIC_REPRESENTATION_CONTEXT(3)
+            GLOBAL_UNIT_ASSIGNED_CONTEXT((#68))
+            REPRESENTATION_CONTEXT('',''));
+        #70=ADVANCED_BREP_SHAPE_REPRESENTATION('tube',(#64),#69);
+    ";
+
+    #[test]
+    fn import_box() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let solid = import_step(&step(BOX), &mut core)?;
+
+        assert_eq!(solid.shells().len(), 1);
+        assert_eq!(solid.shells().first().faces().len(), 6);
+        assert_volume(&solid, 10. * 20. * 30.);
+
+        Ok(())
+    }
+
+    #[test]
+    fn import_tube() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let solid = import_step(&step(TUBE), &mut core)?;
+
+        assert_eq!(solid.shells().len(), 1);
+        assert_eq!(solid.shells().first().faces().len(), 4);
+
+        let inch = 25.4_f64;
+        assert_volume(&solid, PI * (1. - 0.25) * inch.powi(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn import_tilted_tube() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        // The same tube, with its axis tilted by 45 degrees around the x-axis.
+        // Circles around such axes can't be computed exactly, without taking
+        // care.
+        let tilted = TUBE
+            .replace("#2=CARTESIAN_POINT('',(0.,0.,1.))", &top(2, 0.))
+            .replace("#4=CARTESIAN_POINT('',(1.,0.,1.))", &top(4, 1.))
+            .replace("#6=CARTESIAN_POINT('',(0.5,0.,1.))", &top(6, 0.5))
+            .replace("DIRECTION('',(0.,0.,1.))", "DIRECTION('',(0.,1.,1.))")
+            .replace("DIRECTION('',(0.,0.,-1.))", "DIRECTION('',(0.,-1.,-1.))");
+        let solid = import_step(&step(&tilted), &mut core)?;
+
+        assert_eq!(solid.shells().first().faces().len(), 4);
+
+        let inch = 25.4_f64;
+        assert_volume(&solid, PI * (1. - 0.25) * inch.powi(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_file() {
+        let mut core = Instance::new();
+
+        for step in [
+            "ISO-10303-21;\nDATA;\n#1=CARTESIAN_POINT('',(0.,0.,0.)\n",
+            "DATA;\nENDSEC;\nEND-ISO-10303-21;\n",
+            &step("#1=CARTESIAN_POINT('',(0.,0.,0.));"),
+            &step("#1=MANIFOLD_SOLID_BREP('',#2);"),
+            &step(&BOX.replace(
+                "#17=DIRECTION('',(1.,0.,0.))",
+                "#17=DIRECTION('',(0.,0.,0.))",
+            )),
+            &step(&TUBE.replace("CIRCLE('',#14,1.)", "CIRCLE('',#14,0.)")),
+            &step(&TUBE.replace(
+                "CYLINDRICAL_SURFACE('',#16,1.)",
+                "CYLINDRICAL_SURFACE('',#16,-1.)",
+            )),
+        ] {
+            let result = import_step(step, &mut core);
+            assert!(matches!(result, Err(Error::Step(_))), "{step}");
+        }
+    }
+
+    #[test]
+    fn unsupported_geometry() {
+        let mut core = Instance::new();
+
+        for data in [
+            BOX.replace(
+                "#51=PLANE('',#50)",
+                "#51=CONICAL_SURFACE('',#50,1.,1.)",
+            ),
+            BOX.replace("#105=MANIFOLD_SOLID_BREP", "#105=BREP_WITH_VOIDS"),
+            BOX.replace("SI_UNIT(.MILLI.,.METRE.)", "SI_UNIT(.PICO.,.METRE.)"),
+        ] {
+            let result = import_step(&step(&data), &mut core);
+            assert!(matches!(result, Err(Error::UnsupportedStep(_))));
+        }
+    }
+
+    #[test]
+    fn invalid_geometry() {
+        let mut core = Instance::new();
+
+        // The top of the box is moved, without updating the planes of the
+        // faces that border it.
+        let step = step(&BOX.replace(
+            "#7=CARTESIAN_POINT('',(10.,20.,30.))",
+            "#7=CARTESIAN_POINT('',(10.,20.,31.))",
+        ));
+        let result = import_step(&step, &mut core);
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    /// A point at the top of the tilted tube, with the provided x coordinate
+    fn top(id: u64, x: f64) -> String {
+        let height = std::f64::consts::FRAC_1_SQRT_2;
+        format!("#{id}=CARTESIAN_POINT('',({x:?},{height:?},{height:?}))")
+    }
+
+    /// Create a STEP file, with the provided data section
+    fn step(data: &str) -> String {
+        format!(
+            "ISO-10303-21;\n\
+            HEADER;\n\
+            FILE_DESCRIPTION((''),'2;1');\n\
+            FILE_NAME('','',(''),(''),'','','');\n\
+            FILE_SCHEMA(('AUTOMOTIVE_DESIGN'));\n\
+            ENDSEC;\n\
+            DATA;\n\
+            {data}\n\
+            ENDSEC;\n\
+            END-ISO-10303-21;\n"
+        )
+    }
+
+    fn assert_volume(solid: &Solid, expected: f64) {
+        let volume = solid.mass_properties().volume;
+        assert!(
+            (volume - Scalar::from(expected)).abs() < Scalar::from(1e-6),
+            "Unexpected volume: {volume} (expected {expected})"
+        );
+    }
+}
@@ -0,0 +1,443 @@
+//! A parser for the exchange structure of STEP files, as defined in ISO 10303-21
+
+use std::{collections::BTreeMap, iter::Peekable, str::CharIndices};
+
+use crate::Error;
+
+/// The instances in the `DATA` section of a STEP file, by their id
+pub type Instances = BTreeMap<u64, Instance>;
+
+/// An entity instance
+///
+/// Simple instances consist of a single record. Complex instances combine
+/// multiple records, one for each of the entity types they are made of.
+#[derive(Clone, Debug)]
+pub struct Instance {
+    pub records: Vec<Record>,
+}
+
+impl Instance {
+    /// Access the record of the provided entity type
+    pub fn record(&self, name: &str) -> Option<&Record> {
+        self.records.iter().find(|record| record.name == name)
+    }
+}
+
+/// A record of an entity instance, its type and parameters
+#[derive(Clone, Debug)]
+pub struct Record {
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+}
+
+impl Record {
+    /// Access the parameter at the provided index
+    pub fn parameter(&self, index: usize) -> Result<&Parameter, Error> {
+        self.parameters.get(index).ok_or_else(|| {
+            invalid(format!("`{}` is missing parameter {index}", self.name))
+        })
+    }
+
+    /// Access a parameter that refers to another instance
+    pub fn reference(&self, index: usize) -> Result<u64, Error> {
+        match self.parameter(index)? {
+            Parameter::Reference(id) => Ok(*id),
+            _ => Err(self.unexpected(index, "reference")),
+        }
+    }
+
+    /// Access a parameter that is a list of references
+    pub fn references(&self, index: usize) -> Result<Vec<u64>, Error> {
+        self.list(index)?
+            .iter()
+            .map(|parameter| match parameter {
+                Parameter::Reference(id) => Ok(*id),
+                _ => Err(self.unexpected(index, "list of references")),
+            })
+            .collect()
+    }
+
+    /// Access a parameter that is a number
+    pub fn number(&self, index: usize) -> Result<f64, Error> {
+        match self.parameter(index)? {
+            Parameter::Number(number) => Ok(*number),
+            _ => Err(self.unexpected(index, "number")),
+        }
+    }
+
+    /// Access a parameter that is a list of a fixed amount of numbers
+    pub fn numbers<const N: usize>(
+        &self,
+        index: usize,
+    ) -> Result<[f64; N], Error> {
+        let mut numbers = [0.; N];
+        let list = self.list(index)?;
+
+        if list.len() != N {
+            return Err(self.unexpected(index, &format!("{N} numbers")));
+        }
+        for (number, parameter) in numbers.iter_mut().zip(list) {
+            let Parameter::Number(value) = parameter else {
+                return Err(self.unexpected(index, &format!("{N} numbers")));
+            };
+            *number = *value;
+        }
+
+        Ok(numbers)
+    }
+
+    /// Access a parameter that is a list
+    pub fn list(&self, index: usize) -> Result<&[Parameter], Error> {
+        match self.parameter(index)? {
+            Parameter::List(list) => Ok(list),
+            _ => Err(self.unexpected(index, "list")),
+        }
+    }
+
+    /// Access a parameter that is a logical value, `.T.` or `.F.`
+    pub fn logical(&self, index: usize) -> Result<bool, Error> {
+        match self.parameter(index)? {
+            Parameter::Enumeration(value) if value == "T" => Ok(true),
+            Parameter::Enumeration(value) if value == "F" => Ok(false),
+            _ => Err(self.unexpected(index, "logical value")),
+        }
+    }
+
+    fn unexpected(&self, index: usize, expected: &str) -> Error {
+        invalid(format!(
+            "expected parameter {index} of `{}` to be {expected}",
+            self.name
+        ))
+    }
+}
+
+/// A parameter of a record
+#[derive(Clone, Debug)]
+pub enum Parameter {
+    Reference(u64),
+    Number(f64),
+    String(String),
+    Enumeration(String),
+    List(Vec<Parameter>),
+
+    /// A value with an explicit type, like `LENGTH_MEASURE(1.0)`
+    Typed(Record),
+
+    /// An unset value, written as `$`
+    Unset,
+
+    /// A value that is derived from other values, written as `*`
+    Derived,
+}
+
+/// Parse the instances in the `DATA` sections of a STEP file
+pub fn parse(step: &str) -> Result<Instances, Error> {
+    let mut tokens = Tokens::new(step).peekable();
+    let mut instances = BTreeMap::new();
+
+    if next(&mut tokens)? != Token::Keyword("ISO-10303-21".to_string()) {
+        return Err(invalid("file doesn't start with `ISO-10303-21`"));
+    }
+    expect(&mut tokens, Token::Semicolon)?;
+
+    // Skip over everything up to the data section. Its only content that is
+    // relevant here are the instances in the data section.
+    loop {
+        match next(&mut tokens)? {
+            Token::Keyword(keyword) if keyword == "DATA" => {
+                // The data section can have parameters, in newer versions of
+                // the format.
+                while next(&mut tokens)? != Token::Semicolon {}
+                break;
+            }
+            Token::Keyword(keyword) if keyword == "END-ISO-10303-21" => {
+                return Ok(instances);
+            }
+            _ => {}
+        }
+    }
+
+    loop {
+        let id = match next(&mut tokens)? {
+            Token::Reference(id) => id,
+            Token::Keyword(keyword) if keyword == "ENDSEC" => {
+                expect(&mut tokens, Token::Semicolon)?;
+                break;
+            }
+            token => {
+                return Err(invalid(format!(
+                    "expected instance, found {token:?}"
+                )));
+            }
+        };
+        expect(&mut tokens, Token::Equals)?;
+
+        let records = match next(&mut tokens)? {
+            Token::Keyword(name) => vec![record(name, &mut tokens)?],
+            Token::OpenParen => {
+                let mut records = Vec::new();
+                loop {
+                    match next(&mut tokens)? {
+                        Token::Keyword(name) => {
+                            records.push(record(name, &mut tokens)?);
+                        }
+                        Token::CloseParen => break,
+                        token => {
+                            return Err(invalid(format!(
+                                "expected record, found {token:?}"
+                            )));
+                        }
+                    }
+                }
+                records
+            }
+            token => {
+                return Err(invalid(format!(
+                    "expected record, found {token:?}"
+                )));
+            }
+        };
+        expect(&mut tokens, Token::Semicolon)?;
+
+        instances.insert(id, Instance { records });
+    }
+
+    Ok(instances)
+}
+
+fn record(
+    name: String,
+    tokens: &mut Peekable<Tokens>,
+) -> Result<Record, Error> {
+    expect(tokens, Token::OpenParen)?;
+    let parameters = parameters(tokens)?;
+    Ok(Record { name, parameters })
+}
+
+/// Parse parameters up to and including the closing parenthesis
+fn parameters(tokens: &mut Peekable<Tokens>) -> Result<Vec<Parameter>, Error> {
+    let mut values = Vec::new();
+
+    if let Some(Ok(Token::CloseParen)) = tokens.peek() {
+        tokens.next();
+        return Ok(values);
+    }
+
+    loop {
+        let parameter = match next(tokens)? {
+            Token::Reference(id) => Parameter::Reference(id),
+            Token::Number(number) => Parameter::Number(number),
+            Token::String(string) => Parameter::String(string),
+            Token::Enumeration(value) => Parameter::Enumeration(value),
+            Token::Keyword(name) => Parameter::Typed(record(name, tokens)?),
+            Token::OpenParen => Parameter::List(parameters(tokens)?),
+            Token::Dollar => Parameter::Unset,
+            Token::Asterisk => Parameter::Derived,
+            token => {
+                return Err(invalid(format!(
+                    "expected parameter, found {token:?}"
+                )));
+            }
+        };
+        values.push(parameter);
+
+        match next(tokens)? {
+            Token::Comma => continue,
+            Token::CloseParen => break,
+            token => {
+                return Err(invalid(format!(
+                    "expected `,` or `)`, found {token:?}"
+                )));
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+fn next(tokens: &mut Peekable<Tokens>) -> Result<Token, Error> {
+    tokens
+        .next()
+        .unwrap_or_else(|| Err(invalid("unexpected end of file")))
+}
+
+fn expect(tokens: &mut Peekable<Tokens>, expected: Token) -> Result<(), Error> {
+    let token = next(tokens)?;
+    if token != expected {
+        return Err(invalid(format!("expected {expected:?}, found {token:?}")));
+    }
+
+    Ok(())
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Step(message.into())
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Keyword(String),
+    Reference(u64),
+    Number(f64),
+    String(String),
+    Enumeration(String),
+    OpenParen,
+    CloseParen,
+    Comma,
+    Semicolon,
+    Equals,
+    Dollar,
+    Asterisk,
+}
+
+struct Tokens<'r> {
+    source: &'r str,
+    chars: Peekable<CharIndices<'r>>,
+}
+
+impl<'r> Tokens<'r> {
+    fn new(source: &'r str) -> Self {
+        Self {
+            source,
+            chars: source.char_indices().peekable(),
+        }
+    }
+
+    /// Consume characters while they match
+    ///
+    /// Returns the consumed characters, preceded by any that were consumed
+    /// since `start`.
+    fn take_while(
+        &mut self,
+        start: usize,
+        f: impl Fn(char) -> bool,
+    ) -> &'r str {
+        let mut end = self
+            .chars
+            .peek()
+            .map(|&(i, _)| i)
+            .unwrap_or(self.source.len());
+        while let Some(&(i, c)) = self.chars.peek() {
+            if !f(c) {
+                break;
+            }
+            end = i + c.len_utf8();
+            self.chars.next();
+        }
+
+        &self.source[start..end]
+    }
+
+    fn string(&mut self) -> Result<Token, Error> {
+        let mut string = String::new();
+
+        loop {
+            match self.chars.next() {
+                // Apostrophes within strings are escaped by doubling them.
+                Some((_, '\'')) => match self.chars.peek() {
+                    Some((_, '\'')) => {
+                        self.chars.next();
+                        string.push('\'');
+                    }
+                    _ => break,
+                },
+                Some((_, c)) => string.push(c),
+                None => return Err(invalid("unterminated string")),
+            }
+        }
+
+        Ok(Token::String(string))
+    }
+
+    fn skip_comment(&mut self) -> Result<(), Error> {
+        let mut previous = None;
+
+        for (_, c) in self.chars.by_ref() {
+            if previous == Some('*') && c == '/' {
+                return Ok(());
+            }
+            previous = Some(c);
+        }
+
+        Err(invalid("unterminated comment"))
+    }
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Result<Token, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (start, c) = self.chars.next()?;
+
+            let token = match c {
+                c if c.is_whitespace() => continue,
+                '/' if matches!(self.chars.peek(), Some((_, '*'))) => {
+                    self.chars.next();
+                    if let Err(err) = self.skip_comment() {
+                        return Some(Err(err));
+                    }
+                    continue;
+                }
+                '(' => Token::OpenParen,
+                ')' => Token::CloseParen,
+                ',' => Token::Comma,
+                ';' => Token::Semicolon,
+                '=' => Token::Equals,
+                '$' => Token::Dollar,
+                '*' => Token::Asterisk,
+                '\'' => return Some(self.string()),
+                '#' => {
+                    let id = self.take_while(start + 1, |c| c.is_ascii_digit());
+                    match id.parse() {
+                        Ok(id) => Token::Reference(id),
+                        Err(_) => {
+                            return Some(Err(invalid("invalid reference")));
+                        }
+                    }
+                }
+                '.' if matches!(
+                    self.chars.peek(),
+                    Some((_, c)) if c.is_ascii_alphabetic() || *c == '_'
+                ) =>
+                {
+                    let value = self.take_while(start + 1, |c| {
+                        c.is_ascii_alphanumeric() || c == '_'
+                    });
+                    let value = value.to_string();
+                    if self.chars.next().map(|(_, c)| c) != Some('.') {
+                        return Some(Err(invalid("unterminated enumeration")));
+                    }
+                    Token::Enumeration(value)
+                }
+                c if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => {
+                    let number = self.take_while(start, |c| {
+                        c.is_ascii_digit()
+                            || matches!(c, '-' | '+' | '.' | 'E' | 'e')
+                    });
+                    match number.parse() {
+                        Ok(number) => Token::Number(number),
+                        Err(_) => {
+                            return Some(Err(invalid(format!(
+                                "invalid number `{number}`"
+                            ))));
+                        }
+                    }
+                }
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    let keyword = self.take_while(start, |c| {
+                        c.is_ascii_alphanumeric() || matches!(c, '_' | '-')
+                    });
+                    Token::Keyword(keyword.to_uppercase())
+                }
+                c => {
+                    return Some(Err(invalid(format!(
+                        "unexpected character `{c}`"
+                    ))));
+                }
+            };
+
+            return Some(Ok(token));
+        }
+    }
+}
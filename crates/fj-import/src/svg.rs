@@ -0,0 +1,594 @@
+//! # SVG import
+//!
+//! See [`import_svg`].
+
+use std::{f64::consts::PI, str::FromStr};
+
+use fj_core::{algorithms::approx::Tolerance, objects::Sketch, Instance};
+use fj_math::{Point, Scalar};
+use svgtypes::{Length, PathParser, PathSegment, PointsParser, Transform};
+
+use crate::{
+    contour::{build_sketch, Contour},
+    Error,
+};
+
+/// Import the shapes of an SVG document into a [`Sketch`]
+///
+/// Supports `<path>`, `<rect>`, `<circle>`, `<ellipse>`, `<polygon>`, and
+/// `<polyline>` elements, and the `transform` attribute on those and on the
+/// elements containing them. Every subpath becomes a closed contour, regardless
+/// of whether it is closed in the document, matching how SVG fills shapes.
+/// Contours that are nested within others become holes.
+///
+/// Lines become lines. Circular arcs, including circles, become arcs, unless a
+/// transform distorts them into ellipses. Elliptical arcs and Bézier curves
+/// are approximated by lines, that deviate from the original curve by no more
+/// than `tolerance`.
+///
+/// Coordinates are taken as they are, in user units, ignoring any `viewBox`.
+/// The y-axis is flipped, as it points down in SVG, so the sketch looks like
+/// the document does in a viewer. The resulting sketch can be placed on any
+/// surface, by sweeping it or by building faces from its regions.
+///
+/// Shapes must not overlap, as they are not combined with each other.
+pub fn import_svg(
+    svg: &str,
+    tolerance: impl Into<Tolerance>,
+    core: &mut Instance,
+) -> Result<Sketch, Error> {
+    let tolerance = tolerance.into().inner();
+    let document = roxmltree::Document::parse(svg)?;
+
+    let mut importer = Importer {
+        tolerance: tolerance.into_f64(),
+        transform: Transform::new(1., 0., 0., -1., 0., 0.),
+        contours: Vec::new(),
+        current: None,
+    };
+    importer.visit(document.root_element())?;
+
+    Ok(build_sketch(importer.contours, tolerance, core))
+}
+
+struct Importer {
+    tolerance: f64,
+
+    /// The transform from the current element's coordinates to the sketch's
+    transform: Transform,
+
+    contours: Vec<Contour>,
+    current: Option<Contour>,
+}
+
+impl Importer {
+    fn visit(&mut self, node: roxmltree::Node) -> Result<(), Error> {
+        let name = node.tag_name().name();
+        if matches!(
+            name,
+            "defs" | "clipPath" | "mask" | "marker" | "pattern" | "symbol"
+        ) {
+            return Ok(());
+        }
+
+        let parent_transform = self.transform;
+        if let Some(transform) = node.attribute("transform") {
+            self.transform =
+                multiply(&self.transform, &Transform::from_str(transform)?);
+        }
+
+        match name {
+            "path" => {
+                self.path(node.attribute("d").unwrap_or_default())?;
+            }
+            "rect" => {
+                let [x, y, width, height] = ["x", "y", "width", "height"]
+                    .map(|name| number(node, name));
+                let [x, y, width, height] = [x?, y?, width?, height?];
+
+                if width > 0. && height > 0. {
+                    self.move_to([x, y]);
+                    self.line_to([x + width, y]);
+                    self.line_to([x + width, y + height]);
+                    self.line_to([x, y + height]);
+                    self.close();
+                }
+            }
+            "circle" | "ellipse" => {
+                let [cx, cy] = ["cx", "cy"].map(|name| number(node, name));
+                let [rx, ry] = if name == "circle" {
+                    let r = number(node, "r")?;
+                    [r, r]
+                } else {
+                    [number(node, "rx")?, number(node, "ry")?]
+                };
+                let [cx, cy] = [cx?, cy?];
+
+                if rx > 0. && ry > 0. {
+                    let [right, left] = [[cx + rx, cy], [cx - rx, cy]];
+
+                    self.move_to(right);
+                    self.elliptical_arc(right, [rx, ry], 0., false, true, left);
+                    self.elliptical_arc(left, [rx, ry], 0., false, true, right);
+                    self.close();
+                }
+            }
+            "polygon" | "polyline" => {
+                let points = node.attribute("points").unwrap_or_default();
+                for (i, (x, y)) in PointsParser::from(points).enumerate() {
+                    if i == 0 {
+                        self.move_to([x, y]);
+                    } else {
+                        self.line_to([x, y]);
+                    }
+                }
+                self.close();
+            }
+            _ => {}
+        }
+
+        for child in node.children().filter(|node| node.is_element()) {
+            self.visit(child)?;
+        }
+
+        self.transform = parent_transform;
+        Ok(())
+    }
+
+    fn path(&mut self, data: &str) -> Result<(), Error> {
+        let mut current = [0., 0.];
+        let mut start = [0., 0.];
+
+        // The second control point of the previous segment, if it was a Bézier
+        // curve. Used to compute the first control point of smooth curves.
+        let mut previous_cubic: Option<[f64; 2]> = None;
+        let mut previous_quadratic: Option<[f64; 2]> = None;
+
+        for segment in PathParser::from(data) {
+            let segment = segment?;
+
+            let relative = |abs: bool, [x, y]: [f64; 2]| {
+                if abs {
+                    [x, y]
+                } else {
+                    [current[0] + x, current[1] + y]
+                }
+            };
+            let reflect = |control: Option<[f64; 2]>| match control {
+                Some([x, y]) => [2. * current[0] - x, 2. * current[1] - y],
+                None => current,
+            };
+
+            let (mut cubic, mut quadratic) = (None, None);
+
+            let end = match segment {
+                PathSegment::MoveTo { abs, x, y } => {
+                    let end = relative(abs, [x, y]);
+                    self.move_to(end);
+                    start = end;
+                    end
+                }
+                PathSegment::LineTo { abs, x, y } => {
+                    let end = relative(abs, [x, y]);
+                    self.line_to(end);
+                    end
+                }
+                PathSegment::HorizontalLineTo { abs, x } => {
+                    let end =
+                        [if abs { x } else { current[0] + x }, current[1]];
+                    self.line_to(end);
+                    end
+                }
+                PathSegment::VerticalLineTo { abs, y } => {
+                    let end =
+                        [current[0], if abs { y } else { current[1] + y }];
+                    self.line_to(end);
+                    end
+                }
+                PathSegment::CurveTo {
+                    abs,
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => {
+                    let [c1, c2, end] =
+                        [[x1, y1], [x2, y2], [x, y]].map(|p| relative(abs, p));
+                    self.cubic([current, c1, c2, end]);
+                    cubic = Some(c2);
+                    end
+                }
+                PathSegment::SmoothCurveTo { abs, x2, y2, x, y } => {
+                    let c1 = reflect(previous_cubic);
+                    let [c2, end] =
+                        [[x2, y2], [x, y]].map(|p| relative(abs, p));
+                    self.cubic([current, c1, c2, end]);
+                    cubic = Some(c2);
+                    end
+                }
+                PathSegment::Quadratic { abs, x1, y1, x, y } => {
+                    let [c, end] = [[x1, y1], [x, y]].map(|p| relative(abs, p));
+                    self.quadratic([current, c, end]);
+                    quadratic = Some(c);
+                    end
+                }
+                PathSegment::SmoothQuadratic { abs, x, y } => {
+                    let c = reflect(previous_quadratic);
+                    let end = relative(abs, [x, y]);
+                    self.quadratic([current, c, end]);
+                    quadratic = Some(c);
+                    end
+                }
+                PathSegment::EllipticalArc {
+                    abs,
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    x,
+                    y,
+                } => {
+                    let end = relative(abs, [x, y]);
+                    self.elliptical_arc(
+                        current,
+                        [rx, ry],
+                        x_axis_rotation,
+                        large_arc,
+                        sweep,
+                        end,
+                    );
+                    end
+                }
+                PathSegment::ClosePath { .. } => {
+                    self.close();
+                    start
+                }
+            };
+
+            current = end;
+            previous_cubic = cubic;
+            previous_quadratic = quadratic;
+        }
+
+        self.close();
+        Ok(())
+    }
+
+    fn move_to(&mut self, point: [f64; 2]) {
+        self.close();
+        self.current = Some(Contour::new(self.point(point)));
+    }
+
+    fn line_to(&mut self, point: [f64; 2]) {
+        let point = self.point(point);
+        let tolerance = Scalar::from_f64(self.tolerance);
+
+        // Drawing commands without a preceding move start where the previous
+        // subpath started.
+        let contour = self.current.get_or_insert_with(|| {
+            let start = self
+                .contours
+                .last()
+                .map(|contour| contour.start())
+                .unwrap_or(point);
+            Contour::new(start)
+        });
+        contour.line_to(point, tolerance);
+    }
+
+    fn close(&mut self) {
+        if let Some(contour) = self.current.take() {
+            self.contours.push(contour);
+        }
+    }
+
+    fn cubic(&mut self, [p0, p1, p2, p3]: [[f64; 2]; 4]) {
+        // The second derivative of the curve is bounded by six times the
+        // largest second difference of its control points.
+        let second_derivative = norm(second_difference(p0, p1, p2))
+            .max(norm(second_difference(p1, p2, p3)))
+            * 6.;
+
+        self.flatten(second_derivative, |t| {
+            let s = 1. - t;
+            let weights =
+                [s * s * s, 3. * s * s * t, 3. * s * t * t, t * t * t];
+            combine([p0, p1, p2, p3], weights)
+        });
+    }
+
+    fn quadratic(&mut self, [p0, p1, p2]: [[f64; 2]; 3]) {
+        let second_derivative = norm(second_difference(p0, p1, p2)) * 2.;
+
+        self.flatten(second_derivative, |t| {
+            let s = 1. - t;
+            combine([p0, p1, p2], [s * s, 2. * s * t, t * t])
+        });
+    }
+
+    /// Approximate a curve with lines
+    ///
+    /// Expects an upper bound for the magnitude of the curve's second
+    /// derivative, in the document's coordinates. The distance between a curve
+    /// and a line approximating it is at most an eighth of that, times the
+    /// square of the line's length in curve parameters.
+    fn flatten(
+        &mut self,
+        second_derivative: f64,
+        curve: impl Fn(f64) -> [f64; 2],
+    ) {
+        let second_derivative = second_derivative * self.scale();
+        let num_lines = (second_derivative / self.tolerance / 8.)
+            .sqrt()
+            .ceil()
+            .max(1.) as u64;
+
+        for i in 1..=num_lines {
+            self.line_to(curve(i as f64 / num_lines as f64));
+        }
+    }
+
+    /// Add an elliptical arc, as defined by an SVG path segment
+    ///
+    /// The conversion from endpoint to center parameterization follows
+    /// appendix B.2.4 of the SVG 2 specification.
+    fn elliptical_arc(
+        &mut self,
+        start: [f64; 2],
+        [rx, ry]: [f64; 2],
+        x_axis_rotation: f64,
+        large_arc: bool,
+        sweep: bool,
+        end: [f64; 2],
+    ) {
+        if start == end {
+            return;
+        }
+        let [mut rx, mut ry] = [rx.abs(), ry.abs()];
+        if rx == 0. || ry == 0. {
+            self.line_to(end);
+            return;
+        }
+
+        let (sin, cos) = x_axis_rotation.to_radians().sin_cos();
+        let [dx, dy] = [(start[0] - end[0]) / 2., (start[1] - end[1]) / 2.];
+        let [x1, y1] = [cos * dx + sin * dy, -sin * dx + cos * dy];
+
+        // Scale up radii that are too small to connect the end points.
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1. {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+
+        let numerator =
+            rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let sign = if large_arc == sweep { -1. } else { 1. };
+        let coefficient = sign * (numerator / denominator).max(0.).sqrt();
+
+        let [cx1, cy1] =
+            [coefficient * rx * y1 / ry, -coefficient * ry * x1 / rx];
+        let center = [
+            cos * cx1 - sin * cy1 + (start[0] + end[0]) / 2.,
+            sin * cx1 + cos * cy1 + (start[1] + end[1]) / 2.,
+        ];
+
+        let angle = |[ux, uy]: [f64; 2], [vx, vy]: [f64; 2]| {
+            (ux * vy - uy * vx).atan2(ux * vx + uy * vy)
+        };
+        let start_angle = angle([1., 0.], [(x1 - cx1) / rx, (y1 - cy1) / ry]);
+        let mut sweep_angle = angle(
+            [(x1 - cx1) / rx, (y1 - cy1) / ry],
+            [(-x1 - cx1) / rx, (-y1 - cy1) / ry],
+        );
+        if sweep && sweep_angle < 0. {
+            sweep_angle += 2. * PI;
+        }
+        if !sweep && sweep_angle > 0. {
+            sweep_angle -= 2. * PI;
+        }
+
+        let point = |t: f64| {
+            let (sin_t, cos_t) = t.sin_cos();
+            [
+                center[0] + rx * cos_t * cos - ry * sin_t * sin,
+                center[1] + rx * cos_t * sin + ry * sin_t * cos,
+            ]
+        };
+
+        let is_circle = (rx - ry).abs() <= f64::EPSILON * rx.max(ry) * 8.;
+        if is_circle && self.is_similarity() {
+            // An arc can't span more than half of the circle, if it is to be
+            // defined by a point on it. Split it in the middle.
+            let middle = start_angle + sweep_angle / 2.;
+            let [quarter, three_quarters] = [0.25, 0.75]
+                .map(|fraction| point(start_angle + sweep_angle * fraction));
+
+            self.arc_through(quarter, point(middle));
+            self.arc_through(three_quarters, end);
+            return;
+        }
+
+        // The deviation of a chord from an arc with radius `r`, spanning the
+        // angle `a`, is `r * (1 - cos(a / 2))`.
+        let radius = rx.max(ry) * self.scale();
+        let max_angle = if self.tolerance < radius {
+            2. * (1. - self.tolerance / radius).acos()
+        } else {
+            PI
+        };
+        let num_lines = (sweep_angle.abs() / max_angle).ceil().max(1.) as u64;
+
+        for i in 1..num_lines {
+            let t = start_angle + sweep_angle * i as f64 / num_lines as f64;
+            self.line_to(point(t));
+        }
+        self.line_to(end);
+    }
+
+    fn arc_through(&mut self, through: [f64; 2], end: [f64; 2]) {
+        let [through, end] = [through, end].map(|point| self.point(point));
+        let tolerance = Scalar::from_f64(self.tolerance);
+
+        if let Some(contour) = &mut self.current {
+            contour.arc_through(through, end, tolerance);
+        }
+    }
+
+    /// Convert a point from document coordinates into sketch coordinates
+    fn point(&self, [x, y]: [f64; 2]) -> Point<2> {
+        let Transform { a, b, c, d, e, f } = self.transform;
+        Point::from([a * x + c * y + e, b * x + d * y + f])
+    }
+
+    /// Indicate whether the current transform preserves the shape of circles
+    fn is_similarity(&self) -> bool {
+        let Transform { a, b, c, d, .. } = self.transform;
+        let epsilon = f64::EPSILON * 8. * (a * a + b * b + c * c + d * d);
+
+        (a * a + b * b - c * c - d * d).abs() <= epsilon
+            && (a * c + b * d).abs() <= epsilon
+    }
+
+    /// The largest factor by which the current transform scales distances
+    fn scale(&self) -> f64 {
+        let Transform { a, b, c, d, .. } = self.transform;
+        (a * a + b * b + c * c + d * d).sqrt()
+    }
+}
+
+fn number(node: roxmltree::Node, name: &str) -> Result<f64, Error> {
+    match node.attribute(name) {
+        Some(value) => Ok(Length::from_str(value)?.number),
+        None => Ok(0.),
+    }
+}
+
+/// Compute `a * b`, which applies `b` first, then `a`
+fn multiply(a: &Transform, b: &Transform) -> Transform {
+    Transform::new(
+        a.a * b.a + a.c * b.b,
+        a.b * b.a + a.d * b.b,
+        a.a * b.c + a.c * b.d,
+        a.b * b.c + a.d * b.d,
+        a.a * b.e + a.c * b.f + a.e,
+        a.b * b.e + a.d * b.f + a.f,
+    )
+}
+
+fn second_difference(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> [f64; 2] {
+    [a[0] - 2. * b[0] + c[0], a[1] - 2. * b[1] + c[1]]
+}
+
+fn norm([x, y]: [f64; 2]) -> f64 {
+    (x * x + y * y).sqrt()
+}
+
+fn combine<const N: usize>(
+    points: [[f64; 2]; N],
+    weights: [f64; N],
+) -> [f64; 2] {
+    let mut result = [0., 0.];
+    for (point, weight) in points.iter().zip(weights) {
+        result[0] += point[0] * weight;
+        result[1] += point[1] * weight;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use fj_core::Instance;
+
+    use crate::{
+        tests::{assert_area, num_arcs, validate},
+        Error,
+    };
+
+    use super::import_svg;
+
+    #[test]
+    fn path_with_arcs_and_hole() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        // A square, using relative commands, with a circular hole made from two
+        // relative arcs.
+        let svg = r#"
+            <svg xmlns="http://www.w3.org/2000/svg">
+                <path d="
+                    M 0 0 h 10 v 10 h -10 z
+                    M 5 2 a 3 3 0 0 1 0 6 a 3 3 0 0 1 0 -6 z
+                "/>
+            </svg>
+        "#;
+        let sketch = import_svg(svg, 0.001, &mut core)?;
+
+        assert_eq!(sketch.regions().len(), 1);
+        let region = sketch.regions().first();
+        assert_eq!(region.interiors().len(), 1);
+        assert_eq!(num_arcs(region), 4);
+        assert_area(region, 100. - 9. * PI);
+
+        validate(&sketch, &mut core)
+    }
+
+    #[test]
+    fn transformed_shapes() -> anyhow::Result<()> {
+        let mut core = Instance::new();
+
+        let svg = r#"
+            <svg xmlns="http://www.w3.org/2000/svg">
+                <g transform="translate(20 0)">
+                    <rect x="0" y="0" width="4" height="2"/>
+                    <circle cx="10" cy="0" r="1" transform="scale(2)"/>
+                </g>
+            </svg>
+        "#;
+        let sketch = import_svg(svg, 0.001, &mut core)?;
+
+        assert_eq!(sketch.regions().len(), 2);
+        for region in sketch.regions() {
+            assert!(region.interiors().is_empty());
+        }
+        let num_arcs = sketch
+            .regions()
+            .iter()
+            .map(|region| num_arcs(region))
+            .sum::<usize>();
+        assert_eq!(num_arcs, 4);
+
+        validate(&sketch, &mut core)
+    }
+
+    #[test]
+    fn invalid_xml() {
+        let mut core = Instance::new();
+
+        let result = import_svg("<svg><path></svg>", 0.001, &mut core);
+        assert!(matches!(result, Err(Error::Xml(_))));
+    }
+
+    #[test]
+    fn invalid_path_data() {
+        let mut core = Instance::new();
+
+        let svg = r#"<svg><path d="M 0 0 L 1 x"/></svg>"#;
+        let result = import_svg(svg, 0.001, &mut core);
+        assert!(matches!(result, Err(Error::Svg(_))));
+    }
+
+    #[test]
+    fn invalid_attribute() {
+        let mut core = Instance::new();
+
+        let svg = r#"<svg><rect width="wide" height="1"/></svg>"#;
+        let result = import_svg(svg, 0.001, &mut core);
+        assert!(matches!(result, Err(Error::Svg(_))));
+    }
+}
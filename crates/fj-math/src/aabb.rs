@@ -1,7 +1,7 @@
 use parry2d_f64::bounding_volume::BoundingVolume as _;
 use parry3d_f64::bounding_volume::BoundingVolume as _;
 
-use super::{Point, Vector};
+use super::{Point, Scalar, Vector};
 
 /// An axis-aligned bounding box (AABB)
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -45,6 +45,26 @@ impl<const D: usize> Aabb<D> {
 
         true
     }
+
+    /// Compute the distance between this AABB and another one
+    ///
+    /// Returns zero, if the AABBs overlap or touch. This is a lower bound for
+    /// the distance between anything contained in the AABBs.
+    pub fn distance_to_aabb(&self, other: &Self) -> Scalar {
+        let mut gap = Vector::<D>::default();
+
+        for (i, gap) in gap.components.iter_mut().enumerate() {
+            let [self_min, self_max, other_min, other_max] =
+                [self.min, self.max, other.min, other.max]
+                    .map(|point| point.coords.components[i]);
+
+            *gap = (self_min - other_max)
+                .max(other_min - self_max)
+                .max(Scalar::ZERO);
+        }
+
+        gap.magnitude()
+    }
 }
 
 impl Aabb<2> {
@@ -156,6 +176,8 @@ impl From<parry3d_f64::bounding_volume::Aabb> for Aabb<3> {
 
 #[cfg(test)]
 mod tests {
+    use crate::Scalar;
+
     use super::Aabb;
 
     #[test]
@@ -174,4 +196,18 @@ mod tests {
         assert!(!aabb.contains([0., 2.]));
         assert!(!aabb.contains([4., 2.]));
     }
+
+    #[test]
+    fn distance_to_aabb() {
+        let aabb = Aabb::<2>::from_points([[1., 1.], [3., 3.]]);
+
+        let overlapping = Aabb::<2>::from_points([[2., 2.], [4., 4.]]);
+        assert_eq!(aabb.distance_to_aabb(&overlapping), Scalar::ZERO);
+
+        let beside = Aabb::<2>::from_points([[4., 1.], [5., 3.]]);
+        assert_eq!(aabb.distance_to_aabb(&beside), Scalar::ONE);
+
+        let diagonal = Aabb::<2>::from_points([[6., 7.], [8., 8.]]);
+        assert_eq!(aabb.distance_to_aabb(&diagonal), Scalar::from(5.));
+    }
 }
@@ -64,16 +64,10 @@ impl Arc {
             let from_center = p0 - center;
             from_center.v.atan2(from_center.u)
         };
-        let end_angle = {
-            let from_center = p1 - center;
-            let offset = if more_than_half_turn {
-                Scalar::TAU
-            } else {
-                Scalar::ZERO
-            };
-
-            from_center.v.atan2(from_center.u) + offset
-        };
+        // Deriving the end angle from the start angle, instead of from `p1`,
+        // makes sure the arc goes in the right direction, regardless of where
+        // `atan2` wraps around.
+        let end_angle = start_angle + angle_rad;
         Self {
             center,
             radius,
@@ -123,6 +117,18 @@ mod tests {
             0_f64.to_radians(),
             270_f64.to_radians(),
         );
+        check_arc_calculation(
+            [0., 0.],
+            1.,
+            90_f64.to_radians(),
+            270_f64.to_radians(),
+        );
+        check_arc_calculation(
+            [2., 1.],
+            1.,
+            170_f64.to_radians(),
+            -90_f64.to_radians(),
+        );
     }
 
     fn check_arc_calculation(
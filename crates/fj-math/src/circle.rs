@@ -123,8 +123,8 @@ impl<const D: usize> Circle<D> {
         &self,
         point: impl Into<Point<D>>,
     ) -> Point<1> {
-        let vector = (point.into() - self.center).to_uv();
-        let atan = Scalar::atan2(vector.v, vector.u);
+        let vector = point.into() - self.center;
+        let atan = Scalar::atan2(vector.dot(&self.b), vector.dot(&self.a));
         let coord = if atan >= Scalar::ZERO {
             atan
         } else {
@@ -154,7 +154,13 @@ impl<const D: usize> Circle<D> {
 
     /// Calculate an AABB for the circle
     pub fn aabb(&self) -> Aabb<D> {
-        let center_to_min_max = Vector::from_component(self.radius());
+        // Along each axis, the circle extends as far from its center as the
+        // combined components of `a` and `b` reach.
+        let mut center_to_min_max = Vector::default();
+        for (i, extent) in center_to_min_max.components.iter_mut().enumerate() {
+            let [a, b] = [self.a, self.b].map(|vector| vector.components[i]);
+            *extent = Scalar::from_f64((a * a + b * b).into_f64().sqrt());
+        }
 
         Aabb {
             min: self.center() - center_to_min_max,
@@ -210,4 +216,31 @@ mod tests {
             Point::from([FRAC_PI_2 * 3.]),
         );
     }
+
+    #[test]
+    fn point_to_circle_coords_respects_circle_orientation() {
+        let circle = Circle::from_center_and_radius([0., 0.], 1.).reverse();
+
+        assert_eq!(
+            circle.point_to_circle_coords([0., -1.]),
+            Point::from([FRAC_PI_2]),
+        );
+        assert_eq!(
+            circle.point_to_circle_coords([0., 1.]),
+            Point::from([FRAC_PI_2 * 3.]),
+        );
+    }
+
+    #[test]
+    fn aabb_of_tilted_circle() {
+        let circle = Circle {
+            center: Point::from([0., 0., 0.]),
+            a: Vector::from([1., 0., 0.]),
+            b: Vector::from([0., 0.6, 0.8]),
+        };
+
+        let aabb = circle.aabb();
+        assert_eq!(aabb.min, Point::from([-1., -0.6, -0.8]));
+        assert_eq!(aabb.max, Point::from([1., 0.6, 0.8]));
+    }
 }
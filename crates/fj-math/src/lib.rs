@@ -36,6 +36,7 @@ mod arc;
 mod circle;
 mod coordinates;
 mod line;
+mod obb;
 mod plane;
 mod point;
 mod poly_chain;
@@ -51,6 +52,7 @@ pub use self::{
     circle::Circle,
     coordinates::{Uv, Xyz, T},
     line::Line,
+    obb::Obb,
     plane::Plane,
     point::Point,
     poly_chain::PolyChain,
@@ -0,0 +1,87 @@
+use super::{Aabb, Point, Scalar, Vector};
+
+/// An oriented bounding box (OBB)
+///
+/// Unlike an [`Aabb`], the box can be rotated in any way. This can make it a
+/// much tighter fit for objects that are not aligned to the coordinate axes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Obb {
+    /// The center of the OBB
+    pub center: Point<3>,
+
+    /// The axes of the OBB
+    ///
+    /// The axes are unit vectors that are perpendicular to each other.
+    pub axes: [Vector<3>; 3],
+
+    /// Half the size of the OBB, along each of its axes
+    pub half_extents: [Scalar; 3],
+}
+
+impl Obb {
+    /// Determine whether the OBB contains a given point
+    pub fn contains(&self, point: impl Into<Point<3>>) -> bool {
+        let offset = point.into() - self.center;
+
+        self.axes
+            .iter()
+            .zip(self.half_extents)
+            .all(|(axis, half_extent)| offset.dot(axis).abs() <= half_extent)
+    }
+
+    /// Access the vertices of the OBB
+    pub fn vertices(&self) -> [Point<3>; 8] {
+        let [a, b, c] = [0, 1, 2]
+            .map(|i| self.axes[i] * self.half_extents[i])
+            .map(|vector| [-vector, vector]);
+
+        let mut vertices = [self.center; 8];
+        for (i, vertex) in vertices.iter_mut().enumerate() {
+            *vertex = self.center + a[i & 1] + b[(i >> 1) & 1] + c[i >> 2];
+        }
+
+        vertices
+    }
+
+    /// Compute the volume of the OBB
+    pub fn volume(&self) -> Scalar {
+        let [a, b, c] = self.half_extents;
+        a * b * c * 8.
+    }
+
+    /// Compute an AABB that contains the OBB
+    pub fn aabb(&self) -> Aabb<3> {
+        Aabb::<3>::from_points(self.vertices())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Point, Scalar, Vector};
+
+    use super::Obb;
+
+    #[test]
+    fn contains() {
+        let diagonal = Scalar::from_f64(0.5_f64.sqrt());
+
+        let obb = Obb {
+            center: Point::from([1., 1., 0.]),
+            axes: [
+                Vector::from([diagonal, diagonal, Scalar::ZERO]),
+                Vector::from([-diagonal, diagonal, Scalar::ZERO]),
+                Vector::from([0., 0., 1.]),
+            ],
+            half_extents: [2., 0.5, 1.].map(Scalar::from_f64),
+        };
+
+        assert!(obb.contains([1., 1., 0.]));
+        assert!(obb.contains([2., 2., 0.5]));
+        assert!(!obb.contains([2., 0., 0.]));
+        assert!(!obb.contains([1., 1., 2.]));
+
+        for vertex in obb.vertices() {
+            assert!(obb.aabb().contains(vertex));
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use parry3d_f64::query::{Ray, RayCast as _};
+use parry3d_f64::{
+    math::Isometry,
+    query::{ClosestPoints, Ray, RayCast as _},
+};
 
 use crate::Vector;
 
@@ -102,6 +105,28 @@ impl Triangle<3> {
             .map(Into::into)
     }
 
+    /// Compute the closest points between this triangle and another one
+    ///
+    /// Returns the closest point on this triangle first, the one on the other
+    /// triangle second. Returns `None`, if the triangles intersect or touch.
+    pub fn closest_points(&self, other: &Self) -> Option<[Point<3>; 2]> {
+        let closest_points = parry3d_f64::query::closest_points(
+            &Isometry::identity(),
+            &self.to_parry(),
+            &Isometry::identity(),
+            &other.to_parry(),
+            f64::MAX,
+        )
+        .expect("Closest points between triangles are supported by Parry");
+
+        match closest_points {
+            ClosestPoints::WithinMargin(a, b) => {
+                Some([a, b].map(Point::from_na))
+            }
+            ClosestPoints::Intersecting | ClosestPoints::Disjoint => None,
+        }
+    }
+
     /// Compute the triangle's normal
     pub fn normal(&self) -> Vector<3> {
         self.to_parry()
@@ -195,4 +220,23 @@ mod tests {
             Triangle::from([[0.0, 0.0, 0.0], [2.0, 1.0, 0.0], [2.0, 0.0, 0.0]]);
         assert_eq!(triangle.normal(), Vector::from([0.0, 0.0, -1.0]));
     }
+
+    #[test]
+    fn closest_points() {
+        let a =
+            Triangle::from([[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+
+        let above =
+            Triangle::from([[0.0, 0.0, 1.0], [1.0, 0.0, 2.0], [0.0, 1.0, 2.0]]);
+        let [p, q] = a.closest_points(&above).unwrap();
+        assert!(p.distance_to(&Point::from([0.0, 0.0, 0.0])) < 1e-12.into());
+        assert!(q.distance_to(&Point::from([0.0, 0.0, 1.0])) < 1e-12.into());
+
+        let crossing = Triangle::from([
+            [0.25, 0.25, -1.0],
+            [0.25, 0.25, 1.0],
+            [2.0, 2.0, 0.0],
+        ]);
+        assert_eq!(a.closest_points(&crossing), None);
+    }
 }
@@ -1,6 +1,7 @@
 use std::{num::ParseFloatError, path::PathBuf, str::FromStr};
 
 use fj_core::algorithms::approx::{InvalidTolerance, Tolerance};
+use fj_export::StlFormat;
 use fj_math::Scalar;
 
 /// Standardized CLI for Fornjot models
@@ -20,6 +21,15 @@ pub struct Args {
     #[arg(short, long, value_name = "PATH")]
     pub export: Option<PathBuf>,
 
+    /// Variant of STL to export: `binary`, `binary-colors`, or `ascii`
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        value_parser = parse_stl_format,
+        default_value = "binary"
+    )]
+    pub stl_format: StlFormat,
+
     /// How much the export can deviate from the original model
     #[arg(short, long, value_parser = parse_tolerance)]
     pub tolerance: Option<Tolerance>,
@@ -47,6 +57,15 @@ fn parse_tolerance(input: &str) -> Result<Tolerance, ArgsError> {
     Ok(tolerance)
 }
 
+fn parse_stl_format(input: &str) -> Result<StlFormat, ArgsError> {
+    match input {
+        "binary" => Ok(StlFormat::Binary),
+        "binary-colors" => Ok(StlFormat::BinaryWithColors),
+        "ascii" => Ok(StlFormat::Ascii),
+        _ => Err(ArgsError::ParseStlFormat(input.to_string())),
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ArgsError {
     #[error("Error parsing tolerance")]
@@ -54,4 +73,7 @@ pub enum ArgsError {
 
     #[error(transparent)]
     InvalidTolerance(#[from] InvalidTolerance),
+
+    #[error("Unknown STL format `{0}`")]
+    ParseStlFormat(String),
 }
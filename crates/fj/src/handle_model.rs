@@ -68,7 +68,10 @@ where
     let mesh = (model, tolerance).triangulate();
 
     if let Some(path) = args.export {
-        crate::export::export(&mesh, &path)?;
+        let options = crate::export::ExportOptions {
+            stl_format: args.stl_format,
+        };
+        crate::export::export_with_options(&mesh, &path, &options)?;
         return Ok(());
     }
 
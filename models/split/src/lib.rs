@@ -3,6 +3,7 @@ use fj::core::{
     operations::{
         split::SplitFace, sweep::SweepFaceOfShell, update::UpdateSolid,
     },
+    queries::Select,
 };
 
 pub fn model(
@@ -15,7 +16,7 @@ pub fn model(
     cuboid.update_shell(
         cuboid.shells().only(),
         |shell, core| {
-            let face = shell.faces().first();
+            let face = shell.select_faces().with_normal([0., 0., -1.]).only();
             let cycle = face.region().exterior();
 
             let line = [
@@ -23,7 +24,7 @@ pub fn model(
                 (cycle.half_edges().nth(2).unwrap(), [split_pos]),
             ];
 
-            let (shell, [face, _]) = shell.split_face(face, line, core);
+            let (shell, [face, _]) = shell.split_face(&face, line, core);
 
             [shell.sweep_face_of_shell(face, [0., 0., -size / 2.], core)]
         },